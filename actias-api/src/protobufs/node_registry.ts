@@ -19,12 +19,14 @@ export namespace node_registry {
             ...rest: any[]
         ): Observable<NodeRegistration>;
         // Periodic liveness and load report. NOT_FOUND means the node has
-    // already aged out and must register again.
+    // already aged out and must register again. The reply is how the
+    // rebalancer speaks: an overloaded node is asked to hand some of its
+    // hottest objects off, rate-limited per node.
         heartbeat(
             data: HeartbeatRequest,
             metadata?: Metadata,
             ...rest: any[]
-        ): Observable<google.protobuf.Empty>;
+        ): Observable<HeartbeatResponse>;
         // Every node considered alive right now; the aged-out are gone.
         listNodes(
             data: google.protobuf.Empty,
@@ -182,6 +184,10 @@ export namespace node_registry {
     export interface ReleaseLeaseRequest {
         objectId?: string;
         nodeId?: string;
+        // Set by a rebalancing handoff: for the cooldown, a claim by the
+    // releasing node lands on its coolest live peer instead, so the next
+    // local call cannot pull the object straight back.
+        handoff?: boolean;
     }
     export interface GetLeaseRequest {
         // blake3 of the object identity, hex.
@@ -231,6 +237,16 @@ export namespace node_registry {
         nodeId?: string;
         // Instantaneous load: requests in flight when the beat was sent.
         load?: number;
+        // The node&#x27;s busiest resident objects since its last beat, hottest
+    // first, as object ids; what the rebalancer picks handoffs from.
+        hotObjects?: string[];
+    }
+    export interface HeartbeatResponse {
+        // Objects the node is asked to hand off: drain the mailbox, ship,
+    // release the lease, and let the next caller claim it elsewhere.
+    // Always a subset of the reported hot objects the node still holds;
+    // empty on almost every beat.
+        handoffs?: string[];
     }
     export interface Node {
         nodeId?: string;
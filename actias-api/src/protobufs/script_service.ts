@@ -51,6 +51,41 @@ export namespace script_service {
         // Step literals found in the sources: the console&#x27;s
     // declared-possible skeleton, a superset of what may run.
         workflowSteps?: string[];
+        // Delivery policies declared with &#x60;queue &quot;name&quot; { ... }&#x60;, one per
+    // consumed queue at most; undeclared fields take the node&#x27;s defaults.
+        queuePolicies?: script_service.QueuePolicy[];
+        // Topics declared with &#x60;topic &quot;name&quot;&#x60;. Subscriptions are the
+    // &#x60;topic:&lt;name&gt;&#x60; entries in &#x60;events&#x60;; any number of scripts may hold
+    // one for the same topic.
+        topics?: string[];
+        // Schedules declared with &#x60;workflow &quot;name&quot; { schedule = ... }&#x60;; each
+    // rides the &#x60;__cron&#x60; object of its expression.
+        workflowSchedules?: script_service.WorkflowSchedule[];
+        // Retentions declared with &#x60;workflow &quot;name&quot; { retention = ... }&#x60;;
+    // settled runs of other definitions are kept forever.
+        workflowRetentions?: script_service.WorkflowRetention[];
+    }
+    // How a queue retries: attempts before dead-lettering, the first backoff
+    // and its ceiling. Durations are resolved to milliseconds at publish.
+    export interface QueuePolicy {
+        queue?: string;
+        maxAttempts?: number;
+        backoffMs?: number;
+        maxBackoffMs?: number;
+    }
+    // When a workflow starts on its own, and what a fire does while the
+    // previous scheduled run is still going: skip, buffer-one or allow.
+    export interface WorkflowSchedule {
+        workflow?: string;
+        schedule?: string;
+        overlap?: string;
+    }
+    // How long a workflow definition keeps completed and cancelled runs
+    // before they are purged, and whether the journal is archived first.
+    export interface WorkflowRetention {
+        workflow?: string;
+        retentionMs?: number;
+        archive?: boolean;
     }
     export interface ScriptConfig {
         id?: string;
@@ -189,7 +224,9 @@ export namespace script_service {
         projectId?: string;
         // Object class the owner is resolved for: a user class resolves its
     // declaring script, &#x60;__queue&#x60; the consumer (&#x60;on &quot;queue:&lt;name&gt;&quot;&#x60;),
-    // &#x60;__database&#x60; a declarer.
+    // &#x60;__database&#x60; a declarer, &#x60;__topic&#x60; a declarer or subscriber and
+    // &#x60;__subscription&#x60; (&#x60;&lt;topic&gt;/&lt;script id&gt;&#x60; or
+    // &#x60;database/&lt;database&gt;/&lt;script id&gt;&#x60;) the subscriber its name carries.
         class?: string;
         // Instance name; platform classes resolve by it, user classes by the
     // class alone.
@@ -200,6 +237,18 @@ export namespace script_service {
     export interface ClassOwner {
         scriptId?: string;
     }
+    export interface ListSubscribersRequest {
+        // Project the topic is scoped to.
+        projectId?: string;
+        // Topic name, as in &#x60;on &quot;topic:&lt;name&gt;&quot;&#x60;; ignored when &#x60;event&#x60; is set.
+        topic?: string;
+        // The whole event subscribers declare, as in &#x60;on &quot;database:&lt;name&gt;&quot;&#x60;.
+        event?: string;
+    }
+    // Scripts whose current revision subscribes to the event, by id.
+    export interface Subscribers {
+        scriptIds?: string[];
+    }
     export interface ScriptService {
         queryScript(
             data: FindScriptRequest,
@@ -292,6 +341,13 @@ export namespace script_service {
             metadata?: Metadata,
             ...rest: any[]
         ): Observable<ClassOwner>;
+        // Which scripts&#x27; current contracts subscribe to a topic; a topic with
+    // no subscribers answers an empty list, not NOT_FOUND.
+        listSubscribers(
+            data: ListSubscribersRequest,
+            metadata?: Metadata,
+            ...rest: any[]
+        ): Observable<Subscribers>;
         // Named environments over revisions; set is upsert, so a move and a
     // create are the same call.
         setAlias(
@@ -40,6 +40,16 @@ export namespace worker_data {
             metadata?: Metadata,
             ...rest: any[]
         ): Observable<ReadValue>;
+        // Replaces an object&#x27;s storage with a dump, as a fenced takeover:
+    // the receiving node claims the lease (a first hop forwards once to
+    // a live holder instead), drains the resident vm, and ships the new
+    // file at its own epoch before swapping it in, so a zombie holder&#x27;s
+    // uploads lose and the next call runs over the restored data.
+        restore(
+            data: RestoreRequest,
+            metadata?: Metadata,
+            ...rest: any[]
+        ): Observable<RestoreResult>;
     }
     // What one object call carries: the identity and the call, never code
     // coordinates.
@@ -64,6 +74,11 @@ export namespace worker_data {
     // already spent, so a stale lease view can never bounce a call
     // between nodes.
         firstHop?: boolean;
+        // What is left of the caller&#x27;s deadline, in milliseconds, measured
+    // when the call left; 0 means the caller set none. The receiver
+    // rebuilds the deadline against its own clock, so node clocks never
+    // need to agree, and a call still queued when it lapses never runs.
+        budgetMs?: number;
     }
     // The calling script&#x27;s identity as it travels between nodes.
     export interface Caller {
@@ -100,6 +115,11 @@ export namespace worker_data {
     // to the lease holder for the freshest copy. A forwarded read
     // answers from what its node has and never forwards again.
         firstHop?: boolean;
+        // The whole file instead of the overview: schema and rows as SQL,
+    // &#x60;{ format, content }&#x60; (ReadStats only).
+        dump?: boolean;
+        // With &#x60;dump&#x60;, the SQLite file itself, base64 in &#x60;content&#x60;.
+        raw?: boolean;
     }
     // One read&#x27;s answer.
     export interface ReadValue {
@@ -108,5 +128,26 @@ export namespace worker_data {
     // as empty, not as an error.
         valueJson?: string;
     }
+    // What one restore replaces an object&#x27;s storage with.
+    export interface RestoreRequest {
+        // The identity scope: the project id, or the script id for a cron
+    // object.
+        scopeId?: string;
+        class?: string;
+        name?: string;
+        // A SQL dump as ReadStats&#x27; &#x60;dump&#x60; produces one.
+        sql?: string;
+        // A whole SQLite file.
+        file?: Uint8Array;
+        // True when the caller is not a worker: a first hop may forward once
+    // to a live lease holder, which restores in its place.
+        firstHop?: boolean;
+    }
+    // One restore&#x27;s outcome.
+    export interface RestoreResult {
+        // The restored file&#x27;s size in bytes.
+        sizeBytes?: number;
+        // The lease epoch the restored snapshot shipped at.
+        epoch?: number;
+    }
 }
-
@@ -0,0 +1,143 @@
+import { of } from 'rxjs';
+import { BadRequestException } from '@nestjs/common';
+import { CronsController } from './crons.controller';
+import { ResourcesService } from './resources.service';
+
+const PROJECT = { id: 'project-1' } as any;
+
+/** Two scripts arming the same expression, one through a listener and
+ * one through a workflow schedule, over recording mocks. */
+function controller() {
+  const listScripts = jest.fn(() =>
+    of({
+      scripts: [
+        {
+          id: 'script-a',
+          publicIdentifier: 'billing',
+          currentRevisionId: 'r1',
+        },
+        {
+          id: 'script-b',
+          publicIdentifier: 'reports',
+          currentRevisionId: 'r2',
+        },
+      ],
+    }),
+  );
+  const getRevision = jest.fn(({ id }: { id: string }) =>
+    of({
+      scriptConfig: {
+        capabilities:
+          id === 'r1'
+            ? { events: ['cron:0 9 * * *', 'topic:orders'] }
+            : {
+                workflowSchedules: [
+                  {
+                    workflow: 'digest',
+                    schedule: '0 9 * * *',
+                    overlap: 'skip',
+                  },
+                ],
+              },
+      },
+    }),
+  );
+  const readStats = jest.fn(() =>
+    of({
+      valueJson: JSON.stringify({
+        tz: 'UTC',
+        catch_up: 'latest',
+        next_ms: 2000,
+        last_ms: 1000,
+        missed: 0,
+        fires: [
+          {
+            scheduled_ms: 1000,
+            fired_ms: 1001,
+            duration_ms: 4,
+            error: null,
+            manual: false,
+          },
+        ],
+      }),
+    }),
+  );
+  const dispatch = jest.fn(() =>
+    of({
+      resultJson: JSON.stringify({
+        scheduled_ms: 5000,
+        fired_ms: 5000,
+        duration_ms: 2,
+        error: 'boom',
+        manual: true,
+      }),
+      error: '',
+    }),
+  );
+
+  const grpc = (service: object) => ({ getService: () => service } as any);
+  const resources = new ResourcesService(
+    grpc({ listScripts, getRevision }),
+    grpc({}),
+    grpc({ readStats, dispatch }),
+    { get: jest.fn(() => 'internal-token') } as any,
+  );
+  resources.onModuleInit();
+
+  return { instance: new CronsController(resources), readStats, dispatch };
+}
+
+describe('the cron list', () => {
+  it("reads each instance under its script's scope", async () => {
+    const { instance, readStats } = controller();
+
+    const crons = await instance.listCrons(PROJECT);
+
+    expect(crons.map((cron) => [cron.declaredBy, cron.event])).toEqual([
+      ['billing', 'cron:0 9 * * *'],
+      ['reports', 'cron:0 9 * * *'],
+    ]);
+    expect(crons[1].workflows).toEqual(['digest']);
+    expect(crons[0].fires[0]).toEqual({
+      scheduledMs: 1000,
+      firedMs: 1001,
+      durationMs: 4,
+      error: undefined,
+      manual: false,
+    });
+    expect(
+      readStats.mock.calls.map((call: any[]) => call[0].scopeId),
+    ).toEqual(['script-a', 'script-b']);
+  });
+});
+
+describe('a manual trigger', () => {
+  it('refuses to guess between scripts declaring the same event', async () => {
+    const { instance, dispatch } = controller();
+
+    await expect(
+      instance.triggerCron(PROJECT, { event: '0 9 * * *' }),
+    ).rejects.toBeInstanceOf(BadRequestException);
+    expect(dispatch).not.toHaveBeenCalled();
+  });
+
+  it("fires the named script's instance", async () => {
+    const { instance, dispatch } = controller();
+
+    const fire = await instance.triggerCron(PROJECT, {
+      event: 'cron:0 9 * * *',
+      script: 'reports',
+    });
+
+    expect(fire).toMatchObject({ manual: true, error: 'boom' });
+    expect(dispatch).toHaveBeenCalledWith(
+      expect.objectContaining({
+        scopeId: 'script-b',
+        class: '__cron',
+        name: 'cron:0 9 * * *',
+        method: 'fire',
+      }),
+      expect.anything(),
+    );
+  });
+});
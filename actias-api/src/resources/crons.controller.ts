@@ -0,0 +1,190 @@
+import {
+  BadRequestException,
+  Body,
+  Controller,
+  Get,
+  NotFoundException,
+  Post,
+} from '@nestjs/common';
+import { ApiParam, ApiTags } from '@nestjs/swagger';
+import { lastValueFrom } from 'rxjs';
+import { AclByProject } from 'src/project/acl/acl.guard';
+import { AccessFields } from 'src/project/acl/accessFields';
+import { EntityParam } from 'src/util/entitydecorator';
+import { Projects } from 'src/entities/Projects';
+import { toHttpException } from 'src/exceptions/grpc.exception';
+import { ResourcesService } from './resources.service';
+import { CronDto, CronFireDto, CronTriggerDto } from './dto/crons.dto';
+
+/** The platform class a cron event rides on. */
+const CRON_CLASS = '__cron';
+
+/** One cron event and the script whose instance fires it. */
+interface DeclaredCron {
+  event: string;
+  scriptId: string;
+  declaredBy: string;
+  workflows: string[];
+}
+
+/** A fire row as the worker's read spells it. */
+interface FireRow {
+  scheduled_ms: number;
+  fired_ms: number;
+  duration_ms: number;
+  error?: string | null;
+  manual: boolean;
+}
+
+function toFire(row: FireRow): CronFireDto {
+  return {
+    scheduledMs: row.scheduled_ms,
+    firedMs: row.fired_ms,
+    durationMs: row.duration_ms,
+    error: row.error ?? undefined,
+    manual: row.manual,
+  };
+}
+
+/**
+ * A project's cron events: which fire when, what the last fires did, and
+ * a manual fire for debugging. Each event's instance is scoped to its
+ * script, not the project, so every read and call here carries the
+ * declaring script's id. One family of the backplane
+ * (`/project/:id/crons`).
+ */
+@ApiTags('crons')
+@Controller('project/:project/crons')
+export class CronsController {
+  constructor(private readonly resources: ResourcesService) {}
+
+  /** Every cron event a live contract declares, listeners and workflow
+   * schedules alike, with its instance's policy, next and last fire and
+   * recent history. */
+  @Get()
+  @AclByProject(AccessFields.SCRIPT_READ)
+  @ApiParam({ name: 'project', schema: { type: 'string' }, type: 'string' })
+  async listCrons(
+    @EntityParam('project', Projects) project: Projects,
+  ): Promise<CronDto[]> {
+    const declared = await this.declaredCrons(project);
+    const crons = await Promise.all(
+      declared.map(async (cron) => {
+        const state = (await this.resources.workerRead(
+          project,
+          CRON_CLASS,
+          cron.event,
+          { scopeId: cron.scriptId },
+        )) as {
+          tz?: string | null;
+          catch_up?: string | null;
+          next_ms?: number | null;
+          last_ms?: number | null;
+          missed?: number;
+          fires?: FireRow[];
+        } | null;
+        return {
+          event: cron.event,
+          declaredBy: cron.declaredBy,
+          workflows: cron.workflows,
+          tz: state?.tz ?? undefined,
+          catchUp: state?.catch_up ?? undefined,
+          nextMs: state?.next_ms ?? undefined,
+          lastMs: state?.last_ms ?? undefined,
+          missed: Number(state?.missed ?? 0),
+          fires: (state?.fires ?? []).map(toFire),
+        };
+      }),
+    );
+    return crons.sort(
+      (a, b) =>
+        a.declaredBy.localeCompare(b.declaredBy) ||
+        a.event.localeCompare(b.event),
+    );
+  }
+
+  /** Fires the event's listener and scheduled workflows once, now,
+   * without touching the schedule; answers with the fire's history row.
+   * A bare expression is read as `cron:<expr>`. */
+  @Post('trigger')
+  @AclByProject(AccessFields.SCRIPT_WRITE)
+  @ApiParam({ name: 'project', schema: { type: 'string' }, type: 'string' })
+  async triggerCron(
+    @EntityParam('project', Projects) project: Projects,
+    @Body() body: CronTriggerDto,
+  ): Promise<CronFireDto> {
+    const event = body.event.startsWith('cron:')
+      ? body.event
+      : `cron:${body.event}`;
+    const matches = (await this.declaredCrons(project)).filter(
+      (cron) =>
+        cron.event === event &&
+        (!body.script || cron.declaredBy === body.script),
+    );
+    if (matches.length === 0) {
+      throw new NotFoundException(
+        body.script
+          ? `Script '${body.script}' declares no '${event}'.`
+          : `No script declares '${event}'.`,
+      );
+    }
+    if (matches.length > 1) {
+      throw new BadRequestException(
+        `'${event}' is declared by ${matches
+          .map((cron) => cron.declaredBy)
+          .join(', ')}; name the script to fire.`,
+      );
+    }
+
+    const fired = (await this.resources.dispatchObject(
+      project,
+      CRON_CLASS,
+      event,
+      'fire',
+      [event],
+      matches[0].scriptId,
+    )) as FireRow;
+    return toFire(fired);
+  }
+
+  /** The cron events each script's current revision arms: its `cron:`
+   * listeners plus the expressions its workflows are scheduled on. */
+  private async declaredCrons(project: Projects): Promise<DeclaredCron[]> {
+    const page = await lastValueFrom(
+      this.resources.scripts
+        .listScripts({ projectId: project.id, pageSize: 500, page: 1 })
+        .pipe(toHttpException()),
+    );
+    const declared: DeclaredCron[] = [];
+    for (const script of page.scripts || []) {
+      if (!script.currentRevisionId) continue;
+      const revision = await lastValueFrom(
+        this.resources.scripts
+          .getRevision({
+            id: script.currentRevisionId,
+            withBundle: false,
+            manifestOnly: false,
+          })
+          .pipe(toHttpException()),
+      );
+      const capabilities = revision.scriptConfig?.capabilities;
+      const events = new Map<string, string[]>();
+      for (const event of capabilities?.events ?? []) {
+        if (event.startsWith('cron:')) events.set(event, []);
+      }
+      for (const schedule of capabilities?.workflowSchedules ?? []) {
+        const event = `cron:${schedule.schedule}`;
+        events.set(event, [...(events.get(event) ?? []), schedule.workflow]);
+      }
+      for (const [event, workflows] of events) {
+        declared.push({
+          event,
+          scriptId: script.id,
+          declaredBy: script.publicIdentifier,
+          workflows,
+        });
+      }
+    }
+    return declared;
+  }
+}
@@ -12,12 +12,13 @@ function controller(answers: { read?: unknown; dispatch?: unknown } = {}) {
   const dispatch = jest.fn(() =>
     of({ resultJson: JSON.stringify(answers.dispatch ?? null), error: '' }),
   );
+  const restore = jest.fn(() => of({ sizeBytes: 8192, epoch: 4 }));
 
   const grpc = (service: object) => ({ getService: () => service } as any);
   const resources = new ResourcesService(
     grpc({}),
     grpc({}),
-    grpc({ readStats, dispatch }),
+    grpc({ readStats, dispatch, restore }),
     { get: jest.fn(() => 'internal-token') } as any,
   );
   resources.onModuleInit();
@@ -26,6 +27,7 @@ function controller(answers: { read?: unknown; dispatch?: unknown } = {}) {
     instance: new DatabasesController(resources),
     readStats,
     dispatch,
+    restore,
   };
 }
 
@@ -94,3 +96,74 @@ describe('the sql console', () => {
     );
   });
 });
+
+describe('the sql shell', () => {
+  it('reads from the file itself, never through the owner', async () => {
+    const { instance, readStats, dispatch } = controller({
+      read: [{ n: 1 }],
+    });
+
+    const rows = await instance.readDatabase(PROJECT, 'shop', {
+      sql: 'SELECT 1 AS n',
+    });
+
+    expect(rows).toEqual({ rows: [{ n: 1 }] });
+    expect(readStats).toHaveBeenCalledWith(
+      expect.objectContaining({ class: '__database', sql: 'SELECT 1 AS n' }),
+      expect.anything(),
+    );
+    expect(dispatch).not.toHaveBeenCalled();
+  });
+});
+
+describe('dump and restore', () => {
+  it('asks for the raw file only when told to', async () => {
+    const { instance, readStats } = controller({
+      read: { format: 'sqlite', content: 'U1FMaXRl' },
+    });
+
+    const dump = await instance.dumpDatabase(PROJECT, 'shop', 'true');
+
+    expect(dump).toEqual({ format: 'sqlite', content: 'U1FMaXRl' });
+    expect(readStats).toHaveBeenCalledWith(
+      expect.objectContaining({ dump: true, raw: true }),
+      expect.anything(),
+    );
+  });
+
+  it('refuses to dump a database nothing has written', async () => {
+    const { instance } = controller({ read: null });
+
+    await expect(instance.dumpDatabase(PROJECT, 'ghost')).rejects.toThrow(
+      'nothing to dump',
+    );
+  });
+
+  it('sends a raw dump as bytes and a sql dump as text', async () => {
+    const { instance, restore } = controller();
+
+    const restored = await instance.restoreDatabase(PROJECT, 'shop', {
+      format: 'sqlite',
+      content: Buffer.from('SQLite').toString('base64'),
+    });
+    expect(restored).toEqual({ sizeBytes: 8192, epoch: 4 });
+    expect(restore).toHaveBeenLastCalledWith(
+      expect.objectContaining({
+        class: '__database',
+        name: 'shop',
+        file: Buffer.from('SQLite'),
+        firstHop: true,
+      }),
+      expect.anything(),
+    );
+
+    await instance.restoreDatabase(PROJECT, 'shop', {
+      format: 'sql',
+      content: 'CREATE TABLE t (n);',
+    });
+    expect(restore).toHaveBeenLastCalledWith(
+      expect.objectContaining({ sql: 'CREATE TABLE t (n);' }),
+      expect.anything(),
+    );
+  });
+});
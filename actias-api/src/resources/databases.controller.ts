@@ -1,12 +1,22 @@
-import { Body, Controller, Get, Param, Post } from '@nestjs/common';
-import { ApiParam, ApiTags } from '@nestjs/swagger';
+import {
+  BadRequestException,
+  Body,
+  Controller,
+  Get,
+  Param,
+  Post,
+  Query,
+} from '@nestjs/common';
+import { ApiParam, ApiQuery, ApiTags } from '@nestjs/swagger';
 import { AclByProject } from 'src/project/acl/acl.guard';
 import { AccessFields } from 'src/project/acl/accessFields';
 import { EntityParam } from 'src/util/entitydecorator';
 import { Projects } from 'src/entities/Projects';
 import { CLASSES, ResourcesService } from './resources.service';
 import {
+  DatabaseDumpDto,
   DatabaseOverviewDto,
+  DatabaseRestoredDto,
   ResourceInstanceDto,
   SqlQueryDto,
   SqlRowsDto,
@@ -57,6 +67,76 @@ export class DatabasesController {
     return this.dispatchSql(project, name, 'read', body);
   }
 
+  /**
+   * Runs one read-only statement against the file itself, no vm: the
+   * nearest copy answers under the script-guard authorizer, so nothing
+   * touches the owner. What `actias sql shell` runs by default.
+   */
+  @Post(':name/read')
+  @AclByProject(AccessFields.DATABASE_READ)
+  @ApiParam({ name: 'project', schema: { type: 'string' }, type: 'string' })
+  async readDatabase(
+    @EntityParam('project', Projects) project: Projects,
+    @Param('name') name: string,
+    @Body() body: SqlQueryDto,
+  ): Promise<SqlRowsDto> {
+    const rows = await this.resources.workerRead(
+      project,
+      CLASSES.databases,
+      name,
+      { sql: body.sql },
+    );
+    return { rows: Array.isArray(rows) ? rows : [] };
+  }
+
+  /** The whole database from the freshest copy: schema and rows as SQL,
+   * or with `raw`, the SQLite file itself, base64. */
+  @Get(':name/dump')
+  @AclByProject(AccessFields.DATABASE_READ)
+  @ApiParam({ name: 'project', schema: { type: 'string' }, type: 'string' })
+  @ApiQuery({ name: 'raw', required: false, type: Boolean })
+  async dumpDatabase(
+    @EntityParam('project', Projects) project: Projects,
+    @Param('name') name: string,
+    @Query('raw') raw?: string,
+  ): Promise<DatabaseDumpDto> {
+    const dump = (await this.resources.workerRead(
+      project,
+      CLASSES.databases,
+      name,
+      { dump: true, raw: raw === 'true' },
+    )) as DatabaseDumpDto | null;
+    if (!dump) {
+      throw new BadRequestException(
+        `Database '${name}' has never been written; there is nothing to dump.`,
+      );
+    }
+    return dump;
+  }
+
+  /**
+   * Replaces the database with a dump, as a fenced takeover: the owner's
+   * vm drains, the new file ships at a fresh lease epoch, and the next
+   * call runs over it. A dump that does not load replaces nothing.
+   */
+  @Post(':name/restore')
+  @AclByProject(AccessFields.DATABASE_WRITE)
+  @ApiParam({ name: 'project', schema: { type: 'string' }, type: 'string' })
+  async restoreDatabase(
+    @EntityParam('project', Projects) project: Projects,
+    @Param('name') name: string,
+    @Body() body: DatabaseDumpDto,
+  ): Promise<DatabaseRestoredDto> {
+    return this.resources.restoreObject(
+      project,
+      CLASSES.databases,
+      name,
+      body.format === 'sqlite'
+        ? { file: Buffer.from(body.content, 'base64') }
+        : { sql: body.content },
+    );
+  }
+
   /** Executes a statement through the owner, transactional, single-writer. */
   @Post(':name/execute')
   @AclByProject(AccessFields.DATABASE_WRITE)
@@ -0,0 +1,90 @@
+import { ApiProperty } from '@nestjs/swagger';
+import { IsOptional, IsString } from 'class-validator';
+
+/** One fire from a cron instance's history. */
+export class CronFireDto {
+  @ApiProperty({
+    description:
+      'The occurrence the fire stood for; a manual fire stands for its own moment.',
+  })
+  scheduledMs: number;
+
+  @ApiProperty()
+  firedMs: number;
+
+  @ApiProperty({
+    description: 'How long the listener and scheduled starts took together.',
+  })
+  durationMs: number;
+
+  @ApiProperty({
+    required: false,
+    nullable: true,
+    description: 'What failed, listener and starts joined.',
+  })
+  error?: string;
+
+  @ApiProperty({ description: 'Fired by hand rather than by the alarm.' })
+  manual: boolean;
+}
+
+/** One cron event a live contract declares, with what its instance
+ * recorded. */
+export class CronDto {
+  @ApiProperty({ description: 'The event, `cron:<expr>`.' })
+  event: string;
+
+  @ApiProperty({ description: 'Public identifier of the declaring script.' })
+  declaredBy: string;
+
+  @ApiProperty({
+    description: 'Workflows scheduled on the expression.',
+    type: [String],
+  })
+  workflows: string[];
+
+  @ApiProperty({
+    required: false,
+    nullable: true,
+    description: 'The zone the expression reads; absent until first armed.',
+  })
+  tz?: string;
+
+  @ApiProperty({
+    required: false,
+    nullable: true,
+    description: 'all, latest or none.',
+  })
+  catchUp?: string;
+
+  @ApiProperty({ required: false, nullable: true })
+  nextMs?: number;
+
+  @ApiProperty({
+    required: false,
+    nullable: true,
+    description: 'The last occurrence the schedule fired.',
+  })
+  lastMs?: number;
+
+  @ApiProperty({ description: 'Occurrences the catch-up policy skipped.' })
+  missed: number;
+
+  @ApiProperty({ type: [CronFireDto], description: 'Newest first.' })
+  fires: CronFireDto[];
+}
+
+export class CronTriggerDto {
+  @ApiProperty({ description: 'The event to fire, `cron:<expr>`.' })
+  @IsString()
+  event: string;
+
+  @ApiProperty({
+    required: false,
+    description:
+      'Public identifier of the declaring script; needed only when several declare the event.',
+  })
+  @IsOptional()
+  @IsString()
+  script?: string;
+}
@@ -1,5 +1,14 @@
 import { ApiProperty } from '@nestjs/swagger';
-import { IsArray, IsOptional, IsString } from 'class-validator';
+import {
+  ArrayNotEmpty,
+  IsArray,
+  IsIn,
+  IsInt,
+  IsOptional,
+  IsString,
+  Max,
+  Min,
+} from 'class-validator';
 
 /**
  * One queue or database a project holds: declared by a live contract,
@@ -23,6 +32,19 @@ export class ResourceInstanceDto {
   orphaned: boolean;
 }
 
+/** The delivery policy a queue runs under: node defaults overlaid with
+ * what its consumer declared. */
+export class QueuePolicyDto {
+  @ApiProperty({ description: 'Deliveries attempted before dead-lettering.' })
+  maxAttempts: number;
+
+  @ApiProperty({ description: 'First retry delay; doubles per attempt.' })
+  backoffMs: number;
+
+  @ApiProperty({ description: 'Ceiling the retry delay doubles up to.' })
+  maxBackoffMs: number;
+}
+
 export class QueueStatsDto {
   @ApiProperty({ description: 'Every message still queued.' })
   depth: number;
@@ -30,11 +52,30 @@ export class QueueStatsDto {
   @ApiProperty({ description: 'Messages due now, in delivery.' })
   inFlight: number;
 
+  @ApiProperty({ description: 'Messages sent for later, not due yet.' })
+  scheduled: number;
+
+  @ApiProperty({
+    description: 'Grouped messages waiting behind an older one in their group.',
+  })
+  blocked: number;
+
+  @ApiProperty({
+    description: 'Messages a pull consumer holds under an unexpired lease.',
+  })
+  leased: number;
+
   @ApiProperty({ required: false, nullable: true })
   oldestPending?: number;
 
   @ApiProperty()
   deadLetters: number;
+
+  @ApiProperty({
+    required: false,
+    description: 'Absent until the queue has been dispatched to once.',
+  })
+  policy?: QueuePolicyDto;
 }
 
 /** One live or dead message row, as the inspector's table shows it. */
@@ -42,12 +83,20 @@ export class QueueMessageDto {
   @ApiProperty()
   id: number;
 
-  @ApiProperty({ description: 'pending, in-flight or dead.' })
+  @ApiProperty({
+    description: 'scheduled, pending, in-flight, leased, blocked or dead.',
+  })
   state: string;
 
   @ApiProperty()
   attempts: number;
 
+  @ApiProperty({
+    required: false,
+    description: 'Attempts the policy allows before dead-lettering.',
+  })
+  maxAttempts?: number;
+
   @ApiProperty({ description: 'Payload prefix.' })
   preview: string;
 
@@ -62,6 +111,18 @@ export class QueueMessageDto {
 
   @ApiProperty({ required: false, nullable: true })
   diedMs?: number;
+
+  @ApiProperty({
+    required: false,
+    description: 'The key that deduplicates repeats of this send.',
+  })
+  dedupKey?: string;
+
+  @ApiProperty({
+    required: false,
+    description: 'Ordering group; delivers after its earlier messages.',
+  })
+  group?: string;
 }
 
 export class ColumnInfoDto {
@@ -137,6 +198,79 @@ export class RetriedDto {
   requeued: number;
 }
 
+export class PurgedDto {
+  @ApiProperty({
+    description: 'How many messages, live and dead, were discarded.',
+  })
+  purged: number;
+}
+
+/** What a pull consumer asks for: how many messages, hidden for how long. */
+export class LeaseRequestDto {
+  @ApiProperty({ required: false, minimum: 1, maximum: 100, default: 1 })
+  @IsOptional()
+  @IsInt()
+  @Min(1)
+  @Max(100)
+  count?: number;
+
+  @ApiProperty({
+    required: false,
+    description:
+      'How long leased messages stay hidden before they return unacked: "30s", "5m". Defaults to 30s.',
+  })
+  @IsOptional()
+  @IsString()
+  visibility?: string;
+}
+
+/** One leased message; settle it by receipt before `leasedUntil`. */
+export class LeasedMessageDto {
+  @ApiProperty()
+  id: number;
+
+  @ApiProperty({ description: 'The message as it was sent.' })
+  body: unknown;
+
+  @ApiProperty({
+    description: 'Which delivery attempt this lease is; ack or nack settles it.',
+  })
+  attempt: number;
+
+  @ApiProperty({ description: 'When the lease lapses and the message returns.' })
+  leasedUntil: number;
+
+  @ApiProperty({
+    description:
+      'What ack or nack settles this lease with; it stops working once the lease lapses.',
+  })
+  receipt: string;
+}
+
+/** Settles leased messages by receipt; a nack may say why. */
+export class SettleRequestDto {
+  @ApiProperty({ type: [String] })
+  @IsArray()
+  @ArrayNotEmpty()
+  @IsString({ each: true })
+  receipts: string[];
+
+  @ApiProperty({
+    required: false,
+    description: 'Why the messages were refused; journaled as the attempt error.',
+  })
+  @IsOptional()
+  @IsString()
+  reason?: string;
+}
+
+export class SettledDto {
+  @ApiProperty({
+    description: 'How many of the receipts named a live lease and are now settled.',
+  })
+  settled: number;
+}
+
 export class SqlQueryDto {
   @ApiProperty()
   @IsString()
@@ -153,6 +287,32 @@ export class SqlRowsDto {
   rows: unknown[];
 }
 
+/** A whole database for a backup or a move. */
+export class DatabaseDumpDto {
+  @ApiProperty({
+    enum: ['sql', 'sqlite'],
+    description:
+      'sql: schema and rows as statements; sqlite: the file itself, base64.',
+  })
+  @IsIn(['sql', 'sqlite'])
+  format: 'sql' | 'sqlite';
+
+  @ApiProperty()
+  @IsString()
+  content: string;
+}
+
+/** What a landed restore reports. */
+export class DatabaseRestoredDto {
+  @ApiProperty({ description: 'The restored file size in bytes.' })
+  sizeBytes: number;
+
+  @ApiProperty({
+    description: 'The lease epoch the restored snapshot shipped at.',
+  })
+  epoch: number;
+}
+
 export class QueueEventDto {
   @ApiProperty()
   seq: number;
@@ -27,7 +27,7 @@ export class WorkflowRunDto {
 
   @ApiProperty({
     description:
-      'completed, cancelled, sleeping, awaiting, running or unstarted.',
+      'completed, cancelled, failed, sleeping, awaiting, running or unstarted.',
   })
   status: string;
 
@@ -69,14 +69,17 @@ export class WorkflowJournalRowDto {
 
   @ApiProperty({
     description:
-      'STARTED, INTENT, RESULT, TIMER, SIGNAL, CHILD, CANCEL, COMPLETED or AMBIENT.',
+      'STARTED, INTENT, RESULT, TIMER, SIGNAL, CHILD, CANCEL, COMPLETED, AMBIENT, FAILED, UPDATE, PATCH or COMPENSATION.',
   })
   kind: string;
 
   @ApiProperty({ type: 'object', additionalProperties: true })
   data: Record<string, unknown>;
 
-  @ApiProperty()
+  @ApiProperty({
+    description:
+      'Row format; negative on rows of the generation continue-as-new archived, which are never replayed.',
+  })
   format: number;
 }
 
@@ -94,6 +97,9 @@ export class WorkflowRunDetailDto {
   @ApiProperty({ type: 'object', additionalProperties: true })
   detail: Record<string, unknown>;
 
+  @ApiProperty({ description: 'The step or gate the run is at.' })
+  atStep: string;
+
   @ApiProperty({ type: [WorkflowJournalRowDto] })
   journal: WorkflowJournalRowDto[];
 }
@@ -129,3 +135,14 @@ export class RunCancelDto {
   @IsString()
   reason?: string;
 }
+
+export class RunMigrateDto {
+  @ApiProperty({
+    required: false,
+    description:
+      "The revision id to move the run onto; omitted, the script's current revision.",
+  })
+  @IsOptional()
+  @IsString()
+  revision?: string;
+}
@@ -28,7 +28,14 @@ function controller(answers: { read?: unknown; dispatch?: unknown } = {}) {
 describe('queue stats', () => {
   it('maps the worker read onto the dto', async () => {
     const { instance, readStats } = controller({
-      read: { depth: 3, in_flight: 1, oldest_pending: 12, dead_letters: 2 },
+      read: {
+        depth: 3,
+        in_flight: 1,
+        scheduled: 4,
+        blocked: 2,
+        oldest_pending: 12,
+        dead_letters: 2,
+      },
     });
 
     const stats = await instance.queueStats(PROJECT, 'jobs');
@@ -36,6 +43,9 @@ describe('queue stats', () => {
     expect(stats).toEqual({
       depth: 3,
       inFlight: 1,
+      scheduled: 4,
+      blocked: 2,
+      leased: 0,
       oldestPending: 12,
       deadLetters: 2,
     });
@@ -58,6 +68,9 @@ describe('queue stats', () => {
     expect(stats).toEqual({
       depth: 0,
       inFlight: 0,
+      scheduled: 0,
+      blocked: 0,
+      leased: 0,
       oldestPending: undefined,
       deadLetters: 0,
     });
@@ -76,4 +89,67 @@ describe('queue controls', () => {
       expect.anything(),
     );
   });
+
+  it('reports how many messages a purge discarded', async () => {
+    const { instance, dispatch } = controller({ dispatch: 12 });
+
+    const purged = await instance.purge(PROJECT, 'jobs');
+
+    expect(purged).toEqual({ purged: 12 });
+    expect(dispatch).toHaveBeenCalledWith(
+      expect.objectContaining({ method: 'purge', class: '__queue' }),
+      expect.anything(),
+    );
+  });
+});
+
+describe('pull consumption', () => {
+  it('leases through the data plane and maps the rows', async () => {
+    const { instance, dispatch } = controller({
+      dispatch: [
+        {
+          id: 7,
+          body: { n: 1 },
+          attempt: 2,
+          leased_until: 99,
+          receipt: '7:99',
+        },
+      ],
+    });
+
+    const leased = await instance.lease(PROJECT, 'jobs', {
+      count: 10,
+      visibility: '1m',
+    });
+
+    expect(leased).toEqual([
+      { id: 7, body: { n: 1 }, attempt: 2, leasedUntil: 99, receipt: '7:99' },
+    ]);
+    expect(dispatch).toHaveBeenCalledWith(
+      expect.objectContaining({
+        method: 'lease',
+        class: '__queue',
+        argumentsJson: JSON.stringify([10, '1m']),
+      }),
+      expect.anything(),
+    );
+  });
+
+  it('settles by receipt and reports how many were still leased', async () => {
+    const { instance, dispatch } = controller({ dispatch: 1 });
+
+    const settled = await instance.nack(PROJECT, 'jobs', {
+      receipts: ['7:99', '8:99'],
+      reason: 'schema mismatch',
+    });
+
+    expect(settled).toEqual({ settled: 1 });
+    expect(dispatch).toHaveBeenCalledWith(
+      expect.objectContaining({
+        method: 'nack',
+        argumentsJson: JSON.stringify([['7:99', '8:99'], 'schema mismatch']),
+      }),
+      expect.anything(),
+    );
+  });
 });
@@ -1,4 +1,4 @@
-import { Controller, Get, Param, Post, Query } from '@nestjs/common';
+import { Body, Controller, Get, Param, Post, Query } from '@nestjs/common';
 import { ApiParam, ApiQuery, ApiTags } from '@nestjs/swagger';
 import { AclByProject } from 'src/project/acl/acl.guard';
 import { AccessFields } from 'src/project/acl/accessFields';
@@ -6,11 +6,16 @@ import { EntityParam } from 'src/util/entitydecorator';
 import { Projects } from 'src/entities/Projects';
 import { CLASSES, ResourcesService } from './resources.service';
 import {
+  LeasedMessageDto,
+  LeaseRequestDto,
+  PurgedDto,
   QueueEventDto,
   QueueMessageDto,
   QueueStatsDto,
   ResourceInstanceDto,
   RetriedDto,
+  SettledDto,
+  SettleRequestDto,
 } from './dto/resources.dto';
 
 /**
@@ -46,14 +51,32 @@ export class QueuesController {
     )) as {
       depth?: number;
       in_flight?: number;
+      scheduled?: number;
+      blocked?: number;
+      leased?: number;
       oldest_pending?: number;
       dead_letters?: number;
+      policy?: {
+        max_attempts: number;
+        backoff_base_ms: number;
+        max_backoff_ms: number;
+      } | null;
     } | null;
     return {
       depth: stats?.depth ?? 0,
       inFlight: stats?.in_flight ?? 0,
+      scheduled: stats?.scheduled ?? 0,
+      blocked: stats?.blocked ?? 0,
+      leased: stats?.leased ?? 0,
       oldestPending: stats?.oldest_pending ?? undefined,
       deadLetters: stats?.dead_letters ?? 0,
+      policy: stats?.policy
+        ? {
+            maxAttempts: stats.policy.max_attempts,
+            backoffMs: stats.policy.backoff_base_ms,
+            maxBackoffMs: stats.policy.max_backoff_ms,
+          }
+        : undefined,
     };
   }
 
@@ -76,11 +99,15 @@ export class QueuesController {
       id: Number(row.id),
       state: String(row.state ?? ''),
       attempts: Number(row.attempts ?? 0),
+      maxAttempts:
+        row.max_attempts == null ? undefined : Number(row.max_attempts),
       preview: String(row.preview ?? ''),
       size: Number(row.size ?? 0),
       enqueuedMs: Number(row.enqueued_ms ?? 0),
       nextMs: row.next_ms == null ? undefined : Number(row.next_ms),
       diedMs: row.died_ms == null ? undefined : Number(row.died_ms),
+      dedupKey: row.dedup_key == null ? undefined : String(row.dedup_key),
+      group: row.group == null ? undefined : String(row.group),
     }));
   }
 
@@ -158,4 +185,89 @@ export class QueuesController {
       [Number(id)],
     );
   }
+
+  /** Discards every message, live and dead. */
+  @Post(':name/purge')
+  @AclByProject(AccessFields.SCRIPT_WRITE)
+  @ApiParam({ name: 'project', schema: { type: 'string' }, type: 'string' })
+  async purge(
+    @EntityParam('project', Projects) project: Projects,
+    @Param('name') name: string,
+  ): Promise<PurgedDto> {
+    const count = await this.resources.dispatchObject(
+      project,
+      CLASSES.queues,
+      name,
+      'purge',
+      [],
+    );
+    return { purged: Number(count ?? 0) };
+  }
+
+  /** Pull consumption for services outside Actias: leases up to `count`
+   * due messages, hidden from delivery and other pullers until
+   * `visibility` runs out. Authenticate with a project service token. */
+  @Post(':name/lease')
+  @AclByProject(AccessFields.SCRIPT_WRITE)
+  @ApiParam({ name: 'project', schema: { type: 'string' }, type: 'string' })
+  async lease(
+    @EntityParam('project', Projects) project: Projects,
+    @Param('name') name: string,
+    @Body() request: LeaseRequestDto,
+  ): Promise<LeasedMessageDto[]> {
+    const rows = (await this.resources.dispatchObject(
+      project,
+      CLASSES.queues,
+      name,
+      'lease',
+      [request.count ?? 1, request.visibility ?? null],
+    )) as Record<string, unknown>[] | null;
+    return (Array.isArray(rows) ? rows : []).map((row) => ({
+      id: Number(row.id),
+      body: row.body ?? null,
+      attempt: Number(row.attempt ?? 1),
+      leasedUntil: Number(row.leased_until ?? 0),
+      receipt: String(row.receipt ?? ''),
+    }));
+  }
+
+  /** Settles leased messages as delivered; receipts whose lease lapsed
+   * or was settled are skipped. */
+  @Post(':name/ack')
+  @AclByProject(AccessFields.SCRIPT_WRITE)
+  @ApiParam({ name: 'project', schema: { type: 'string' }, type: 'string' })
+  async ack(
+    @EntityParam('project', Projects) project: Projects,
+    @Param('name') name: string,
+    @Body() request: SettleRequestDto,
+  ): Promise<SettledDto> {
+    const count = await this.resources.dispatchObject(
+      project,
+      CLASSES.queues,
+      name,
+      'ack',
+      [request.receipts],
+    );
+    return { settled: Number(count ?? 0) };
+  }
+
+  /** Settles leased messages as refused: each retries on the queue's
+   * backoff or dead-letters once its attempts are spent. */
+  @Post(':name/nack')
+  @AclByProject(AccessFields.SCRIPT_WRITE)
+  @ApiParam({ name: 'project', schema: { type: 'string' }, type: 'string' })
+  async nack(
+    @EntityParam('project', Projects) project: Projects,
+    @Param('name') name: string,
+    @Body() request: SettleRequestDto,
+  ): Promise<SettledDto> {
+    const count = await this.resources.dispatchObject(
+      project,
+      CLASSES.queues,
+      name,
+      'nack',
+      [request.receipts, request.reason ?? null],
+    );
+    return { settled: Number(count ?? 0) };
+  }
 }
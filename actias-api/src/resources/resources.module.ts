@@ -10,6 +10,7 @@ import { QueuesController } from './queues.controller';
 import { DatabasesController } from './databases.controller';
 import { ObjectsController } from './objects.controller';
 import { WorkflowsController } from './workflows.controller';
+import { CronsController } from './crons.controller';
 
 @Module({
   imports: [
@@ -41,6 +42,7 @@ import { WorkflowsController } from './workflows.controller';
     DatabasesController,
     ObjectsController,
     WorkflowsController,
+    CronsController,
   ],
   providers: [ResourcesService],
 })
@@ -28,7 +28,9 @@ export function clampPageSize(requested?: number): number {
  * The backplane's shared plumbing: the grpc clients and the worker
  * data-plane calls every resource family rides. Controllers stay thin
  * route surfaces; identity is project-scoped ((project, class, name)),
- * so reads and calls carry the project, never a script.
+ * so reads and calls carry the project, never a script. The one
+ * exception is a cron instance, scoped to the script whose schedule it
+ * is; its callers pass that script's id as the scope.
  */
 @Injectable()
 export class ResourcesService {
@@ -82,22 +84,31 @@ export class ResourcesService {
   /** One typed platform read over the data plane, answered from the
    * freshest copy the worker can reach (its file, the holder's, the
    * replica). With `sql`, one read-only statement instead of the class
-   * overview; with `messages`, the queue's message rows. */
+   * overview; with `messages`, the queue's message rows; with `dump`, the
+   * whole file as SQL (or with `raw`, as base64). */
   async workerRead(
     project: Projects,
     className: string,
     name: string,
-    options: { sql?: string; messages?: boolean } = {},
+    options: {
+      sql?: string;
+      messages?: boolean;
+      dump?: boolean;
+      raw?: boolean;
+      scopeId?: string;
+    } = {},
   ): Promise<Record<string, unknown> | unknown[] | null> {
     const value = await lastValueFrom(
       this.workers
         .readStats(
           {
-            scopeId: project.id,
+            scopeId: options.scopeId ?? project.id,
             class: className,
             name,
             sql: options.sql,
             messages: options.messages ?? false,
+            dump: options.dump ?? false,
+            raw: options.raw ?? false,
             firstHop: true,
           },
           this.internalMetadata(),
@@ -140,12 +151,13 @@ export class ResourcesService {
     name: string,
     method: string,
     args: unknown[],
+    scopeId?: string,
   ): Promise<unknown> {
     const result = await lastValueFrom(
       this.workers
         .dispatch(
           {
-            scopeId: project.id,
+            scopeId: scopeId ?? project.id,
             class: className,
             name,
             method,
@@ -162,6 +174,36 @@ export class ResourcesService {
     return this.parseValue(result.resultJson);
   }
 
+  /** Replaces an object's storage with a dump through a fenced takeover
+   * on the holder's node (or whichever claims it); the dump is checked
+   * before anything is replaced, and a refusal surfaces as a 400. */
+  async restoreObject(
+    project: Projects,
+    className: string,
+    name: string,
+    dump: { sql: string } | { file: Buffer },
+  ): Promise<{ sizeBytes: number; epoch: number }> {
+    const restored = await lastValueFrom(
+      this.workers
+        .restore(
+          {
+            scopeId: project.id,
+            class: className,
+            name,
+            ...dump,
+            firstHop: true,
+          },
+          this.internalMetadata(),
+        )
+        .pipe(toHttpException()),
+    );
+    // uint64 fields arrive as proto-loader Longs; Number() reads them.
+    return {
+      sizeBytes: Number(restored.sizeBytes ?? 0),
+      epoch: Number(restored.epoch ?? 0),
+    };
+  }
+
   /** One overview read mapped onto the DTO, whatever class owns the file. */
   async overviewOf(
     project: Projects,
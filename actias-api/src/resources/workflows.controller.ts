@@ -1,5 +1,5 @@
-import { Body, Controller, Get, Param, Post } from '@nestjs/common';
-import { ApiParam, ApiTags } from '@nestjs/swagger';
+import { Body, Controller, Get, Param, Post, Query } from '@nestjs/common';
+import { ApiParam, ApiQuery, ApiTags } from '@nestjs/swagger';
 import { lastValueFrom } from 'rxjs';
 import { AclByProject } from 'src/project/acl/acl.guard';
 import { AccessFields } from 'src/project/acl/accessFields';
@@ -11,8 +11,10 @@ import { randomUUID } from 'crypto';
 import {
   RunSignalDto,
   RunCancelDto,
+  RunMigrateDto,
   RunStartDto,
   WorkflowDefinitionDto,
+  WorkflowJournalRowDto,
   WorkflowRunDetailDto,
   WorkflowRunDto,
 } from './dto/workflows.dto';
@@ -69,13 +71,16 @@ export class WorkflowsController {
   }
 
   /** The definition's runs, newest first, each with its journal-derived
-   * status; the directory names them, the files answer for them. */
+   * status; the directory names them, the files answer for them.
+   * `status` keeps only runs in that state. */
   @Get(':definition/runs')
   @AclByProject(AccessFields.SCRIPT_READ)
   @ApiParam({ name: 'project', schema: { type: 'string' }, type: 'string' })
+  @ApiQuery({ name: 'status', required: false, type: String })
   async listRuns(
     @EntityParam('project', Projects) project: Projects,
     @Param('definition') definition: string,
+    @Query('status') status?: string,
   ): Promise<WorkflowRunDto[]> {
     const directory = await lastValueFrom(
       this.resources.registry
@@ -113,7 +118,9 @@ export class WorkflowsController {
         };
       }),
     );
-    return runs.sort((a, b) => (b.startedAt ?? 0) - (a.startedAt ?? 0));
+    return runs
+      .filter((run) => !status || run.status === status)
+      .sort((a, b) => (b.startedAt ?? 0) - (a.startedAt ?? 0));
   }
 
   /** One run, whole: status plus the journal the CI view folds. */
@@ -137,16 +144,38 @@ export class WorkflowsController {
       project,
       WORKFLOW_CLASS,
       name,
-    )) as { status?: Record<string, unknown> } | null;
+    )) as { status?: Record<string, unknown>; at?: unknown } | null;
     return {
       id,
       definition,
       status: String(head?.status?.status ?? 'unstarted'),
       detail: head?.status ?? {},
+      atStep: String(head?.at ?? ''),
       journal: rows as WorkflowRunDetailDto['journal'],
     };
   }
 
+  /** The run's journal after `since`, oldest first: what a follower
+   * polls. Rows of an archived generation are included. */
+  @Get(':definition/runs/:id/journal')
+  @AclByProject(AccessFields.SCRIPT_READ)
+  @ApiParam({ name: 'project', schema: { type: 'string' }, type: 'string' })
+  @ApiQuery({ name: 'since', required: false, type: Number })
+  async runJournal(
+    @EntityParam('project', Projects) project: Projects,
+    @Param('definition') definition: string,
+    @Param('id') id: string,
+    @Query('since') since?: string,
+  ): Promise<WorkflowJournalRowDto[]> {
+    const journal = await this.resources.readJournal(
+      project,
+      WORKFLOW_CLASS,
+      `${definition}/${id}`,
+      Number(since ?? 0),
+    );
+    return Array.isArray(journal) ? (journal as WorkflowJournalRowDto[]) : [];
+  }
+
   /** Starts (or joins) a run; the id is the idempotency key, minted
    * here when the caller has none. */
   @Post(':definition/runs')
@@ -188,6 +217,28 @@ export class WorkflowsController {
     return (outcome ?? {}) as Record<string, unknown>;
   }
 
+  /** Moves the run onto another revision of its script, the current one
+   * unless named. Refused, with nothing changed, when the new code cannot
+   * replay the run's journal. */
+  @Post(':definition/runs/:id/migrate')
+  @AclByProject(AccessFields.SCRIPT_WRITE)
+  @ApiParam({ name: 'project', schema: { type: 'string' }, type: 'string' })
+  async migrate(
+    @EntityParam('project', Projects) project: Projects,
+    @Param('definition') definition: string,
+    @Param('id') id: string,
+    @Body() body: RunMigrateDto,
+  ): Promise<Record<string, unknown>> {
+    const outcome = await this.resources.dispatchObject(
+      project,
+      WORKFLOW_CLASS,
+      `${definition}/${id}`,
+      'migrate',
+      body.revision ? [body.revision] : [],
+    );
+    return (outcome ?? {}) as Record<string, unknown>;
+  }
+
   /** Delivers a named signal into the run; a parked await resumes. */
   @Post(':definition/runs/:id/signal')
   @AclByProject(AccessFields.SCRIPT_WRITE)
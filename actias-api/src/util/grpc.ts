@@ -19,6 +19,7 @@ export const grpcClient = (
       options: {
         url: configService.get<string>(configValue),
         maxReceiveMessageLength: Number.MAX_SAFE_INTEGER,
+        maxSendMessageLength: Number.MAX_SAFE_INTEGER,
         package: packageName,
         protoPath: protoPaths,
       },
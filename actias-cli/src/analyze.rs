@@ -47,6 +47,8 @@ type WorkflowRun = {
     signal: (self: WorkflowRun, name: string, payload: any?) -> any,
     cancel: (self: WorkflowRun, reason: string?) -> any,
     status: (self: WorkflowRun) -> any,
+    query: (self: WorkflowRun, name: string, ...any) -> any,
+    update: (self: WorkflowRun, name: string, payload: any?) -> any,
     started: any,
 }
 type WorkflowDefinition = {
@@ -64,7 +66,12 @@ type Wf = {
     spawn: (self: Wf, definition: string, input: any) -> WfJob,
     all: (self: Wf, jobs: { WfJob | string }, opts: { timeout: (string | number)? }?) -> { any },
     race: (self: Wf, jobs: { WfJob | string }, opts: { timeout: (string | number)? }?) -> (any, string?),
+    on_query: (self: Wf, name: string, handler: (...any) -> any) -> (),
+    on_update: (self: Wf, name: string, validator: (any) -> any, handler: ((any) -> any)?) -> (),
+    continue_as_new: (self: Wf, input: any) -> (),
+    patched: (self: Wf, id: string) -> boolean,
 }
+type WorkflowRegistrar = (body: (Wf, any) -> any) -> WorkflowDefinition
 local kv: (string) -> KvNamespace = nil :: any
 local secret: (string) -> string = nil :: any
 local on: (string) -> ((any) -> any) -> () = nil :: any
@@ -72,7 +79,7 @@ local object: (string) -> ({ [string]: any }) -> ObjectHandle = nil :: any
 local objects: (string) -> ObjectHandle = nil :: any
 local database: (string) -> Database = nil :: any
 local queue: (string) -> Queue = nil :: any
-local workflow: (string) -> ((Wf, any) -> any) -> WorkflowDefinition = nil :: any
+local workflow: (string) -> (WorkflowRegistrar & ((options: { schedule: string?, overlap: string?, retention: (string | number)?, archive: boolean? }) -> WorkflowRegistrar)) = nil :: any
 local workflows: (string) -> WorkflowDefinition = nil :: any
 local json: { stringify: (any) -> string, parse: (string) -> any } = nil :: any
 local log: { debug: (any) -> (), info: (any) -> (), warn: (any) -> (), error: (any) -> () } = nil :: any
@@ -91,6 +91,42 @@ pub enum Commands {
         #[clap(subcommand)]
         sub: SqlOperations,
     },
+    /// 📬 Inspect and control a project's queue
+    Queue {
+        /// Queue name as declared in code (`queue "name"`).
+        name: String,
+        /// Project the queue belongs to.
+        #[clap(long, short)]
+        project: String,
+        /// Print JSON instead of tables, for scripts and jq.
+        #[clap(long, global = true)]
+        json: bool,
+        #[clap(subcommand)]
+        sub: QueueOperations,
+    },
+    /// 🔀 Inspect and operate a project's workflow runs
+    #[clap(alias = "wf")]
+    Workflow {
+        /// Project the runs belong to.
+        #[clap(long, short)]
+        project: String,
+        /// Print JSON instead of tables, for scripts and jq.
+        #[clap(long, global = true)]
+        json: bool,
+        #[clap(subcommand)]
+        sub: WorkflowOperations,
+    },
+    /// ⏰ Inspect a project's cron events and fire them by hand
+    Cron {
+        /// Project the scripts belong to.
+        #[clap(long, short)]
+        project: String,
+        /// Print JSON instead of tables, for scripts and jq.
+        #[clap(long, global = true)]
+        json: bool,
+        #[clap(subcommand)]
+        sub: CronOperations,
+    },
     /// 🧪 Run tests/*.lua on the local runtime with in-memory fakes.
     Test {
         /// Directory of project; defaults to the current one.
@@ -108,6 +144,154 @@ pub enum SqlOperations {
         #[clap(long, default_value = ".")]
         directory: String,
     },
+    /// 🐚 Open an interactive SQL prompt on the deployed database. Reads
+    /// answer from the nearest copy; writes need --write.
+    Shell {
+        /// Project the database belongs to.
+        #[clap(long, short)]
+        project: String,
+        /// Send statements through the database object instead, so they
+        /// may write.
+        #[clap(long)]
+        write: bool,
+    },
+    /// 📦 Dump the database as SQL, schema and rows.
+    Dump {
+        /// Project the database belongs to.
+        #[clap(long, short)]
+        project: String,
+        /// Dump the SQLite file itself instead.
+        #[clap(long)]
+        raw: bool,
+        /// File to write; standard output by default.
+        #[clap(long, short)]
+        output: Option<String>,
+    },
+    /// ♻️ Replace the database with a dump, SQL or a SQLite file.
+    Restore {
+        /// Project the database belongs to.
+        #[clap(long, short)]
+        project: String,
+        /// The dump to restore.
+        file: String,
+        /// Skip the confirmation prompt.
+        #[clap(long, short)]
+        yes: bool,
+    },
+}
+
+#[derive(Parser, Debug)]
+pub enum CronOperations {
+    /// 📑 List cron events with their next and last fire and last
+    /// outcome.
+    List {
+        /// Also print each event's recent fires.
+        #[clap(long)]
+        history: bool,
+    },
+    /// 🔥 Fire an event's listener and scheduled workflows once, now;
+    /// the schedule itself is untouched.
+    Trigger {
+        /// The event as `cron:<expr>`, or the bare expression.
+        event: String,
+        /// Script declaring the event, when several do.
+        #[clap(long, short)]
+        script: Option<String>,
+    },
+}
+
+#[derive(Parser, Debug)]
+pub enum WorkflowOperations {
+    /// 📑 List a definition's runs, newest first.
+    List {
+        /// Workflow name as declared in code (`workflow "name"`).
+        definition: String,
+        /// Only runs in this state: failed, awaiting, sleeping, running,
+        /// completed or cancelled.
+        #[clap(long, short)]
+        status: Option<String>,
+    },
+    /// 🔍 Show a run's status, position and step timeline.
+    Describe {
+        /// The run as `<definition>/<id>`.
+        run: String,
+    },
+    /// 📜 Print a run's journal.
+    Journal {
+        /// The run as `<definition>/<id>`.
+        run: String,
+        /// Only rows after this journal sequence number.
+        #[clap(long, default_value_t = 0)]
+        since: i64,
+        /// Keep polling for new rows until interrupted.
+        #[clap(long, short)]
+        follow: bool,
+    },
+    /// 📨 Deliver a named signal; a parked await resumes.
+    Signal {
+        /// The run as `<definition>/<id>`.
+        run: String,
+        /// Signal name the run awaits.
+        name: String,
+        /// JSON object delivered as the signal's payload.
+        #[clap(long)]
+        payload: Option<String>,
+    },
+    /// 🛑 Cancel a run and its children.
+    Cancel {
+        /// The run as `<definition>/<id>`.
+        run: String,
+        /// Recorded as the cancellation reason.
+        #[clap(long)]
+        reason: Option<String>,
+        /// Skip the confirmation prompt.
+        #[clap(long, short)]
+        yes: bool,
+    },
+    /// ▶️ Re-enter a failed run at its failed step with fresh attempts.
+    Resume {
+        /// The run as `<definition>/<id>`.
+        run: String,
+    },
+    /// ⏪ Replay a run's journal against local code and report where it
+    /// diverges.
+    Replay {
+        /// The run as `<definition>/<id>`.
+        run: String,
+        /// Project directory holding the code to check; defaults to the
+        /// current one.
+        #[clap(long, default_value = ".")]
+        revision: String,
+    },
+}
+
+#[derive(Parser, Debug)]
+pub enum QueueOperations {
+    /// 📊 Depth, in-flight, scheduled and dead-letter counts.
+    Stats,
+    /// 📑 List live and dead messages, newest first.
+    Messages,
+    /// 📜 Print the queue's journal.
+    Events {
+        /// Only events after this journal sequence number.
+        #[clap(long, default_value_t = 0)]
+        since: i64,
+        /// Keep polling for new events until interrupted.
+        #[clap(long, short)]
+        follow: bool,
+    },
+    /// 🔁 Requeue one dead letter by id.
+    Retry { id: i64 },
+    /// 🔁 Requeue every dead letter.
+    RetryDead,
+    /// 🚮 Discard one message, live or dead.
+    Drop { id: i64 },
+    /// 🧹 Discard every message, live and dead.
+    Purge {
+        /// Skip the confirmation prompt.
+        #[clap(long, short)]
+        yes: bool,
+    },
 }
 
 #[derive(Parser, Debug)]
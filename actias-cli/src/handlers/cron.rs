@@ -0,0 +1,127 @@
+//! Inspect a project's cron events from the terminal and fire them by
+//! hand: when each fires next, when it last did, whether that went well,
+//! and a manual fire for debugging a listener or a scheduled workflow.
+
+use colored::{ColoredString, Colorize};
+use prettytable::{Table, row};
+
+use crate::{
+    client::{
+        Client,
+        types::{CronFireDto, CronTriggerDto},
+    },
+    commands::CronOperations,
+    errors::{Result, progenitor_error},
+    util::{format_ms, print_json},
+};
+
+/// Handle cron command
+pub async fn handle(
+    client: &Client,
+    project: &str,
+    json: bool,
+    operation: &CronOperations,
+) -> Result<()> {
+    match operation {
+        CronOperations::List { history } => {
+            let crons = client
+                .list_crons()
+                .project(project)
+                .send()
+                .await
+                .map_err(progenitor_error)?
+                .into_inner();
+            if json {
+                return print_json(&crons);
+            }
+            if crons.is_empty() {
+                println!("No script declares a cron event.");
+                return Ok(());
+            }
+
+            let mut table = Table::new();
+            table.add_row(row![
+                "Event", "Script", "Zone", "Next", "Last", "Outcome", "Missed"
+            ]);
+            for cron in &crons {
+                let event = if cron.workflows.is_empty() {
+                    cron.event.clone()
+                } else {
+                    format!("{} → {}", cron.event, cron.workflows.join(", "))
+                };
+                table.add_row(row![
+                    event,
+                    cron.declared_by,
+                    cron.tz.as_deref().unwrap_or("UTC"),
+                    cron.next_ms.map(format_ms).unwrap_or_default(),
+                    cron.fires
+                        .first()
+                        .map(|fire| format_ms(fire.fired_ms))
+                        .unwrap_or_default(),
+                    cron.fires.first().map(outcome).unwrap_or_default(),
+                    cron.missed,
+                ]);
+            }
+            table.printstd();
+
+            if *history {
+                for cron in crons.iter().filter(|cron| !cron.fires.is_empty()) {
+                    println!();
+                    println!("⏰ {} ({})", cron.event.purple(), cron.declared_by);
+                    let mut table = Table::new();
+                    table.add_row(row!["Scheduled", "Fired", "Took", "Outcome"]);
+                    for fire in &cron.fires {
+                        table.add_row(row![
+                            if fire.manual {
+                                "manual".to_string()
+                            } else {
+                                format_ms(fire.scheduled_ms)
+                            },
+                            format_ms(fire.fired_ms),
+                            format!("{}ms", fire.duration_ms),
+                            outcome(fire),
+                        ]);
+                    }
+                    table.printstd();
+                }
+            }
+        }
+        CronOperations::Trigger { event, script } => {
+            let fire = client
+                .trigger_cron()
+                .project(project)
+                .body(
+                    CronTriggerDto::builder()
+                        .event(event.clone())
+                        .script(script.clone()),
+                )
+                .send()
+                .await
+                .map_err(progenitor_error)?
+                .into_inner();
+            if json {
+                return print_json(&fire);
+            }
+
+            println!(
+                "🔥 Fired {} in {}ms: {}",
+                event.purple(),
+                fire.duration_ms,
+                outcome(&fire)
+            );
+            if let Some(error) = &fire.error {
+                println!("   {}", error.red());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// A fire's verdict as the tables show it.
+fn outcome(fire: &CronFireDto) -> ColoredString {
+    match fire.error {
+        Some(_) => "failed".red(),
+        None => "ok".green(),
+    }
+}
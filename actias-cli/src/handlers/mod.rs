@@ -1,9 +1,11 @@
 pub mod aliases;
 pub mod check;
+pub mod cron;
 pub mod dev;
 pub mod init;
 pub mod projects;
 pub mod publish;
+pub mod queues;
 pub mod revisions;
 pub mod scripts;
 pub mod secrets;
@@ -11,3 +13,4 @@ pub mod sql;
 pub mod tail;
 pub mod test;
 pub mod tokens;
+pub mod workflows;
@@ -1,5 +1,6 @@
 use colored::*;
 use inquire::{Confirm, Text};
+use std::collections::BTreeMap;
 use std::path::Path;
 
 use crate::{
@@ -7,7 +8,8 @@ use crate::{
     client::{
         Client,
         types::{
-            CapabilitiesDto, CreateRevisionDto, CreateScriptDto, MissingBlobsDto, ScriptConfigDto,
+            BundleDto, CapabilitiesDto, CreateRevisionDto, CreateScriptDto, FileDto,
+            MissingBlobsDto, ScriptConfigDto,
         },
     },
     errors::{Error, Result, progenitor_error},
@@ -63,6 +65,32 @@ pub async fn handle(client: &Client, script_dir: &str) -> Result<()> {
             declared.queues.join(", ").purple()
         );
     }
+    if !declared.topics.is_empty() {
+        println!(
+            "📣 Declares topics: {}",
+            declared.topics.join(", ").purple()
+        );
+    }
+    for schedule in &declared.workflow_schedules {
+        println!(
+            "⏰ Schedules {} on {} ({})",
+            schedule.workflow.purple(),
+            schedule.schedule,
+            schedule.overlap
+        );
+    }
+    for retention in &declared.workflow_retentions {
+        println!(
+            "🧹 Keeps settled {} runs for {}s{}",
+            retention.workflow.purple(),
+            retention.retention_ms / 1000,
+            if retention.archive {
+                ", archiving journals"
+            } else {
+                ""
+            }
+        );
+    }
 
     let mut config_dto: ScriptConfigDto = script_config.clone().into();
     config_dto.capabilities = Some(CapabilitiesDto {
@@ -78,6 +106,14 @@ pub async fn handle(client: &Client, script_dir: &str) -> Result<()> {
 
     let mut bundle = script_config.to_bundle().map_err(Error::Script)?;
 
+    if let Some(deployed) = script
+        .current_revision_id
+        .as_deref()
+        .filter(|id| !id.is_empty())
+    {
+        warn_migration_drift(client, deployed, &bundle).await;
+    }
+
     // The store already holds any blob it has seen from anyone; files whose
     // hash it knows publish as manifest-only entries with no content.
     let hashes: Vec<String> = bundle
@@ -145,6 +181,65 @@ pub async fn handle(client: &Client, script_dir: &str) -> Result<()> {
     Ok(())
 }
 
+/// Warns about migrations the bundle edits or removes relative to the
+/// deployed revision. Databases already migrated refuse an edited file
+/// outright, and a removed one never reruns on a fresh database, so
+/// either means production and a fresh copy diverge. Comparing the
+/// store's content hashes is enough; nothing here blocks the publish.
+async fn warn_migration_drift(client: &Client, deployed: &str, bundle: &BundleDto) {
+    let revision = match client
+        .get_revision()
+        .id(deployed)
+        .with_bundle(true)
+        .send()
+        .await
+    {
+        Ok(revision) => revision.into_inner(),
+        Err(error) => {
+            println!(
+                "{}",
+                format!("Could not compare migrations with the deployed revision: {error}")
+                    .bright_black()
+            );
+            return;
+        }
+    };
+
+    let migrations = |files: &[FileDto]| -> BTreeMap<String, Option<String>> {
+        files
+            .iter()
+            .filter(|file| {
+                file.file_path.starts_with("migrations/") && file.file_path.ends_with(".sql")
+            })
+            .map(|file| (file.file_path.clone(), file.hash.clone()))
+            .collect()
+    };
+    let before = migrations(
+        revision
+            .bundle
+            .as_ref()
+            .map(|bundle| bundle.files.as_slice())
+            .unwrap_or_default(),
+    );
+    let after = migrations(&bundle.files);
+
+    for (path, hash) in &before {
+        match after.get(path) {
+            None => println!(
+                "⚠️ {} {} was removed; databases that applied it keep its changes, fresh ones will not",
+                "Migration".yellow(),
+                path.purple()
+            ),
+            Some(local) if hash.is_some() && local != hash => println!(
+                "⚠️ {} {} was edited after deploy; databases that applied it will refuse calls until it is restored",
+                "Migration".yellow(),
+                path.purple()
+            ),
+            Some(_) => {}
+        }
+    }
+}
+
 /// Create a new script when ID is not present
 async fn create_new_script(
     client: &Client,
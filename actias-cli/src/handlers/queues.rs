@@ -0,0 +1,248 @@
+//! Inspect and control a project's queues from the terminal: the same
+//! stats, listings, journal and dead-letter controls the dashboard's
+//! inspector has, for on-call work without a browser.
+
+use colored::Colorize;
+use inquire::Confirm;
+use prettytable::{Table, row};
+
+use crate::{
+    client::{Client, types::QueueEventDto},
+    commands::QueueOperations,
+    errors::{Error, Result, progenitor_error},
+    util::{follow_journal, format_ms, print_json},
+};
+
+/// Handle queue command
+pub async fn handle(
+    client: &Client,
+    project: &str,
+    name: &str,
+    json: bool,
+    operation: &QueueOperations,
+) -> Result<()> {
+    match operation {
+        QueueOperations::Stats => {
+            let stats = client
+                .queue_stats()
+                .project(project)
+                .name(name)
+                .send()
+                .await
+                .map_err(progenitor_error)?
+                .into_inner();
+            if json {
+                return print_json(&stats);
+            }
+
+            let mut table = Table::new();
+            table.add_row(row!["Depth", stats.depth]);
+            table.add_row(row!["In flight", stats.in_flight]);
+            table.add_row(row!["Scheduled", stats.scheduled]);
+            table.add_row(row!["Blocked", stats.blocked]);
+            table.add_row(row!["Leased", stats.leased]);
+            table.add_row(row!["Dead letters", stats.dead_letters]);
+            table.add_row(row![
+                "Oldest pending",
+                stats.oldest_pending.map(format_ms).unwrap_or_default()
+            ]);
+            if let Some(policy) = &stats.policy {
+                table.add_row(row!["Max attempts", policy.max_attempts]);
+                table.add_row(row![
+                    "Backoff",
+                    format!("{}ms up to {}ms", policy.backoff_ms, policy.max_backoff_ms)
+                ]);
+            }
+
+            println!("📊 Queue {}", name.purple());
+            table.printstd();
+        }
+        QueueOperations::Messages => {
+            let messages = client
+                .queue_messages()
+                .project(project)
+                .name(name)
+                .send()
+                .await
+                .map_err(progenitor_error)?
+                .into_inner();
+            if json {
+                return print_json(&messages);
+            }
+            if messages.is_empty() {
+                println!("No messages in {}.", name.purple());
+                return Ok(());
+            }
+
+            let mut table = Table::new();
+            table.add_row(row![
+                "ID", "State", "Attempts", "Enqueued", "Next", "Group", "Preview"
+            ]);
+            for message in messages {
+                let attempts = match message.max_attempts {
+                    Some(max) => format!("{}/{max}", message.attempts),
+                    None => message.attempts.to_string(),
+                };
+                let state = if message.state == "dead" {
+                    message.state.red()
+                } else {
+                    message.state.normal()
+                };
+                table.add_row(row![
+                    message.id,
+                    state,
+                    attempts,
+                    format_ms(message.enqueued_ms),
+                    message.next_ms.map(format_ms).unwrap_or_default(),
+                    message.group.unwrap_or_default(),
+                    message.preview,
+                ]);
+            }
+            table.printstd();
+        }
+        QueueOperations::Events { since, follow } => {
+            events(client, project, name, json, *since, *follow).await?;
+        }
+        QueueOperations::Retry { id } => {
+            let retried = client
+                .retry_message()
+                .project(project)
+                .name(name)
+                .id(id.to_string())
+                .send()
+                .await
+                .map_err(progenitor_error)?
+                .into_inner();
+            if json {
+                return print_json(&retried);
+            }
+            if retried.requeued == 0.0 {
+                return Err(Error::NotFound(format!(
+                    "Message {id} is not a dead letter in {name}."
+                )));
+            }
+            println!("🔁 Requeued message {} in {}.", id, name.purple());
+        }
+        QueueOperations::RetryDead => {
+            let retried = client
+                .retry_dead()
+                .project(project)
+                .name(name)
+                .send()
+                .await
+                .map_err(progenitor_error)?
+                .into_inner();
+            if json {
+                return print_json(&retried);
+            }
+            println!(
+                "🔁 Requeued {} dead letters in {}.",
+                retried.requeued.to_string().yellow(),
+                name.purple()
+            );
+        }
+        QueueOperations::Drop { id } => {
+            client
+                .drop_message()
+                .project(project)
+                .name(name)
+                .id(id.to_string())
+                .send()
+                .await
+                .map_err(progenitor_error)?;
+            if json {
+                return print_json(&serde_json::json!({ "dropped": id }));
+            }
+            println!("🚮 Dropped message {} from {}.", id, name.purple());
+        }
+        QueueOperations::Purge { yes } => {
+            if !yes
+                && !Confirm::new(&format!(
+                    "Discard every message in {name}, dead letters too?"
+                ))
+                .with_default(false)
+                .prompt()
+                .map_err(|e| Error::Command(e.to_string()))?
+            {
+                return Ok(());
+            }
+
+            let purged = client
+                .purge()
+                .project(project)
+                .name(name)
+                .send()
+                .await
+                .map_err(progenitor_error)?
+                .into_inner();
+            if json {
+                return print_json(&purged);
+            }
+            println!(
+                "🧹 Purged {} messages from {}.",
+                purged.purged.to_string().yellow(),
+                name.purple()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Prints the journal after `since`, and with `follow` keeps asking for
+/// what came after the last printed sequence number until ctrl-c. The
+/// journal is a ring, so a follower that falls further behind than the
+/// ring holds resumes at the oldest event still kept.
+async fn events(
+    client: &Client,
+    project: &str,
+    name: &str,
+    json: bool,
+    since: i64,
+    follow: bool,
+) -> Result<()> {
+    let printed = follow_journal(
+        since,
+        follow,
+        |cursor| async move {
+            Ok::<_, Error>(
+                client
+                    .queue_events()
+                    .project(project)
+                    .name(name)
+                    .since(cursor as f64)
+                    .send()
+                    .await
+                    .map_err(progenitor_error)?
+                    .into_inner(),
+            )
+        },
+        |event: &QueueEventDto| {
+            if json {
+                // One object per line, so a follower pipes into jq.
+                println!("{}", serde_json::to_string(event)?);
+            } else {
+                let kind = match event.kind.as_str() {
+                    "dead-lettered" => event.kind.red(),
+                    "retried" => event.kind.yellow(),
+                    "delivered" => event.kind.green(),
+                    _ => event.kind.normal(),
+                };
+                println!(
+                    "{} {} {} {}",
+                    event.seq.to_string().bright_black(),
+                    format_ms(event.at).bright_black(),
+                    kind,
+                    serde_json::Value::Object(event.detail.clone())
+                );
+            }
+            Ok(event.seq as i64)
+        },
+    )
+    .await?;
+
+    if !printed && !json {
+        println!("No events in {} after {}.", name.purple(), since);
+    }
+    Ok(())
+}
@@ -1,22 +1,50 @@
 //! Database tooling. Migrations are bundle files under
 //! `migrations/<database>/`, applied in file order by the platform at the
-//! database's first touch; the scaffold's only job is the next number.
+//! database's first touch and recorded with their content hash, so an
+//! applied file is never edited, only followed by a new one; the
+//! scaffold's only job is the next number.
+//!
+//! The deployed database is reachable too: a shell for poking at
+//! production data without a throwaway script, and dump and restore for
+//! backups and moves.
 
+use base64::Engine;
 use colored::*;
+use inquire::Confirm;
+use prettytable::{Cell, Row, Table};
 use std::path::Path;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 
 use crate::{
+    client::{
+        Client,
+        types::{DatabaseDumpDto, DatabaseDumpDtoFormat, SqlQueryDto},
+    },
     commands::SqlOperations,
-    errors::{Error, Result},
+    errors::{Error, Result, progenitor_error},
 };
 
-pub fn handle(database: &str, operation: &SqlOperations) -> Result<()> {
+/// What every SQLite file starts with; how restore tells a raw dump from
+/// a SQL one.
+const SQLITE_HEADER: &[u8] = b"SQLite format 3\0";
+
+/// Handle sql command
+pub async fn handle(client: &Client, database: &str, operation: &SqlOperations) -> Result<()> {
     match operation {
         SqlOperations::Create { name, directory } => create(database, name, directory),
+        SqlOperations::Shell { project, write } => shell(client, project, database, *write).await,
+        SqlOperations::Dump {
+            project,
+            raw,
+            output,
+        } => dump(client, project, database, *raw, output.as_deref()).await,
+        SqlOperations::Restore { project, file, yes } => {
+            restore(client, project, database, file, *yes).await
+        }
     }
 }
 
-fn create(database: &str, name: &str, directory: &str) -> Result<()> {
+pub fn create(database: &str, name: &str, directory: &str) -> Result<()> {
     let dir = Path::new(directory).join("migrations").join(database);
     std::fs::create_dir_all(&dir).map_err(|e| Error::Io(e.to_string()))?;
 
@@ -45,3 +73,244 @@ fn create(database: &str, name: &str, directory: &str) -> Result<()> {
     println!("📝 {}", file.display().to_string().purple());
     Ok(())
 }
+
+/// The prompt loop. Statements run once a line ends with `;`, so they may
+/// span lines; reads go to the file itself and never wake the owner,
+/// while `write` sends each statement through the database object, one
+/// transaction apiece. A failed statement is printed, not fatal.
+async fn shell(client: &Client, project: &str, database: &str, write: bool) -> Result<()> {
+    let mode = if write {
+        "read-write, through the owner".red()
+    } else {
+        "read-only".green()
+    };
+    eprintln!(
+        "🐚 {} ({mode}). End statements with ;  .tables lists tables, .exit leaves.",
+        database.purple()
+    );
+
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    let mut statement = String::new();
+    loop {
+        let prompt = if statement.is_empty() {
+            format!("{database}> ")
+        } else {
+            "...> ".to_owned()
+        };
+        let mut stderr = tokio::io::stderr();
+        stderr.write_all(prompt.as_bytes()).await?;
+        stderr.flush().await?;
+
+        let Some(line) = lines.next_line().await? else {
+            break;
+        };
+        let line = line.trim();
+
+        if statement.is_empty() {
+            match line {
+                "" => continue,
+                ".exit" | ".quit" => break,
+                ".tables" => {
+                    if let Err(error) = tables(client, project, database).await {
+                        eprintln!("{}", error.to_string().red());
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+        }
+
+        statement.push_str(line);
+        statement.push('\n');
+        if !line.ends_with(';') {
+            continue;
+        }
+
+        let sql = std::mem::take(&mut statement);
+        match run(client, project, database, &sql, write).await {
+            Ok(rows) => print_rows(&rows),
+            Err(error) => eprintln!("{}", error.to_string().red()),
+        }
+    }
+
+    Ok(())
+}
+
+/// One statement's rows, from the file or through the owner.
+async fn run(
+    client: &Client,
+    project: &str,
+    database: &str,
+    sql: &str,
+    write: bool,
+) -> Result<Vec<serde_json::Value>> {
+    let body = SqlQueryDto::builder().sql(sql.to_owned());
+    let rows = if write {
+        client
+            .execute()
+            .project(project)
+            .name(database)
+            .body(body)
+            .send()
+            .await
+    } else {
+        client
+            .read_database()
+            .project(project)
+            .name(database)
+            .body(body)
+            .send()
+            .await
+    }
+    .map_err(progenitor_error)?
+    .into_inner()
+    .rows;
+    Ok(rows)
+}
+
+/// The user tables with their row counts, as the overview reads them.
+async fn tables(client: &Client, project: &str, database: &str) -> Result<()> {
+    let overview = client
+        .database_overview()
+        .project(project)
+        .name(database)
+        .send()
+        .await
+        .map_err(progenitor_error)?
+        .into_inner();
+    for table in &overview.tables {
+        println!("{} ({} rows)", table.name, table.rows);
+    }
+    Ok(())
+}
+
+/// Rows as a table, columns in the first row's order; a statement that
+/// returned nothing says so.
+fn print_rows(rows: &[serde_json::Value]) {
+    let Some(serde_json::Value::Object(first)) = rows.first() else {
+        println!("{}", "(no rows)".dimmed());
+        return;
+    };
+    let columns: Vec<&String> = first.keys().collect();
+
+    let mut table = Table::new();
+    table.add_row(Row::new(
+        columns.iter().map(|column| Cell::new(column)).collect(),
+    ));
+    for row in rows {
+        table.add_row(Row::new(
+            columns
+                .iter()
+                .map(|column| {
+                    Cell::new(&match &row[column.as_str()] {
+                        serde_json::Value::Null => "NULL".to_owned(),
+                        serde_json::Value::String(text) => text.clone(),
+                        other => other.to_string(),
+                    })
+                })
+                .collect(),
+        ));
+    }
+    table.printstd();
+    println!("{}", format!("({} rows)", rows.len()).dimmed());
+}
+
+async fn dump(
+    client: &Client,
+    project: &str,
+    database: &str,
+    raw: bool,
+    output: Option<&str>,
+) -> Result<()> {
+    if raw && output.is_none() {
+        return Err(Error::Command(
+            "A raw dump is a binary file; name one with --output.".to_owned(),
+        ));
+    }
+
+    let dump = client
+        .dump_database()
+        .project(project)
+        .name(database)
+        .raw(raw)
+        .send()
+        .await
+        .map_err(progenitor_error)?
+        .into_inner();
+    let bytes = match dump.format {
+        DatabaseDumpDtoFormat::Sql => dump.content.into_bytes(),
+        DatabaseDumpDtoFormat::Sqlite => base64::engine::general_purpose::STANDARD
+            .decode(dump.content)
+            .map_err(|e| Error::Api(format!("The dump arrived garbled: {e}")))?,
+    };
+
+    match output {
+        Some(output) => {
+            std::fs::write(output, &bytes).map_err(|e| Error::Io(e.to_string()))?;
+            eprintln!(
+                "📦 Dumped {} to {} ({} bytes).",
+                database.purple(),
+                output.purple(),
+                bytes.len()
+            );
+        }
+        None => {
+            let mut stdout = tokio::io::stdout();
+            stdout.write_all(&bytes).await?;
+            stdout.flush().await?;
+        }
+    }
+    Ok(())
+}
+
+async fn restore(
+    client: &Client,
+    project: &str,
+    database: &str,
+    file: &str,
+    yes: bool,
+) -> Result<()> {
+    let bytes = std::fs::read(file).map_err(|e| Error::Io(format!("{file}: {e}")))?;
+    let body = if bytes.starts_with(SQLITE_HEADER) {
+        DatabaseDumpDto {
+            format: DatabaseDumpDtoFormat::Sqlite,
+            content: base64::engine::general_purpose::STANDARD.encode(&bytes),
+        }
+    } else {
+        DatabaseDumpDto {
+            format: DatabaseDumpDtoFormat::Sql,
+            content: String::from_utf8(bytes).map_err(|_| {
+                Error::Command(format!("{file} is neither SQL text nor a SQLite file."))
+            })?,
+        }
+    };
+
+    if !yes
+        && !Confirm::new(&format!(
+            "Replace everything in {database} with {file}? Writes since the dump are lost."
+        ))
+        .with_default(false)
+        .prompt()
+        .map_err(|e| Error::Command(e.to_string()))?
+    {
+        return Ok(());
+    }
+
+    let restored = client
+        .restore_database()
+        .project(project)
+        .name(database)
+        .body(body)
+        .send()
+        .await
+        .map_err(progenitor_error)?
+        .into_inner();
+    println!(
+        "♻️  Restored {} from {} ({} bytes, epoch {}).",
+        database.purple(),
+        file.purple(),
+        restored.size_bytes,
+        restored.epoch
+    );
+    Ok(())
+}
@@ -0,0 +1,370 @@
+//! Inspect and operate a project's workflow runs from the terminal: the
+//! listings, timelines and controls the dashboard's run view has, for
+//! on-call work without a browser.
+
+use std::path::Path;
+
+use actias_worker_core::platform::workflow::{Entry, EntryKind};
+
+use colored::{ColoredString, Colorize};
+use inquire::Confirm;
+use prettytable::{Table, row};
+use serde_json::Value;
+
+use crate::{
+    client::{
+        Client,
+        types::{RunCancelDto, RunSignalDto, WorkflowJournalRowDto},
+    },
+    commands::WorkflowOperations,
+    errors::{Error, Result, progenitor_error},
+    replay,
+    script::ScriptConfig,
+    util::{follow_journal, format_ms, get_dir, print_json},
+};
+
+/// Handle workflow command
+pub async fn handle(
+    client: &Client,
+    project: &str,
+    json: bool,
+    operation: &WorkflowOperations,
+) -> Result<()> {
+    match operation {
+        WorkflowOperations::List { definition, status } => {
+            let mut request = client.list_runs().project(project).definition(definition);
+            if let Some(status) = status {
+                request = request.status(status);
+            }
+            let runs = request.send().await.map_err(progenitor_error)?.into_inner();
+            if json {
+                return print_json(&runs);
+            }
+            if runs.is_empty() {
+                println!("No runs of {}.", definition.purple());
+                return Ok(());
+            }
+
+            let mut table = Table::new();
+            table.add_row(row!["ID", "Status", "At", "Entries", "Started", "Updated"]);
+            for run in runs {
+                table.add_row(row![
+                    run.id,
+                    status_colored(&run.status),
+                    run.at_step,
+                    run.entries,
+                    run.started_at.map(format_ms).unwrap_or_default(),
+                    run.updated_at.map(format_ms).unwrap_or_default(),
+                ]);
+            }
+            table.printstd();
+        }
+        WorkflowOperations::Describe { run } => {
+            let (definition, id) = split_run(run)?;
+            let detail = client
+                .run_detail()
+                .project(project)
+                .definition(definition)
+                .id(id)
+                .send()
+                .await
+                .map_err(progenitor_error)?
+                .into_inner();
+            if json {
+                return print_json(&detail);
+            }
+
+            println!(
+                "🔀 {}/{} is {}",
+                definition.purple(),
+                id,
+                status_colored(&detail.status)
+            );
+            if !detail.at_step.is_empty() {
+                println!("   at {}", detail.at_step.yellow());
+            }
+            if !detail.detail.is_empty() {
+                println!("   {}", Value::Object(detail.detail.clone()));
+            }
+
+            let mut table = Table::new();
+            table.add_row(row!["Seq", "At", "Event"]);
+            for entry in detail.journal.iter().filter(|entry| entry.format > 0.0) {
+                if let Some(event) = describe_entry(entry) {
+                    table.add_row(row![entry.seq, format_ms(entry.at), event]);
+                }
+            }
+            table.printstd();
+        }
+        WorkflowOperations::Journal { run, since, follow } => {
+            let (definition, id) = split_run(run)?;
+            journal(client, project, definition, id, json, *since, *follow).await?;
+        }
+        WorkflowOperations::Signal { run, name, payload } => {
+            let (definition, id) = split_run(run)?;
+            let payload = match payload {
+                Some(text) => match serde_json::from_str(text)? {
+                    Value::Object(map) => map,
+                    _ => {
+                        return Err(Error::Command(
+                            "--payload must be a JSON object.".to_string(),
+                        ));
+                    }
+                },
+                None => serde_json::Map::new(),
+            };
+
+            let state = client
+                .signal()
+                .project(project)
+                .definition(definition)
+                .id(id)
+                .body(RunSignalDto::builder().name(name.clone()).payload(payload))
+                .send()
+                .await
+                .map_err(progenitor_error)?
+                .into_inner();
+            if json {
+                return print_json(&state);
+            }
+            println!(
+                "📨 Sent {} to {}/{}.",
+                name.yellow(),
+                definition.purple(),
+                id
+            );
+        }
+        WorkflowOperations::Cancel { run, reason, yes } => {
+            let (definition, id) = split_run(run)?;
+            if !yes
+                && !Confirm::new(&format!("Cancel {run} and every child it started?"))
+                    .with_default(false)
+                    .prompt()
+                    .map_err(|e| Error::Command(e.to_string()))?
+            {
+                return Ok(());
+            }
+
+            let state = client
+                .cancel()
+                .project(project)
+                .definition(definition)
+                .id(id)
+                .body(RunCancelDto::builder().reason(reason.clone()))
+                .send()
+                .await
+                .map_err(progenitor_error)?
+                .into_inner();
+            if json {
+                return print_json(&state);
+            }
+            println!("🛑 Cancelled {}/{}.", definition.purple(), id);
+        }
+        WorkflowOperations::Resume { run } => {
+            let (definition, id) = split_run(run)?;
+            let state = client
+                .resume()
+                .project(project)
+                .definition(definition)
+                .id(id)
+                .send()
+                .await
+                .map_err(progenitor_error)?
+                .into_inner();
+            if json {
+                return print_json(&state);
+            }
+            println!("▶️ Resumed {}/{}.", definition.purple(), id);
+        }
+        WorkflowOperations::Replay { run, revision } => {
+            let (definition, id) = split_run(run)?;
+            let script_path = get_dir(revision, false, false).map_err(Error::Io)?;
+            let config = ScriptConfig::from_path(Path::new(&script_path)).map_err(Error::Script)?;
+
+            let rows = client
+                .run_journal()
+                .project(project)
+                .definition(definition)
+                .id(id)
+                .send()
+                .await
+                .map_err(progenitor_error)?
+                .into_inner();
+            // Archived generations are history the run never replays.
+            let entries = rows
+                .into_iter()
+                .filter(|row| row.format > 0.0)
+                .map(entry)
+                .collect::<Result<Vec<_>>>()?;
+            if entries.is_empty() {
+                return Err(Error::NotFound(format!("Run {run} has not started.")));
+            }
+
+            let report = replay::replay(&config, run, &entries)
+                .await
+                .map_err(|error| Error::Command(format!("{run} diverges: {error}")))?;
+            if json {
+                return print_json(&report);
+            }
+            println!(
+                "✅ {} replays {} journal rows of {}/{} to {}.",
+                script_path.display(),
+                report["replayed"].to_string().yellow(),
+                definition.purple(),
+                id,
+                report["at"].as_str().unwrap_or_default().yellow()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Prints the journal after `since`, and with `follow` keeps asking for
+/// rows past the last printed sequence number until ctrl-c. The journal
+/// read starts AT the sequence number it is given, so each ask is one
+/// past the cursor. Rows of generations continue-as-new archived are
+/// printed dimmed.
+async fn journal(
+    client: &Client,
+    project: &str,
+    definition: &str,
+    id: &str,
+    json: bool,
+    since: i64,
+    follow: bool,
+) -> Result<()> {
+    let printed = follow_journal(
+        since,
+        follow,
+        |cursor| async move {
+            Ok::<_, Error>(
+                client
+                    .run_journal()
+                    .project(project)
+                    .definition(definition)
+                    .id(id)
+                    .since((cursor + 1) as f64)
+                    .send()
+                    .await
+                    .map_err(progenitor_error)?
+                    .into_inner(),
+            )
+        },
+        |row: &WorkflowJournalRowDto| {
+            if json {
+                // One object per line, so a follower pipes into jq.
+                println!("{}", serde_json::to_string(row)?);
+            } else {
+                let line = format!(
+                    "{} {} {} {}",
+                    row.seq,
+                    format_ms(row.at),
+                    row.kind,
+                    Value::Object(row.data.clone())
+                );
+                if row.format < 0.0 {
+                    println!("{}", line.bright_black());
+                } else {
+                    println!("{line}");
+                }
+            }
+            Ok(row.seq as i64)
+        },
+    )
+    .await?;
+
+    if !printed && !json {
+        println!("No journal rows in {definition}/{id} after {since}.");
+    }
+    Ok(())
+}
+
+/// Splits `<definition>/<id>` at its one slash; `start` refuses ids
+/// containing another.
+fn split_run(run: &str) -> Result<(&str, &str)> {
+    run.split_once('/')
+        .filter(|(definition, id)| !definition.is_empty() && !id.is_empty())
+        .ok_or_else(|| Error::Command(format!("Expected <definition>/<id>, got {run}.")))
+}
+
+/// A downloaded row as the engine reads it.
+fn entry(row: WorkflowJournalRowDto) -> Result<Entry> {
+    let kind: EntryKind =
+        serde_json::from_value(Value::String(row.kind.clone())).map_err(|_| {
+            Error::Command(format!(
+                "Journal row {} has unknown kind {}.",
+                row.seq, row.kind
+            ))
+        })?;
+    Ok(Entry {
+        seq: row.seq as i64,
+        at: row.at as i64,
+        kind,
+        data: Value::Object(row.data),
+        format: row.format as i64,
+    })
+}
+
+/// One timeline line for a journal row; ambient captures are noise here.
+fn describe_entry(entry: &WorkflowJournalRowDto) -> Option<ColoredString> {
+    let field = |key: &str| match entry.data.get(key) {
+        Some(Value::String(text)) => text.clone(),
+        Some(Value::Null) | None => String::new(),
+        Some(other) => other.to_string(),
+    };
+    let event = match entry.kind.as_str() {
+        "AMBIENT" => return None,
+        "STARTED" => format!("started {}", field("input")).normal(),
+        "INTENT" => format!("step {} attempt {}", field("step"), field("attempt")).normal(),
+        "RESULT" => format!("step {} done", field("step")).green(),
+        "FAILED" => {
+            let line = format!(
+                "step {} attempt {} failed: {}",
+                field("step"),
+                field("attempt"),
+                field("error")
+            );
+            if entry.data.get("final") == Some(&Value::Bool(true)) {
+                line.red()
+            } else {
+                line.yellow()
+            }
+        }
+        "TIMER" => match entry.data.get("for") {
+            Some(Value::Null) | None => format!("sleeping until {}", due(entry)).normal(),
+            Some(gate) => format!("awaiting {gate} until {}", due(entry)).normal(),
+        },
+        "SIGNAL" => format!("signal {}", field("name")).cyan(),
+        "UPDATE" => format!("update {}", field("name")).cyan(),
+        "CHILD" => format!("child {}/{}", field("definition"), field("child")).normal(),
+        "PATCH" => format!("patched {}", field("id")).normal(),
+        "COMPENSATION" => match field("phase").as_str() {
+            "intent" => format!("undoing step {}", field("step")).normal(),
+            "failed" => format!("undo of step {} failed: {}", field("step"), field("error")).red(),
+            _ => format!("step {} undone", field("step")).yellow(),
+        },
+        "CANCEL" => format!("cancelled: {}", field("reason")).red(),
+        "COMPLETED" => "completed".green(),
+        other => other.normal(),
+    };
+    Some(event)
+}
+
+fn due(entry: &WorkflowJournalRowDto) -> String {
+    entry
+        .data
+        .get("due_ms")
+        .and_then(Value::as_f64)
+        .map(format_ms)
+        .unwrap_or_default()
+}
+
+fn status_colored(status: &str) -> ColoredString {
+    match status {
+        "failed" | "cancelled" => status.red(),
+        "completed" => status.green(),
+        "awaiting" | "sleeping" => status.yellow(),
+        _ => status.normal(),
+    }
+}
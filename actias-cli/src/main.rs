@@ -5,6 +5,7 @@ mod commands;
 mod errors;
 mod gateway;
 mod handlers;
+mod replay;
 mod router;
 mod script;
 mod settings;
@@ -43,9 +44,13 @@ async fn run() -> errors::Result<()> {
         }
         Commands::Sql {
             ref database,
-            ref sub,
+            sub:
+                commands::SqlOperations::Create {
+                    ref name,
+                    ref directory,
+                },
         } => {
-            return handlers::sql::handle(database, sub);
+            return handlers::sql::create(database, name, directory);
         }
         _ => {}
     }
@@ -0,0 +1,184 @@
+//! `actias wf replay`: a production run's journal, replayed against the
+//! working tree. The run is rebuilt in a scratch file and replayed
+//! read-only by the same engine a worker uses, with the determinism
+//! source fed from the recorded rows, so the first place local code
+//! disagrees with history (a different step, order or ambient read) is
+//! reported before the change ships.
+
+use std::sync::Arc;
+
+use actias_worker_core::platform::workflow::{self, Entry, WfShared};
+use actias_worker_core::runtime::{ActiasRuntime, VmProfile};
+
+use crate::script::ScriptConfig;
+use crate::testing;
+
+/// Replays `entries`, the live generation of run `name`
+/// (`<definition>/<id>`), on the project's code. Step bodies never run:
+/// recorded results replay and the live edge halts, so kv and secrets
+/// are the test fakes and nothing leaves the machine.
+///
+/// # Errors
+/// The divergence, naming the journal row the replay stopped on, or why
+/// the project could not load.
+pub async fn replay(
+    config: &ScriptConfig,
+    name: &str,
+    entries: &[Entry],
+) -> Result<serde_json::Value, String> {
+    let prepared = testing::prepare(config)?;
+    let client = testing::serve_fake_kv(testing::FakeKv::default()).await?;
+    let secret_client = testing::serve_fake_secrets(testing::test_secrets(config)?).await?;
+
+    let dir = tempfile::tempdir().map_err(|e| e.to_string())?;
+    let file = dir.path().join("replay.db");
+    let mut storage = actias_worker_core::storage::SqliteStorage::open(&file)?;
+    workflow::ensure_schema(&mut storage)?;
+    workflow::restore_journal(&mut storage, entries)?;
+
+    let pins = workflow::SecretPins::load(&file)?;
+    let shared = Arc::new(WfShared::default());
+    let vm = ActiasRuntime::with_profile(
+        prepared,
+        client,
+        actias_worker_core::egress::EgressClient::new(
+            actias_worker_core::egress::EgressPolicy::new([], false),
+        )
+        .map_err(|e| e.to_string())?,
+        None,
+        Some(secret_client),
+        None,
+        VmProfile::Workflow {
+            source: shared.clone(),
+            secret_pins: Some(Arc::new(pins)),
+        },
+    )
+    .await
+    .map_err(|e| format!("the entry point failed: {e}"))?;
+    vm.set_app_data(shared);
+
+    let handle = actias_worker_core::objects::spawn_object_task(
+        vm,
+        actias_worker_core::objects::TaskOptions {
+            storage: Some(storage),
+            ..Default::default()
+        },
+    );
+    handle
+        .call(
+            "__dispatch",
+            serde_json::json!({
+                "class": actias_common::classes::WORKFLOW_CLASS,
+                "name": name,
+                "method": "replay",
+                "args": [],
+                "chain": [format!("replay/__workflow/{name}")],
+            }),
+        )
+        .await
+        .map_err(|e| format!("{e:#}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actias_worker_core::platform::workflow::EntryKind;
+
+    fn project(dir: &std::path::Path, main: &str) -> ScriptConfig {
+        std::fs::write(dir.join("main.lua"), main).expect("main");
+        let mut config: ScriptConfig = serde_json::from_str(
+            r#"{"id":"00000000-0000-0000-0000-000000000000",
+                "entryPoint":"main.lua","includes":["**/*.lua"],"ignore":[]}"#,
+        )
+        .expect("config parses");
+        config.project_path = Some(dir.to_path_buf());
+        config
+    }
+
+    fn row(seq: i64, kind: EntryKind, data: serde_json::Value) -> Entry {
+        Entry {
+            seq,
+            at: 1_700_000_000_000 + seq,
+            kind,
+            data,
+            format: workflow::ENTRY_FORMAT,
+        }
+    }
+
+    /// A run that charged, read the clock, and parked on a sleep.
+    fn journal() -> Vec<Entry> {
+        vec![
+            row(
+                1,
+                EntryKind::Started,
+                serde_json::json!({ "input": { "id": "o1" }, "seed": 7, "revision": "r1" }),
+            ),
+            row(
+                2,
+                EntryKind::Intent,
+                serde_json::json!({ "step": "charge", "attempt": 1 }),
+            ),
+            row(
+                3,
+                EntryKind::Result,
+                serde_json::json!({ "step": "charge", "value": { "ok": true }, "attempt": 1 }),
+            ),
+            row(
+                4,
+                EntryKind::Ambient,
+                serde_json::json!({ "tag": "time", "value": 1_700_000_000 }),
+            ),
+            row(
+                5,
+                EntryKind::Timer,
+                serde_json::json!({ "due_ms": 1_700_000_600_000_i64, "for": null }),
+            ),
+        ]
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn matching_code_replays_to_the_live_edge() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let config = project(
+            dir.path(),
+            r#"
+            workflow "order" (function(wf, order)
+                wf:step("charge", function() error("never runs offline") end)
+                local now = os.time()
+                wf:sleep("10m")
+                return { at = now }
+            end)
+            "#,
+        );
+
+        let report = replay(&config, "order/o1", &journal())
+            .await
+            .expect("replays");
+        assert_eq!(report["replayed"], 5, "{report}");
+        assert_eq!(report["at"], "sleep");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn a_reordered_ambient_read_names_its_row() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let config = project(
+            dir.path(),
+            r#"
+            workflow "order" (function(wf, order)
+                local now = os.time()
+                wf:step("charge", function() error("never runs offline") end)
+                wf:sleep("10m")
+                return { at = now }
+            end)
+            "#,
+        );
+
+        let diverged = replay(&config, "order/o1", &journal())
+            .await
+            .expect_err("the clock read moved before the step");
+        assert!(
+            diverged.contains("journal row 2") && diverged.contains("ambient 'time'"),
+            "{diverged}"
+        );
+    }
+}
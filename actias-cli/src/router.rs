@@ -41,6 +41,18 @@ impl Router {
             Commands::Secret { project, sub } => {
                 handlers::secrets::handle(&self.client, &project, &sub).await
             }
+            Commands::Queue {
+                name,
+                project,
+                json,
+                sub,
+            } => handlers::queues::handle(&self.client, &project, &name, json, &sub).await,
+            Commands::Workflow { project, json, sub } => {
+                handlers::workflows::handle(&self.client, &project, json, &sub).await
+            }
+            Commands::Cron { project, json, sub } => {
+                handlers::cron::handle(&self.client, &project, json, &sub).await
+            }
             Commands::Tokens { project, sub } => {
                 handlers::tokens::handle(&self.client, &project, &sub).await
             }
@@ -48,8 +60,11 @@ impl Router {
             Commands::Projects { page } => self.handle_list_projects(page).await,
             Commands::Project { id, sub } => self.handle_project(id, sub).await,
             Commands::Script { id, sub } => self.handle_script(id, sub).await,
+            Commands::Sql { database, sub } => {
+                handlers::sql::handle(&self.client, &database, &sub).await
+            }
             // Handled before authentication in main; unreachable here.
-            Commands::Check { .. } | Commands::Test { .. } | Commands::Sql { .. } => Ok(()),
+            Commands::Check { .. } | Commands::Test { .. } => Ok(()),
         }
     }
 
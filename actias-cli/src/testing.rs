@@ -40,7 +40,7 @@ type PairKey = (String, String, String);
 /// The kv service over a hash map: the same wire surface, none of the
 /// storage. One store lives exactly as long as one test file.
 #[derive(Default, Clone)]
-struct FakeKv {
+pub(crate) struct FakeKv {
     pairs: Arc<Mutex<HashMap<PairKey, proto::Pair>>>,
 }
 
@@ -236,7 +236,7 @@ impl secret_proto::secret_service_server::SecretService for FakeSecrets {
 
 /// Serves one fake secret store on a loopback port; same lifetime story
 /// as [`serve_fake_kv`].
-async fn serve_fake_secrets(
+pub(crate) async fn serve_fake_secrets(
     values: HashMap<String, String>,
 ) -> Result<SecretServiceClient<tonic::transport::Channel>, String> {
     let listener = tokio::net::TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], 0)))
@@ -261,7 +261,7 @@ async fn serve_fake_secrets(
 
 /// Serves one fake store on a loopback port and hands back a connected
 /// client; the server task dies with the process.
-async fn serve_fake_kv(
+pub(crate) async fn serve_fake_kv(
     store: FakeKv,
 ) -> Result<KvServiceClient<tonic::transport::Channel>, String> {
     let listener = tokio::net::TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], 0)))
@@ -283,7 +283,7 @@ async fn serve_fake_kv(
 /// Builds the prepared revision the runtime executes: the project's bundle
 /// with its capability contract derived from the code, exactly as publish
 /// stores it.
-fn prepare(config: &ScriptConfig) -> Result<Arc<PreparedRevision>, String> {
+pub(crate) fn prepare(config: &ScriptConfig) -> Result<Arc<PreparedRevision>, String> {
     let bundle = config.to_bundle()?;
 
     let mut files = Vec::with_capacity(bundle.files.len());
@@ -327,6 +327,41 @@ fn prepare(config: &ScriptConfig) -> Result<Arc<PreparedRevision>, String> {
                 queues: declared.queues,
                 workflows: declared.workflows,
                 workflow_steps: declared.workflow_steps,
+                queue_policies: declared
+                    .queue_policies
+                    .into_iter()
+                    .map(
+                        |policy| actias_worker_core::proto::script_service::QueuePolicy {
+                            queue: policy.queue,
+                            max_attempts: policy.max_attempts,
+                            backoff_ms: policy.backoff_ms,
+                            max_backoff_ms: policy.max_backoff_ms,
+                        },
+                    )
+                    .collect(),
+                topics: declared.topics,
+                workflow_schedules: declared
+                    .workflow_schedules
+                    .into_iter()
+                    .map(
+                        |schedule| actias_worker_core::proto::script_service::WorkflowSchedule {
+                            workflow: schedule.workflow,
+                            schedule: schedule.schedule,
+                            overlap: schedule.overlap,
+                        },
+                    )
+                    .collect(),
+                workflow_retentions: declared
+                    .workflow_retentions
+                    .into_iter()
+                    .map(
+                        |retention| actias_worker_core::proto::script_service::WorkflowRetention {
+                            workflow: retention.workflow,
+                            retention_ms: retention.retention_ms,
+                            archive: retention.archive,
+                        },
+                    )
+                    .collect(),
             }),
         }),
         ..Default::default()
@@ -359,7 +394,7 @@ fn test_files(config: &ScriptConfig) -> Result<Vec<PathBuf>, String> {
 }
 
 /// Secret values for tests, from `tests/secrets.json` when present.
-fn test_secrets(config: &ScriptConfig) -> Result<HashMap<String, String>, String> {
+pub(crate) fn test_secrets(config: &ScriptConfig) -> Result<HashMap<String, String>, String> {
     let Some(root) = config.project_path.as_ref() else {
         return Ok(HashMap::new());
     };
@@ -522,6 +557,45 @@ fn install_workflow_testing(
                             },
                         )?,
                     )?;
+                    let querier = dispatch.clone();
+                    wf.set(
+                        "query",
+                        lua.create_async_function(
+                            move |lua,
+                                  (_this, name, args): (
+                                mlua::Table,
+                                String,
+                                mlua::Variadic<mlua::Value>,
+                            )| {
+                                let querier = querier.clone();
+                                async move {
+                                    let mut arguments = vec![serde_json::json!(name)];
+                                    for value in args {
+                                        arguments.push(lua.from_value(value)?);
+                                    }
+                                    let answer =
+                                        querier("query", serde_json::json!(arguments)).await?;
+                                    lua.to_value(&answer)
+                                }
+                            },
+                        )?,
+                    )?;
+                    let updater = dispatch.clone();
+                    wf.set(
+                        "update",
+                        lua.create_async_function(
+                            move |lua, (_this, name, payload): (mlua::Table, String, mlua::Value)| {
+                                let updater = updater.clone();
+                                async move {
+                                    let payload: serde_json::Value = lua.from_value(payload)?;
+                                    let answer =
+                                        updater("update", serde_json::json!([name, payload]))
+                                            .await?;
+                                    lua.to_value(&answer)
+                                }
+                            },
+                        )?,
+                    )?;
                     Ok(wf)
                 }
             },
@@ -2,14 +2,18 @@ use std::{
     fs,
     io::Write,
     path::{Path, PathBuf},
+    time::Duration,
 };
 
 use base64::Engine;
 use colored::Colorize;
 use include_dir::{Dir, include_dir};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-use crate::{client::types::RevisionFullDto, script::ScriptConfig};
+use crate::{client::types::RevisionFullDto, errors, script::ScriptConfig};
+
+/// How often a `--follow` asks for rows past its cursor.
+pub const FOLLOW_INTERVAL: Duration = Duration::from_secs(2);
 
 /// Convert an API error to a string which can be used to log.
 pub fn progenitor_error(error: progenitor::progenitor_client::Error) -> String {
@@ -179,3 +183,52 @@ pub fn copy_dir_all(src: impl AsRef<Path>, dst: impl AsRef<Path>) -> std::io::Re
     }
     Ok(())
 }
+
+/// Prints a value as indented json, for `--json`.
+pub fn print_json(value: &impl Serialize) -> errors::Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+/// Renders a unix-millisecond timestamp in local time.
+pub fn format_ms(ms: f64) -> String {
+    chrono::DateTime::from_timestamp_millis(ms as i64)
+        .map(|at| {
+            at.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+        })
+        .unwrap_or_default()
+}
+
+/// Prints a journal from `since`, and with `follow` keeps asking for
+/// rows past the last printed sequence number every [`FOLLOW_INTERVAL`]
+/// until ctrl-c. `fetch` gets the cursor, the last sequence number seen;
+/// `print` prints one row and returns its sequence number. Without
+/// `follow`, answers whether anything was printed.
+pub async fn follow_journal<T, F>(
+    since: i64,
+    follow: bool,
+    mut fetch: impl FnMut(i64) -> F,
+    mut print: impl FnMut(&T) -> errors::Result<i64>,
+) -> errors::Result<bool>
+where
+    F: Future<Output = errors::Result<Vec<T>>>,
+{
+    let mut cursor = since;
+    loop {
+        let rows = fetch(cursor).await?;
+        for row in &rows {
+            cursor = cursor.max(print(row)?);
+        }
+
+        if !follow {
+            return Ok(!rows.is_empty());
+        }
+
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => return Ok(true),
+            _ = tokio::time::sleep(FOLLOW_INTERVAL) => {}
+        }
+    }
+}
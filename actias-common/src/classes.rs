@@ -9,7 +9,8 @@
 pub const QUEUE_CLASS: &str = "__queue";
 
 /// The built-in class behind `database "name"`: the sql product face over
-/// object storage.
+/// object storage, whose alarm fans committed row changes out to
+/// `on "database:<name>"` listeners.
 pub const DATABASE_CLASS: &str = "__database";
 
 /// The built-in class behind `on "cron:<expr>"`: one instance per cron
@@ -19,3 +20,13 @@ pub const CRON_CLASS: &str = "__cron";
 /// The built-in class behind `workflow "name"`: one instance per run,
 /// its sqlite holding the replay journal; the mailbox is append order.
 pub const WORKFLOW_CLASS: &str = "__workflow";
+
+/// The built-in class behind `topic "name"`: the published log, appended
+/// once per publish and forwarded to every subscription from its alarm.
+pub const TOPIC_CLASS: &str = "__topic";
+
+/// One script's durable subscription to a topic, named
+/// `<topic>/<subscriber script id>`, or to a database's changes, named
+/// `database/<database>/<subscriber script id>`: a queue in everything
+/// but name, so each subscriber retries and dead-letters on its own.
+pub const SUBSCRIPTION_CLASS: &str = "__subscription";
@@ -56,6 +56,249 @@ pub struct Declarations {
     /// appear as they execute.
     #[serde(default)]
     pub workflow_steps: Vec<String>,
+    /// Delivery policies declared with `queue "name" { ... }`; only a
+    /// queue's consumer may declare one, since its revision is the one
+    /// delivery runs.
+    #[serde(default)]
+    pub queue_policies: Vec<QueuePolicy>,
+    /// Topics declared with `topic "name"`; subscriptions are the
+    /// `topic:<name>` events.
+    #[serde(default)]
+    pub topics: Vec<String>,
+    /// Schedules declared with `workflow "name" { schedule = ... }`.
+    #[serde(default)]
+    pub workflow_schedules: Vec<WorkflowSchedule>,
+    /// How long settled runs are kept, declared with `workflow "name" {
+    /// retention = ... }`; definitions without one keep runs forever.
+    #[serde(default)]
+    pub workflow_retentions: Vec<WorkflowRetention>,
+}
+
+/// One queue's declared delivery policy, durations already in
+/// milliseconds; an absent field takes the node's default.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct QueuePolicy {
+    pub queue: String,
+    #[serde(default)]
+    pub max_attempts: Option<i64>,
+    #[serde(default)]
+    pub backoff_ms: Option<i64>,
+    #[serde(default)]
+    pub max_backoff_ms: Option<i64>,
+}
+
+impl QueuePolicy {
+    /// Reads `{ max_attempts = 10, backoff = "5s", max_backoff = "1h" }`;
+    /// unknown keys and nonsense values fail, so a typo dies at publish
+    /// instead of silently keeping the defaults.
+    fn from_table(queue: &str, table: &mlua::Table) -> Result<Self, String> {
+        let mut policy = QueuePolicy {
+            queue: queue.to_owned(),
+            ..Default::default()
+        };
+
+        for pair in table.pairs::<String, mlua::Value>() {
+            let (key, value) = pair.map_err(|e| format!("Queue '{queue}' policy: {e}"))?;
+            match key.as_str() {
+                "max_attempts" => {
+                    // Luau numbers are doubles; a whole one is a count.
+                    let attempts = match value {
+                        mlua::Value::Integer(attempts) => Some(attempts),
+                        mlua::Value::Number(attempts) if attempts.fract() == 0.0 => {
+                            Some(attempts as i64)
+                        }
+                        _ => None,
+                    }
+                    .filter(|attempts| *attempts >= 1)
+                    .ok_or_else(|| {
+                        format!("Queue '{queue}': max_attempts must be a whole number >= 1.")
+                    })?;
+                    policy.max_attempts = Some(attempts);
+                }
+                "backoff" => {
+                    policy.backoff_ms =
+                        Some(policy_duration(&format!("Queue '{queue}'"), &key, &value)?)
+                }
+                "max_backoff" => {
+                    policy.max_backoff_ms =
+                        Some(policy_duration(&format!("Queue '{queue}'"), &key, &value)?)
+                }
+                other => {
+                    return Err(format!(
+                        "Queue '{queue}' has no policy field '{other}'; \
+                         expected max_attempts, backoff or max_backoff."
+                    ));
+                }
+            }
+        }
+
+        if let (Some(backoff), Some(ceiling)) = (policy.backoff_ms, policy.max_backoff_ms)
+            && ceiling < backoff
+        {
+            return Err(format!(
+                "Queue '{queue}': max_backoff must not be shorter than backoff."
+            ));
+        }
+        Ok(policy)
+    }
+}
+
+/// What a schedule fire does while the previous scheduled run is still
+/// going: drop the fire, hold one to start when it ends, or start anyway.
+pub const OVERLAP_POLICIES: [&str; 3] = ["skip", "buffer-one", "allow"];
+
+/// One workflow's start schedule: a cron expression and its overlap
+/// policy, `skip` unless declared.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct WorkflowSchedule {
+    pub workflow: String,
+    pub schedule: String,
+    pub overlap: String,
+}
+
+/// How long one workflow definition keeps its completed and cancelled
+/// runs, and whether the journal is archived before the purge.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct WorkflowRetention {
+    pub workflow: String,
+    pub retention_ms: i64,
+    #[serde(default)]
+    pub archive: bool,
+}
+
+/// Reads `{ schedule = "0 3 * * *", overlap = "skip", retention = "30d",
+/// archive = true }`; like a queue policy, unknown keys and nonsense
+/// values fail the publish. Every field is optional, but overlap means
+/// nothing without a schedule and archive nothing without a retention.
+fn workflow_options(
+    workflow: &str,
+    table: &mlua::Table,
+) -> Result<(Option<WorkflowSchedule>, Option<WorkflowRetention>), String> {
+    let mut schedule = None;
+    let mut overlap = None;
+    let mut retention_ms = None;
+    let mut archive = None;
+
+    for pair in table.pairs::<String, mlua::Value>() {
+        let (key, value) = pair.map_err(|e| format!("Workflow '{workflow}' options: {e}"))?;
+        let text = match &value {
+            mlua::Value::String(text) => text.to_str().ok().map(|text| text.to_string()),
+            _ => None,
+        };
+        match key.as_str() {
+            "schedule" => {
+                let expr = text.ok_or_else(|| {
+                    format!("Workflow '{workflow}': schedule must be a cron expression.")
+                })?;
+                validate_cron(&expr)?;
+                schedule = Some(expr.trim().to_owned());
+            }
+            "overlap" => {
+                overlap = Some(
+                    text.filter(|policy| OVERLAP_POLICIES.contains(&policy.as_str()))
+                        .ok_or_else(|| {
+                            format!(
+                                "Workflow '{workflow}': overlap must be one of {}.",
+                                OVERLAP_POLICIES.join(", ")
+                            )
+                        })?,
+                );
+            }
+            "retention" => {
+                retention_ms = Some(policy_duration(
+                    &format!("Workflow '{workflow}'"),
+                    &key,
+                    &value,
+                )?);
+            }
+            "archive" => match value {
+                mlua::Value::Boolean(flag) => archive = Some(flag),
+                _ => {
+                    return Err(format!(
+                        "Workflow '{workflow}': archive must be true or false."
+                    ));
+                }
+            },
+            other => {
+                return Err(format!(
+                    "Workflow '{workflow}' has no option '{other}'; \
+                     expected schedule, overlap, retention or archive."
+                ));
+            }
+        }
+    }
+
+    if overlap.is_some() && schedule.is_none() {
+        return Err(format!(
+            "Workflow '{workflow}': overlap without a schedule."
+        ));
+    }
+    if archive.is_some() && retention_ms.is_none() {
+        return Err(format!(
+            "Workflow '{workflow}': archive without a retention."
+        ));
+    }
+    Ok((
+        schedule.map(|schedule| WorkflowSchedule {
+            workflow: workflow.to_owned(),
+            schedule,
+            overlap: overlap.unwrap_or_else(|| "skip".to_owned()),
+        }),
+        retention_ms.map(|retention_ms| WorkflowRetention {
+            workflow: workflow.to_owned(),
+            retention_ms,
+            archive: archive.unwrap_or(false),
+        }),
+    ))
+}
+
+/// A duration written the way scripts write them: "500ms", "30s", "10m",
+/// "24h", "7d", or a bare number of seconds. The worker parses every
+/// runtime duration with this too.
+pub fn parse_duration_ms(raw: &str) -> Result<i64, String> {
+    let raw = raw.trim();
+    if let Ok(seconds) = raw.parse::<f64>() {
+        return Ok((seconds * 1000.0) as i64);
+    }
+
+    let split = raw
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .ok_or_else(|| format!("'{raw}' is not a duration."))?;
+    let (number, unit) = raw.split_at(split);
+    let number: f64 = number
+        .parse()
+        .map_err(|_| format!("'{raw}' is not a duration."))?;
+
+    let factor = match unit.trim() {
+        "ms" => 1.0,
+        "s" => 1000.0,
+        "m" => 60.0 * 1000.0,
+        "h" => 3600.0 * 1000.0,
+        "d" => 86400.0 * 1000.0,
+        other => return Err(format!("Unknown duration unit '{other}'.")),
+    };
+
+    Ok((number * factor) as i64)
+}
+
+/// A policy duration: a [`parse_duration_ms`] string or a number of
+/// seconds, at least a millisecond. `subject` names the declaration in
+/// the error, `Queue 'jobs'` say.
+fn policy_duration(subject: &str, field: &str, value: &mlua::Value) -> Result<i64, String> {
+    let invalid = || format!("{subject}: {field} must be a duration like \"5s\".");
+    let ms = match value {
+        mlua::Value::Integer(seconds) => seconds.saturating_mul(1000),
+        mlua::Value::Number(seconds) => (seconds * 1000.0) as i64,
+        mlua::Value::String(text) => {
+            let text = text.to_str().map_err(|_| invalid())?;
+            parse_duration_ms(&text).map_err(|_| invalid())?
+        }
+        _ => return Err(invalid()),
+    };
+    if ms < 1 {
+        return Err(invalid());
+    }
+    Ok(ms)
 }
 
 /// Ambient globals a script may touch at its top level; each becomes an
@@ -144,6 +387,30 @@ pub fn extract(files: HashMap<String, String>, entry_point: &str) -> Result<Decl
 
     let mut declarations = recorded.lock().expect("no other holder").clone();
     declarations.workflow_steps = step_names;
+
+    // Delivery runs the consumer's revision, so only its policy can ever
+    // apply; one declared anywhere else would be silently ignored.
+    for policy in &declarations.queue_policies {
+        let consumes = format!("queue:{}", policy.queue);
+        if !declarations.events.contains(&consumes) {
+            return Err(format!(
+                "Queue '{}' declares a delivery policy but this script does not consume it; \
+                 declare the policy next to on \"{consumes}\".",
+                policy.queue
+            ));
+        }
+    }
+    let mut seen = Vec::new();
+    for policy in &declarations.queue_policies {
+        if seen.contains(&policy.queue) {
+            return Err(format!(
+                "Queue '{}' declares its delivery policy more than once.",
+                policy.queue
+            ));
+        }
+        seen.push(policy.queue.clone());
+    }
+
     Ok(declarations)
 }
 
@@ -209,6 +476,9 @@ fn install_declarations(lua: &Lua, recorded: &Arc<Mutex<Declarations>>) -> mlua:
         })?,
     )?;
 
+    // `queue "name"` records the queue; the optional curried table,
+    // `queue "name" { max_attempts = 10 }`, records its delivery policy
+    // and hands back the same handle stub.
     let queue_recorded = recorded.clone();
     lua.globals().set(
         "queue",
@@ -217,6 +487,41 @@ fn install_declarations(lua: &Lua, recorded: &Arc<Mutex<Declarations>>) -> mlua:
                 .lock()
                 .expect("no other holder")
                 .queues
+                .push(name.clone());
+
+            let handle = stub(lua)?;
+            let meta = handle.metatable().expect("stubs always carry a metatable");
+            let policy_recorded = queue_recorded.clone();
+            meta.set(
+                "__call",
+                lua.create_function(move |lua, (_, options): (mlua::Value, mlua::Value)| {
+                    if let mlua::Value::Table(options) = options {
+                        let policy = QueuePolicy::from_table(&name, &options)
+                            .map_err(mlua::Error::RuntimeError)?;
+                        policy_recorded
+                            .lock()
+                            .expect("no other holder")
+                            .queue_policies
+                            .push(policy);
+                    }
+                    stub(lua)
+                })?,
+            )?;
+            Ok(handle)
+        })?,
+    )?;
+
+    // `topic "name"` records a topic the script publishes to. Subscribing
+    // is `on "topic:<name>"`, which any number of scripts may declare.
+    let topic_recorded = recorded.clone();
+    lua.globals().set(
+        "topic",
+        lua.create_function(move |lua, name: String| {
+            validate_topic_name(&name).map_err(mlua::Error::RuntimeError)?;
+            topic_recorded
+                .lock()
+                .expect("no other holder")
+                .topics
                 .push(name);
             stub(lua)
         })?,
@@ -237,10 +542,8 @@ fn install_declarations(lua: &Lua, recorded: &Arc<Mutex<Declarations>>) -> mlua:
                 .lock()
                 .expect("no other holder")
                 .workflows
-                .push(name);
-            // `workflow "name" (fn)`: the registrar takes the body and
-            // returns the handle stub callers hold.
-            lua.create_function(|lua, _body: mlua::Function| stub(lua))
+                .push(name.clone());
+            workflow_registrar(lua, name, workflow_recorded.clone())
         })?,
     )?;
 
@@ -259,21 +562,169 @@ fn install_declarations(lua: &Lua, recorded: &Arc<Mutex<Declarations>>) -> mlua:
             // not on the first request the revision ever serves.
             if let Some(expr) = event.strip_prefix("cron:") {
                 validate_cron(expr).map_err(mlua::Error::RuntimeError)?;
+            } else if let Some(name) = event.strip_prefix("topic:") {
+                validate_topic_name(name).map_err(mlua::Error::RuntimeError)?;
+            } else if let Some(name) = event.strip_prefix("database:") {
+                validate_database_event(name).map_err(mlua::Error::RuntimeError)?;
             }
             on_recorded
                 .lock()
                 .expect("no other holder")
                 .events
-                .push(event);
-            // The registrar accepts the handler and drops it; handlers are
-            // never invoked during extraction.
-            lua.create_function(|_, _: mlua::Value| Ok(()))
+                .push(event.clone());
+            registrar(lua, event)
         })?,
     )?;
 
     Ok(())
 }
 
+/// The function `workflow "name"` returns: it takes the body and returns
+/// the handle stub callers hold, or first an options table (`workflow
+/// "name" { schedule = "0 3 * * *" } (fn)`) whose schedule and retention
+/// it records.
+fn workflow_registrar(
+    lua: &Lua,
+    name: String,
+    recorded: Arc<Mutex<Declarations>>,
+) -> mlua::Result<mlua::Function> {
+    lua.create_function(move |lua, argument: mlua::Value| match argument {
+        mlua::Value::Table(options) => {
+            let (schedule, retention) =
+                workflow_options(&name, &options).map_err(mlua::Error::RuntimeError)?;
+            let mut declarations = recorded.lock().expect("no other holder");
+            if declarations
+                .workflow_schedules
+                .iter()
+                .any(|declared| declared.workflow == name)
+                || declarations
+                    .workflow_retentions
+                    .iter()
+                    .any(|declared| declared.workflow == name)
+            {
+                return Err(mlua::Error::RuntimeError(format!(
+                    "Workflow '{name}' declares its options more than once."
+                )));
+            }
+            declarations.workflow_schedules.extend(schedule);
+            declarations.workflow_retentions.extend(retention);
+            drop(declarations);
+            Ok(mlua::Value::Function(workflow_registrar(
+                lua,
+                name.clone(),
+                recorded.clone(),
+            )?))
+        }
+        _ => Ok(mlua::Value::Table(stub(lua)?)),
+    })
+}
+
+/// The function `on "<event>"` returns. It accepts the handler and drops
+/// it, since handlers are never invoked during extraction; a queue event
+/// may take an options table first (`on "queue:jobs" { batch = 50 }
+/// (fn)`), which is checked here so a bad batch fails the publish.
+fn registrar(lua: &Lua, event: String) -> mlua::Result<mlua::Function> {
+    lua.create_function(move |lua, argument: mlua::Value| match argument {
+        mlua::Value::Table(options) if event.starts_with("cron:") => {
+            validate_cron_policy(&event, &options).map_err(mlua::Error::RuntimeError)?;
+            Ok(mlua::Value::Function(registrar(lua, event.clone())?))
+        }
+        mlua::Value::Table(options) => {
+            let Some(queue) = event
+                .strip_prefix("queue:")
+                .or_else(|| event.strip_prefix("topic:"))
+                .or_else(|| event.strip_prefix("database:"))
+            else {
+                return Err(mlua::Error::RuntimeError(format!(
+                    "on \"{event}\" takes no options; only queue, topic, database and cron \
+                     events do."
+                )));
+            };
+            Batching::from_table(queue, &options).map_err(mlua::Error::RuntimeError)?;
+            Ok(mlua::Value::Function(registrar(lua, event.clone())?))
+        }
+        _ => Ok(mlua::Value::Nil),
+    })
+}
+
+/// Largest batch a consumer may declare. A batch is one vm entry holding
+/// every message in memory at once, and its handler runs under one call
+/// budget, so the ceiling keeps a typo from asking for a million.
+pub const MAX_BATCH: i64 = 100;
+
+/// How a consumer takes its messages when it opts into batches:
+/// `on "queue:jobs" { batch = 50, max_wait = "5s" } (fn)`. Publish
+/// checks it here and the worker reads it with the same code, so the two
+/// can never disagree about what a declaration means.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Batching {
+    /// Most messages handed to one listener call.
+    pub size: i64,
+    /// How long the oldest due message may wait for a batch to fill
+    /// before a short batch goes out anyway; zero sends whatever is due.
+    pub max_wait_ms: i64,
+}
+
+impl Batching {
+    /// Reads `{ batch = 50, max_wait = "5s" }`. Unknown keys and
+    /// nonsense values fail the declaration, the way a policy typo does.
+    pub fn from_table(queue: &str, table: &mlua::Table) -> Result<Self, String> {
+        let mut batching = Batching {
+            size: 1,
+            max_wait_ms: 0,
+        };
+
+        for pair in table.pairs::<String, mlua::Value>() {
+            let (key, value) = pair.map_err(|e| format!("Queue '{queue}' options: {e}"))?;
+            match key.as_str() {
+                "batch" => {
+                    // Luau numbers are doubles; a whole one is a count.
+                    batching.size = match value {
+                        mlua::Value::Integer(size) => Some(size),
+                        mlua::Value::Number(size) if size.fract() == 0.0 => Some(size as i64),
+                        _ => None,
+                    }
+                    .filter(|size| (1..=MAX_BATCH).contains(size))
+                    .ok_or_else(|| {
+                        format!(
+                            "Queue '{queue}': batch must be a whole number from 1 to {MAX_BATCH}."
+                        )
+                    })?;
+                }
+                "max_wait" => {
+                    batching.max_wait_ms =
+                        policy_duration(&format!("Queue '{queue}'"), &key, &value)?;
+                }
+                other => {
+                    return Err(format!(
+                        "Queue '{queue}' has no option '{other}'; expected batch or max_wait."
+                    ));
+                }
+            }
+        }
+
+        Ok(batching)
+    }
+}
+
+/// A topic name: non-empty and free of '/', because a subscription is
+/// addressed as `<topic>/<subscriber>`.
+fn validate_topic_name(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() || name.contains('/') {
+        return Err("A topic name is a non-empty string without '/'.".to_owned());
+    }
+    Ok(())
+}
+
+/// A database a listener watches: non-empty and free of '/', because its
+/// subscriptions are addressed as `database/<name>/<subscriber>`.
+fn validate_database_event(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() || name.contains('/') {
+        return Err("A database event names its database without '/'.".to_owned());
+    }
+    Ok(())
+}
+
 /// A cron expression scripts may schedule on: five classic fields or six
 /// with seconds; the parser wants six, so five gain a zero.
 fn validate_cron(expr: &str) -> Result<(), String> {
@@ -291,6 +742,44 @@ fn validate_cron(expr: &str) -> Result<(), String> {
         .map_err(|e| format!("'{expr}' is not a cron expression: {e}"))
 }
 
+/// What a cron fire may do about occurrences it missed.
+pub const CATCH_UP_POLICIES: [&str; 3] = ["all", "latest", "none"];
+
+/// Checks `{ tz = "Europe/Paris", catch_up = "all" }`: unknown keys,
+/// zones and policies fail, matching what the worker accepts.
+fn validate_cron_policy(event: &str, table: &mlua::Table) -> Result<(), String> {
+    for pair in table.pairs::<String, mlua::Value>() {
+        let (key, value) = pair.map_err(|e| format!("Cron '{event}' options: {e}"))?;
+        let text = match &value {
+            mlua::Value::String(text) => text.to_str().ok().map(|text| text.to_string()),
+            _ => None,
+        };
+        match key.as_str() {
+            "tz" => {
+                if text.is_none_or(|zone| zone.parse::<chrono_tz::Tz>().is_err()) {
+                    return Err(format!(
+                        "Cron '{event}': tz must be an IANA zone like \"Europe/Paris\"."
+                    ));
+                }
+            }
+            "catch_up" => {
+                if !text.is_some_and(|policy| CATCH_UP_POLICIES.contains(&policy.as_str())) {
+                    return Err(format!(
+                        "Cron '{event}': catch_up must be one of {}.",
+                        CATCH_UP_POLICIES.join(", ")
+                    ));
+                }
+            }
+            other => {
+                return Err(format!(
+                    "Cron '{event}' has no option '{other}'; expected tz or catch_up."
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Installs inert stubs for every ambient global, so top-level code that
 /// touches the platform surface runs without exercising anything.
 fn install_stubs(lua: &Lua) -> mlua::Result<()> {
@@ -381,6 +870,21 @@ mod tests {
             .collect()
     }
 
+    #[test]
+    fn durations_read_the_way_scripts_write_them() {
+        assert_eq!(parse_duration_ms("500ms").unwrap(), 500);
+        assert_eq!(parse_duration_ms("30s").unwrap(), 30_000);
+        assert_eq!(parse_duration_ms("10m").unwrap(), 600_000);
+        assert_eq!(parse_duration_ms("24h").unwrap(), 86_400_000);
+        assert_eq!(parse_duration_ms("7d").unwrap(), 604_800_000);
+        assert_eq!(parse_duration_ms("1.5s").unwrap(), 1500);
+        // A bare number is seconds.
+        assert_eq!(parse_duration_ms("2").unwrap(), 2000);
+
+        assert!(parse_duration_ms("soon").is_err());
+        assert!(parse_duration_ms("10 fortnights").is_err());
+    }
+
     #[test]
     fn declarations_are_recorded_without_running_handlers() {
         let declarations = extract(
@@ -478,6 +982,289 @@ mod tests {
         .expect("a real schedule extracts");
     }
 
+    #[test]
+    fn a_workflow_schedule_records_and_must_parse() {
+        let declarations = extract(
+            files(&[(
+                "main.lua",
+                r#"
+                workflow "nightly-report" { schedule = "0 3 * * *", overlap = "buffer-one" } (function(wf)
+                    error("bodies never run during extraction")
+                end)
+                workflow "hourly" { schedule = "0 * * * *" } (function(wf) end)
+                "#,
+            )]),
+            "main.lua",
+        )
+        .expect("extraction succeeds");
+
+        assert_eq!(declarations.workflows, vec!["nightly-report", "hourly"]);
+        assert_eq!(
+            declarations.workflow_schedules,
+            vec![
+                WorkflowSchedule {
+                    workflow: "nightly-report".to_owned(),
+                    schedule: "0 3 * * *".to_owned(),
+                    overlap: "buffer-one".to_owned(),
+                },
+                WorkflowSchedule {
+                    workflow: "hourly".to_owned(),
+                    schedule: "0 * * * *".to_owned(),
+                    overlap: "skip".to_owned(),
+                },
+            ]
+        );
+
+        let error = extract(
+            files(&[(
+                "main.lua",
+                r#"workflow "w" { schedule = "0 3 * * *", overlap = "queue" } (function() end)"#,
+            )]),
+            "main.lua",
+        )
+        .expect_err("an unknown overlap fails the pass");
+        assert!(error.contains("overlap must be one of"), "{error}");
+
+        let error = extract(
+            files(&[(
+                "main.lua",
+                r#"workflow "w" { schedule = "nightly" } (function() end)"#,
+            )]),
+            "main.lua",
+        )
+        .expect_err("a bad schedule fails the pass");
+        assert!(error.contains("cron expression"), "{error}");
+    }
+
+    #[test]
+    fn a_workflow_retention_records_and_must_parse() {
+        let declarations = extract(
+            files(&[(
+                "main.lua",
+                r#"
+                workflow "order" { retention = "30d", archive = true } (function(wf) end)
+                workflow "report" { schedule = "0 3 * * *", retention = "12h" } (function(wf) end)
+                workflow "forever" (function(wf) end)
+                "#,
+            )]),
+            "main.lua",
+        )
+        .expect("extraction succeeds");
+
+        assert_eq!(
+            declarations.workflow_retentions,
+            vec![
+                WorkflowRetention {
+                    workflow: "order".to_owned(),
+                    retention_ms: 30 * 86_400_000,
+                    archive: true,
+                },
+                WorkflowRetention {
+                    workflow: "report".to_owned(),
+                    retention_ms: 12 * 3_600_000,
+                    archive: false,
+                },
+            ]
+        );
+        assert_eq!(declarations.workflow_schedules.len(), 1);
+
+        let error = extract(
+            files(&[(
+                "main.lua",
+                r#"workflow "w" { retention = "a while" } (function() end)"#,
+            )]),
+            "main.lua",
+        )
+        .expect_err("a nonsense retention fails the pass");
+        assert!(error.contains("retention must be a duration"), "{error}");
+
+        let error = extract(
+            files(&[(
+                "main.lua",
+                r#"workflow "w" { archive = true } (function() end)"#,
+            )]),
+            "main.lua",
+        )
+        .expect_err("archive needs a retention");
+        assert!(error.contains("archive without a retention"), "{error}");
+    }
+
+    #[test]
+    fn a_consumed_queue_records_its_delivery_policy() {
+        let declarations = extract(
+            files(&[(
+                "main.lua",
+                r#"
+                local jobs = queue "jobs" { max_attempts = 10, backoff = "5s", max_backoff = "1h" }
+                local plain = queue "analytics"
+                on "queue:jobs" (function(message) end)
+                "#,
+            )]),
+            "main.lua",
+        )
+        .expect("extraction succeeds");
+
+        assert_eq!(declarations.queues, vec!["jobs", "analytics"]);
+        assert_eq!(
+            declarations.queue_policies,
+            vec![QueuePolicy {
+                queue: "jobs".to_owned(),
+                max_attempts: Some(10),
+                backoff_ms: Some(5_000),
+                max_backoff_ms: Some(3_600_000),
+            }]
+        );
+    }
+
+    #[test]
+    fn a_queue_policy_is_the_consumers_alone_and_must_parse() {
+        let error = extract(
+            files(&[(
+                "main.lua",
+                r#"local jobs = queue "jobs" { max_attempts = 3 }"#,
+            )]),
+            "main.lua",
+        )
+        .expect_err("a producer cannot set the policy");
+        assert!(error.contains("does not consume"), "{error}");
+
+        let error = extract(
+            files(&[(
+                "main.lua",
+                r#"
+                local jobs = queue "jobs" { max_attempts = 3, backof = "5s" }
+                on "queue:jobs" (function() end)
+                "#,
+            )]),
+            "main.lua",
+        )
+        .expect_err("a misspelled field must fail");
+        assert!(error.contains("no policy field 'backof'"), "{error}");
+
+        let error = extract(
+            files(&[(
+                "main.lua",
+                r#"
+                local jobs = queue "jobs" { backoff = "soon" }
+                on "queue:jobs" (function() end)
+                "#,
+            )]),
+            "main.lua",
+        )
+        .expect_err("a nonsense duration must fail");
+        assert!(error.contains("duration"), "{error}");
+    }
+
+    #[test]
+    fn batch_options_are_checked_at_extraction() {
+        let declarations = extract(
+            files(&[(
+                "main.lua",
+                r#"on "queue:jobs" { batch = 50, max_wait = "5s" } (function(batch) end)"#,
+            )]),
+            "main.lua",
+        )
+        .expect("valid batching extracts");
+        assert_eq!(declarations.events, vec!["queue:jobs".to_owned()]);
+
+        let error = extract(
+            files(&[(
+                "main.lua",
+                r#"on "queue:jobs" { batch = 5000 } (function(batch) end)"#,
+            )]),
+            "main.lua",
+        )
+        .expect_err("an oversized batch must fail");
+        assert!(error.contains("batch must be"), "{error}");
+
+        let error = extract(
+            files(&[("main.lua", r#"on "fetch" { batch = 5 } (function() end)"#)]),
+            "main.lua",
+        )
+        .expect_err("only queue events take options");
+        assert!(error.contains("takes no options"), "{error}");
+    }
+
+    #[test]
+    fn a_cron_policy_must_name_a_zone_and_a_catch_up() {
+        extract(
+            files(&[(
+                "main.lua",
+                r#"on "cron:0 9 * * 1-5" { tz = "America/New_York", catch_up = "all" } (function() end)"#,
+            )]),
+            "main.lua",
+        )
+        .expect("a zone and a policy extract");
+
+        let error = extract(
+            files(&[(
+                "main.lua",
+                r#"on "cron:0 9 * * *" { tz = "Mars/Olympus" } (function() end)"#,
+            )]),
+            "main.lua",
+        )
+        .expect_err("an unknown zone must fail");
+        assert!(error.contains("IANA zone"), "{error}");
+
+        let error = extract(
+            files(&[(
+                "main.lua",
+                r#"on "cron:0 9 * * *" { catch_up = "some" } (function() end)"#,
+            )]),
+            "main.lua",
+        )
+        .expect_err("an unknown policy must fail");
+        assert!(error.contains("catch_up must be"), "{error}");
+    }
+
+    #[test]
+    fn topics_record_publishers_and_subscribers() {
+        let declarations = extract(
+            files(&[(
+                "main.lua",
+                r#"
+                local orders = topic "orders"
+                on "topic:orders" { batch = 10 } (function(batch) end)
+                "#,
+            )]),
+            "main.lua",
+        )
+        .expect("topics extract");
+        assert_eq!(declarations.topics, vec!["orders"]);
+        assert_eq!(declarations.events, vec!["topic:orders"]);
+
+        let error = extract(
+            files(&[("main.lua", r#"on "topic:a/b" (function() end)"#)]),
+            "main.lua",
+        )
+        .expect_err("a slash would break subscription names");
+        assert!(error.contains("without '/'"), "{error}");
+    }
+
+    #[test]
+    fn database_listeners_record_their_event() {
+        let declarations = extract(
+            files(&[(
+                "main.lua",
+                r#"
+                local orders = database "orders"
+                on "database:orders" { batch = 20 } (function(changes) end)
+                "#,
+            )]),
+            "main.lua",
+        )
+        .expect("database listeners extract");
+        assert_eq!(declarations.databases, vec!["orders"]);
+        assert_eq!(declarations.events, vec!["database:orders"]);
+
+        let error = extract(
+            files(&[("main.lua", r#"on "database:" (function() end)"#)]),
+            "main.lua",
+        )
+        .expect_err("a listener names its database");
+        assert!(error.contains("without '/'"), "{error}");
+    }
+
     #[test]
     fn a_runaway_top_level_is_interrupted() {
         // The extractor runs untrusted code; a top-level infinite loop must
@@ -0,0 +1,5 @@
+-- The rebalancer's rate limit: when a node was last asked to hand objects
+-- off. One column instead of a queue of asks, because an ask is only ever
+-- delivered in a heartbeat reply; a cooldown per node is all the state
+-- the policy needs.
+ALTER TABLE nodes ADD COLUMN last_handoff TIMESTAMPTZ;
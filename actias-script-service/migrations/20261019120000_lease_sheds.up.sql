@@ -0,0 +1,9 @@
+-- Which node handed which object off, and until when it may not take it
+-- back. A claim by the shedder inside the window is placed on a peer, so
+-- a handoff sticks for at least the rebalance cooldown.
+CREATE TABLE lease_sheds (
+    object_id TEXT NOT NULL,
+    node_id UUID NOT NULL,
+    until TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (object_id, node_id)
+);
@@ -11,6 +11,14 @@ pub struct Config {
     pub s3_bucket: String,
     /// Silence after which a worker node ages out of the registry.
     pub node_ttl_secs: u32,
+    /// Load below which the rebalancer never asks a node to shed.
+    pub rebalance_min_load: u32,
+    /// Multiple of the coolest peer's load a node must carry to shed.
+    pub rebalance_ratio: f64,
+    /// Objects one handoff ask names at most; 0 disables rebalancing.
+    pub rebalance_batch: usize,
+    /// Least time between two handoff asks to the same node.
+    pub rebalance_cooldown_secs: u64,
 }
 
 impl Config {
@@ -26,6 +34,10 @@ impl Config {
             s3_secret_key: get_env("S3_SECRET_KEY"),
             s3_bucket: get_env_or("S3_BUCKET", "actias-blobs".to_owned()),
             node_ttl_secs: get_env_or("NODE_TTL_SECS", 45),
+            rebalance_min_load: get_env_or("REBALANCE_MIN_LOAD", 16),
+            rebalance_ratio: get_env_or("REBALANCE_RATIO", 2.0),
+            rebalance_batch: get_env_or("REBALANCE_BATCH", 2),
+            rebalance_cooldown_secs: get_env_or("REBALANCE_COOLDOWN_SECS", 60),
         }
     }
 }
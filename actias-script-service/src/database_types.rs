@@ -38,6 +38,14 @@ pub struct Capabilities {
     pub workflows: Vec<String>,
     #[serde(default)]
     pub workflow_steps: Vec<String>,
+    #[serde(default)]
+    pub queue_policies: Vec<actias_declarations::QueuePolicy>,
+    #[serde(default)]
+    pub topics: Vec<String>,
+    #[serde(default)]
+    pub workflow_schedules: Vec<actias_declarations::WorkflowSchedule>,
+    #[serde(default)]
+    pub workflow_retentions: Vec<actias_declarations::WorkflowRetention>,
 }
 
 impl From<crate::proto_script_service::Capabilities> for Capabilities {
@@ -51,6 +59,35 @@ impl From<crate::proto_script_service::Capabilities> for Capabilities {
             queues: val.queues,
             workflows: val.workflows,
             workflow_steps: val.workflow_steps,
+            queue_policies: val
+                .queue_policies
+                .into_iter()
+                .map(|policy| actias_declarations::QueuePolicy {
+                    queue: policy.queue,
+                    max_attempts: policy.max_attempts,
+                    backoff_ms: policy.backoff_ms,
+                    max_backoff_ms: policy.max_backoff_ms,
+                })
+                .collect(),
+            topics: val.topics,
+            workflow_schedules: val
+                .workflow_schedules
+                .into_iter()
+                .map(|schedule| actias_declarations::WorkflowSchedule {
+                    workflow: schedule.workflow,
+                    schedule: schedule.schedule,
+                    overlap: schedule.overlap,
+                })
+                .collect(),
+            workflow_retentions: val
+                .workflow_retentions
+                .into_iter()
+                .map(|retention| actias_declarations::WorkflowRetention {
+                    workflow: retention.workflow,
+                    retention_ms: retention.retention_ms,
+                    archive: retention.archive,
+                })
+                .collect(),
         }
     }
 }
@@ -66,6 +103,35 @@ impl From<Capabilities> for crate::proto_script_service::Capabilities {
             queues: val.queues,
             workflows: val.workflows,
             workflow_steps: val.workflow_steps,
+            queue_policies: val
+                .queue_policies
+                .into_iter()
+                .map(|policy| crate::proto_script_service::QueuePolicy {
+                    queue: policy.queue,
+                    max_attempts: policy.max_attempts,
+                    backoff_ms: policy.backoff_ms,
+                    max_backoff_ms: policy.max_backoff_ms,
+                })
+                .collect(),
+            topics: val.topics,
+            workflow_schedules: val
+                .workflow_schedules
+                .into_iter()
+                .map(|schedule| crate::proto_script_service::WorkflowSchedule {
+                    workflow: schedule.workflow,
+                    schedule: schedule.schedule,
+                    overlap: schedule.overlap,
+                })
+                .collect(),
+            workflow_retentions: val
+                .workflow_retentions
+                .into_iter()
+                .map(|retention| crate::proto_script_service::WorkflowRetention {
+                    workflow: retention.workflow,
+                    retention_ms: retention.retention_ms,
+                    archive: retention.archive,
+                })
+                .collect(),
         }
     }
 }
@@ -54,7 +54,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             blobs,
         )))
         .add_service(NodeRegistryServiceServer::new(
-            node_registry::NodeRegistry::new(pool, config.node_ttl_secs),
+            node_registry::NodeRegistry::new(pool, config.node_ttl_secs).with_rebalance(
+                node_registry::RebalancePolicy {
+                    min_load: config.rebalance_min_load,
+                    ratio: config.rebalance_ratio,
+                    batch: config.rebalance_batch,
+                    cooldown: std::time::Duration::from_secs(config.rebalance_cooldown_secs),
+                },
+            ),
         ))
         .serve(addr)
         .await?;
@@ -14,9 +14,9 @@ use uuid::Uuid;
 use crate::proto_node_registry::{
     AcquireLeaseRequest, AlarmRow, ClassCount, ClearAlarmRequest, CountInstancesRequest,
     CountInstancesResponse, DeregisterRequest, DueAlarmsRequest, DueAlarmsResponse,
-    GetLeaseRequest, GetNodeRequest, HeartbeatRequest, Lease, ListInstancesRequest,
-    ListInstancesResponse, ListNodesResponse, Node, NodeRegistration, ObjectInstance,
-    RegisterNodeRequest, ReleaseLeaseRequest, SetAlarmRequest,
+    ForgetInstanceRequest, GetLeaseRequest, GetNodeRequest, HeartbeatRequest, HeartbeatResponse,
+    Lease, ListInstancesRequest, ListInstancesResponse, ListNodesResponse, Node, NodeRegistration,
+    ObjectInstance, RegisterNodeRequest, ReleaseLeaseRequest, SetAlarmRequest,
     node_registry_service_server::NodeRegistryService,
 };
 
@@ -48,6 +48,53 @@ pub struct NodeRegistry {
     database: Pool<Postgres>,
     /// Silence after which a node has aged out.
     ttl_secs: u32,
+    rebalance: RebalancePolicy,
+}
+
+/// When an overloaded node is asked to hand objects off. Placement is
+/// first-come, so without this a node that happened to touch the popular
+/// objects first keeps them until it dies; the heartbeat already reports
+/// load, and this is what acts on it.
+///
+/// The policy is deliberately sluggish: moving an object costs a drain, a
+/// ship and a restore, so asks are small, spaced by a per-node cooldown,
+/// and only made when the node is clearly hotter than the coolest peer.
+#[derive(Clone, Debug)]
+pub struct RebalancePolicy {
+    /// Load below which a node is never asked, however lopsided the
+    /// cluster; idle clusters stay put.
+    pub min_load: u32,
+    /// How many times the coolest live peer's load a node must carry
+    /// before it sheds.
+    pub ratio: f64,
+    /// Objects one ask names at most.
+    pub batch: usize,
+    /// Least time between two asks to the same node, and how long a
+    /// handed-off object stays off the node that shed it.
+    pub cooldown: std::time::Duration,
+}
+
+impl Default for RebalancePolicy {
+    fn default() -> Self {
+        Self {
+            min_load: 16,
+            ratio: 2.0,
+            batch: 2,
+            cooldown: std::time::Duration::from_secs(60),
+        }
+    }
+}
+
+impl RebalancePolicy {
+    /// Whether a node at `load` should shed, given the coolest other live
+    /// node's load; [`None`] (no peer) never sheds, because there is
+    /// nowhere to go.
+    fn sheds(&self, load: u32, coolest_peer: Option<u32>) -> bool {
+        let Some(coolest) = coolest_peer else {
+            return false;
+        };
+        load >= self.min_load && f64::from(load) > self.ratio * f64::from(coolest.max(1))
+    }
 }
 
 /// What can fail inside the registry. The [`From`] impl below is the one
@@ -68,6 +115,8 @@ enum RegistryError {
     Unheld,
     #[error("claim raced a cascade")]
     ClaimRaced,
+    #[error("the caller does not hold the object")]
+    NotHolder,
 }
 
 impl From<RegistryError> for Status {
@@ -92,6 +141,9 @@ impl From<RegistryError> for Status {
             RegistryError::ClaimRaced => {
                 Status::aborted("The lease was freed mid-claim; try again.")
             }
+            RegistryError::NotHolder => {
+                Status::failed_precondition("Only the object's holder may forget it.")
+            }
         }
     }
 }
@@ -143,7 +195,17 @@ fn claim_identity(request: &AcquireLeaseRequest) -> Result<Option<(Uuid, Uuid)>,
 
 impl NodeRegistry {
     pub fn new(database: Pool<Postgres>, ttl_secs: u32) -> Self {
-        Self { database, ttl_secs }
+        Self {
+            database,
+            ttl_secs,
+            rebalance: RebalancePolicy::default(),
+        }
+    }
+
+    /// Replaces the default rebalancing policy.
+    pub fn with_rebalance(mut self, rebalance: RebalancePolicy) -> Self {
+        self.rebalance = rebalance;
+        self
     }
 
     /// Cadence nodes are told to beat at: several beats fit in one ttl, so
@@ -168,6 +230,97 @@ impl NodeRegistry {
         Ok(())
     }
 
+    /// The objects one beating node is asked to hand off: the hottest of
+    /// those it reported that it still holds, when it is overloaded next
+    /// to its coolest peer and its cooldown has passed. The cooldown is
+    /// taken with a conditional update, only once there is something to
+    /// ask, so two racing beats (a retry, a re-registration) can never
+    /// both ask and a beat that asks nothing spends nothing.
+    async fn handoffs(
+        &self,
+        node_id: Uuid,
+        load: u32,
+        hot_objects: &[String],
+    ) -> Result<Vec<String>, RegistryError> {
+        if hot_objects.is_empty() || self.rebalance.batch == 0 {
+            return Ok(Vec::new());
+        }
+
+        let coolest: Option<i32> = sqlx::query_scalar(
+            "SELECT min(load) FROM nodes WHERE id <> $1 AND last_heartbeat > $2",
+        )
+        .bind(node_id)
+        .bind(self.cutoff())
+        .fetch_one(&self.database)
+        .await?;
+        if !self
+            .rebalance
+            .sheds(load, coolest.map(|load| load.max(0) as u32))
+        {
+            return Ok(Vec::new());
+        }
+
+        // Only objects the node still holds: a report can be a beat
+        // stale, and asking about someone else's lease would be noise.
+        // Filtered before the cooldown is taken, so a stale report asks
+        // nothing and spends nothing.
+        let held: Vec<String> = sqlx::query_scalar(
+            "SELECT object_id FROM leases WHERE node_id = $1 AND object_id = ANY($2)",
+        )
+        .bind(node_id)
+        .bind(hot_objects)
+        .fetch_all(&self.database)
+        .await?;
+        let asks: Vec<String> = hot_objects
+            .iter()
+            .filter(|object_id| held.contains(object_id))
+            .take(self.rebalance.batch)
+            .cloned()
+            .collect();
+        if asks.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let cooled = sqlx::query(
+            "UPDATE nodes SET last_handoff = now()
+             WHERE id = $1 AND (last_handoff IS NULL OR last_handoff <= $2)",
+        )
+        .bind(node_id)
+        .bind(Utc::now() - self.rebalance.cooldown)
+        .execute(&self.database)
+        .await?;
+        if cooled.rows_affected() == 0 {
+            return Ok(Vec::new());
+        }
+
+        Ok(asks)
+    }
+
+    /// Who a claim by `node_id` is made for: the node itself, unless it
+    /// shed the object inside the cooldown and a live peer can take it.
+    async fn claimant(&self, node_id: Uuid, object_id: &str) -> Result<Uuid, RegistryError> {
+        let shed: Option<i32> = sqlx::query_scalar(
+            "SELECT 1 FROM lease_sheds WHERE object_id = $1 AND node_id = $2 AND until > now()",
+        )
+        .bind(object_id)
+        .bind(node_id)
+        .fetch_optional(&self.database)
+        .await?;
+        if shed.is_none() {
+            return Ok(node_id);
+        }
+
+        let peer: Option<Uuid> = sqlx::query_scalar(
+            "SELECT id FROM nodes WHERE id <> $1 AND last_heartbeat > $2
+             ORDER BY load LIMIT 1",
+        )
+        .bind(node_id)
+        .bind(self.cutoff())
+        .fetch_optional(&self.database)
+        .await?;
+        Ok(peer.unwrap_or(node_id))
+    }
+
     /// The conditional claim and everything it settles: directory record,
     /// holder, epoch.
     async fn claim(&self, request: &AcquireLeaseRequest) -> Result<Lease, RegistryError> {
@@ -179,6 +332,11 @@ impl NodeRegistry {
         // out; doing it here means a claim never waits for a liveness read.
         self.reap().await?;
 
+        // A node that handed this object off inside the cooldown claims
+        // it for its coolest live peer instead: it sees a refusal naming
+        // that peer and forwards there, so the handoff sticks.
+        let claimant = self.claimant(node_id, &request.object_id).await?;
+
         // The conditional claim: exactly one row per object, first insert
         // wins, a re-claim by the current holder is a no-op success.
         let claimed = sqlx::query(
@@ -186,7 +344,7 @@ impl NodeRegistry {
              ON CONFLICT (object_id) DO NOTHING",
         )
         .bind(&request.object_id)
-        .bind(node_id)
+        .bind(claimant)
         .execute(&self.database)
         .await?;
 
@@ -212,7 +370,7 @@ impl NodeRegistry {
                 .fetch_optional(&self.database)
                 .await?;
         let holder = holder.ok_or(RegistryError::ClaimRaced)?;
-        let acquired = claimed.rows_affected() == 1 || holder == node_id;
+        let acquired = holder == node_id;
 
         // A fresh claim advances the object's epoch, which never resets:
         // it is the fence storage shipping writes into its manifests.
@@ -266,7 +424,10 @@ impl NodeRegistryService for NodeRegistry {
         }))
     }
 
-    async fn heartbeat(&self, request: Request<HeartbeatRequest>) -> Result<Response<()>, Status> {
+    async fn heartbeat(
+        &self,
+        request: Request<HeartbeatRequest>,
+    ) -> Result<Response<HeartbeatResponse>, Status> {
         let request = request.get_ref();
         let id =
             Uuid::from_str(&request.node_id).map_err(|_| RegistryError::InvalidId("node_id"))?;
@@ -289,7 +450,11 @@ impl NodeRegistryService for NodeRegistry {
             return Err(RegistryError::NodeUnknown.into());
         }
 
-        Ok(Response::new(()))
+        Ok(Response::new(HeartbeatResponse {
+            handoffs: self
+                .handoffs(id, request.load, &request.hot_objects)
+                .await?,
+        }))
     }
 
     async fn list_nodes(
@@ -395,13 +560,32 @@ impl NodeRegistryService for NodeRegistry {
 
         // Only the holder may release; anyone else's release is a no-op,
         // so a laggard cannot free an object out from under its new home.
-        sqlx::query("DELETE FROM leases WHERE object_id = $1 AND node_id = $2")
+        let released = sqlx::query("DELETE FROM leases WHERE object_id = $1 AND node_id = $2")
             .bind(&request.object_id)
             .bind(node_id)
             .execute(&self.database)
             .await
             .map_err(RegistryError::Store)?;
 
+        // A handoff keeps the shedder off the object for the cooldown;
+        // expired marks are swept by the same write.
+        if request.handoff && released.rows_affected() == 1 {
+            sqlx::query("DELETE FROM lease_sheds WHERE until <= now()")
+                .execute(&self.database)
+                .await
+                .map_err(RegistryError::Store)?;
+            sqlx::query(
+                "INSERT INTO lease_sheds (object_id, node_id, until) VALUES ($1, $2, $3)
+                 ON CONFLICT (object_id, node_id) DO UPDATE SET until = $3",
+            )
+            .bind(&request.object_id)
+            .bind(node_id)
+            .bind(Utc::now() + self.rebalance.cooldown)
+            .execute(&self.database)
+            .await
+            .map_err(RegistryError::Store)?;
+        }
+
         Ok(Response::new(()))
     }
 
@@ -437,6 +621,55 @@ impl NodeRegistryService for NodeRegistry {
         Ok(Response::new(()))
     }
 
+    async fn forget_instance(
+        &self,
+        request: Request<ForgetInstanceRequest>,
+    ) -> Result<Response<()>, Status> {
+        let request = request.get_ref();
+        let node_id =
+            Uuid::from_str(&request.node_id).map_err(|_| RegistryError::InvalidId("node_id"))?;
+        let scope_id =
+            Uuid::from_str(&request.scope_id).map_err(|_| RegistryError::InvalidId("scope_id"))?;
+
+        let mut transaction = self.database.begin().await.map_err(RegistryError::Store)?;
+        // The lease row goes last and is the holder check: deleting it
+        // first would let a failed check leave the object unheld.
+        let held: Option<(String,)> =
+            sqlx::query_as("SELECT object_id FROM leases WHERE object_id = $1 AND node_id = $2")
+                .bind(&request.object_id)
+                .bind(node_id)
+                .fetch_optional(&mut *transaction)
+                .await
+                .map_err(RegistryError::Store)?;
+        if held.is_none() {
+            return Err(RegistryError::NotHolder.into());
+        }
+
+        sqlx::query(
+            "DELETE FROM object_instances WHERE scope_id = $1 AND class = $2 AND name = $3",
+        )
+        .bind(scope_id)
+        .bind(&request.class)
+        .bind(&request.name)
+        .execute(&mut *transaction)
+        .await
+        .map_err(RegistryError::Store)?;
+        sqlx::query("DELETE FROM object_alarms WHERE object_id = $1")
+            .bind(&request.object_id)
+            .execute(&mut *transaction)
+            .await
+            .map_err(RegistryError::Store)?;
+        sqlx::query("DELETE FROM leases WHERE object_id = $1 AND node_id = $2")
+            .bind(&request.object_id)
+            .bind(node_id)
+            .execute(&mut *transaction)
+            .await
+            .map_err(RegistryError::Store)?;
+        transaction.commit().await.map_err(RegistryError::Store)?;
+
+        Ok(Response::new(()))
+    }
+
     async fn due_alarms(
         &self,
         request: Request<DueAlarmsRequest>,
@@ -653,6 +886,7 @@ mod tests {
             .heartbeat(Request::new(HeartbeatRequest {
                 node_id: live.clone(),
                 load: 7,
+                ..Default::default()
             }))
             .await
             .expect("a live node's heartbeat lands");
@@ -688,6 +922,7 @@ mod tests {
             .heartbeat(Request::new(HeartbeatRequest {
                 node_id: node,
                 load: 0,
+                ..Default::default()
             }))
             .await;
 
@@ -746,6 +981,7 @@ mod tests {
             .release_lease(Request::new(ReleaseLeaseRequest {
                 object_id: "hash-jobs".to_owned(),
                 node_id: node.clone(),
+                ..Default::default()
             }))
             .await
             .expect("releases");
@@ -1055,6 +1291,74 @@ mod tests {
         assert!(after.is_empty());
     }
 
+    #[test]
+    fn a_node_sheds_only_when_clearly_hotter_than_a_peer() {
+        let policy = RebalancePolicy::default();
+
+        // No peer: nowhere to go.
+        assert!(!policy.sheds(500, None));
+        // Busy, but under the floor: idle clusters stay put.
+        assert!(!policy.sheds(10, Some(0)));
+        // Past the floor and past the ratio against the coolest peer.
+        assert!(policy.sheds(40, Some(10)));
+        // Past the floor, but the peer is about as busy.
+        assert!(!policy.sheds(40, Some(30)));
+        // An idle peer counts as load one, not a division by zero.
+        assert!(policy.sheds(16, Some(0)));
+    }
+
+    #[tokio::test]
+    async fn an_overloaded_node_is_asked_to_hand_off_once_per_cooldown() {
+        let (registry, _database, _guard) = registry(45).await;
+        let registry = registry.with_rebalance(RebalancePolicy {
+            min_load: 10,
+            ratio: 2.0,
+            batch: 1,
+            cooldown: std::time::Duration::from_secs(3600),
+        });
+
+        let hot = register(&registry, "hot:3000").await;
+        let cool = register(&registry, "cool:3000").await;
+        for object in ["h1", "h2"] {
+            registry
+                .acquire_lease(Request::new(AcquireLeaseRequest {
+                    object_id: object.to_owned(),
+                    node_id: hot.clone(),
+                    ..Default::default()
+                }))
+                .await
+                .expect("claims");
+        }
+
+        let beat = |node: String, load: u32, hot_objects: Vec<&str>| {
+            let registry = &registry;
+            async move {
+                registry
+                    .heartbeat(Request::new(HeartbeatRequest {
+                        node_id: node,
+                        load,
+                        hot_objects: hot_objects.into_iter().map(str::to_owned).collect(),
+                    }))
+                    .await
+                    .expect("beats")
+                    .into_inner()
+                    .handoffs
+            }
+        };
+
+        assert!(beat(cool.clone(), 1, vec![]).await.is_empty());
+        // A report of nothing held asks nothing and keeps the cooldown.
+        assert!(beat(hot.clone(), 50, vec!["gone"]).await.is_empty());
+        // A stale report naming an object the node does not hold is
+        // skipped; the batch takes the hottest held one.
+        assert_eq!(
+            beat(hot.clone(), 50, vec!["gone", "h2", "h1"]).await,
+            vec!["h2".to_owned()]
+        );
+        // Still overloaded, but inside the cooldown: no second ask.
+        assert!(beat(hot.clone(), 50, vec!["h1"]).await.is_empty());
+    }
+
     #[tokio::test]
     async fn only_the_holder_can_release_a_lease() {
         let (registry, _database, _guard) = registry(45).await;
@@ -1077,6 +1381,7 @@ mod tests {
             .release_lease(Request::new(ReleaseLeaseRequest {
                 object_id: object.clone(),
                 node_id: stranger.clone(),
+                ..Default::default()
             }))
             .await
             .expect("release answers");
@@ -1096,6 +1401,7 @@ mod tests {
             .release_lease(Request::new(ReleaseLeaseRequest {
                 object_id: object.clone(),
                 node_id: holder.clone(),
+                ..Default::default()
             }))
             .await
             .expect("release answers");
@@ -1110,4 +1416,130 @@ mod tests {
             .into_inner();
         assert!(won.acquired);
     }
+
+    #[tokio::test]
+    async fn a_handed_off_object_is_not_reclaimed_by_its_shedder() {
+        let (registry, _database, _guard) = registry(45).await;
+        let registry = registry.with_rebalance(RebalancePolicy {
+            cooldown: std::time::Duration::from_secs(3600),
+            ..RebalancePolicy::default()
+        });
+
+        let shedder = register(&registry, "shedder:3000").await;
+        let peer = register(&registry, "peer:3000").await;
+        let object = "d".repeat(64);
+        let claim = |node_id: &str| AcquireLeaseRequest {
+            object_id: object.clone(),
+            node_id: node_id.to_owned(),
+            ..Default::default()
+        };
+
+        let first = registry
+            .acquire_lease(Request::new(claim(&shedder)))
+            .await
+            .expect("claims")
+            .into_inner();
+        registry
+            .release_lease(Request::new(ReleaseLeaseRequest {
+                object_id: object.clone(),
+                node_id: shedder.clone(),
+                handoff: true,
+            }))
+            .await
+            .expect("release answers");
+
+        // The shedder's next claim lands on the peer, which it is told to
+        // forward to; the peer's own claim is then the no-op re-claim.
+        let redirected = registry
+            .acquire_lease(Request::new(claim(&shedder)))
+            .await
+            .expect("claim answers")
+            .into_inner();
+        assert!(!redirected.acquired);
+        assert_eq!(redirected.node_id, peer);
+        assert!(redirected.epoch > first.epoch, "the move is a new epoch");
+
+        let settled = registry
+            .acquire_lease(Request::new(claim(&peer)))
+            .await
+            .expect("claim answers")
+            .into_inner();
+        assert!(settled.acquired);
+        assert_eq!(settled.epoch, redirected.epoch);
+    }
+
+    #[tokio::test]
+    async fn forgetting_an_instance_takes_it_out_of_the_store() {
+        let (registry, _database, _guard) = registry(45).await;
+        let holder = register(&registry, "holder:3200").await;
+        let stranger = register(&registry, "stranger:3200").await;
+        let project = Uuid::new_v4();
+        let object = "f".repeat(64);
+
+        registry
+            .acquire_lease(Request::new(AcquireLeaseRequest {
+                object_id: object.clone(),
+                node_id: holder.clone(),
+                scope_id: project.to_string(),
+                class: "__workflow".to_owned(),
+                name: "order/o1".to_owned(),
+                script_id: Uuid::new_v4().to_string(),
+            }))
+            .await
+            .expect("claims");
+        registry
+            .set_alarm(Request::new(SetAlarmRequest {
+                object_id: object.clone(),
+                own_key: "proj-1/__workflow/order/o1".to_owned(),
+                due_ms: 1_000,
+            }))
+            .await
+            .expect("mirrors");
+
+        let forget = |node_id: &str| ForgetInstanceRequest {
+            object_id: object.clone(),
+            node_id: node_id.to_owned(),
+            scope_id: project.to_string(),
+            class: "__workflow".to_owned(),
+            name: "order/o1".to_owned(),
+        };
+        let refused = registry
+            .forget_instance(Request::new(forget(&stranger)))
+            .await
+            .expect_err("only the holder forgets");
+        assert_eq!(refused.code(), tonic::Code::FailedPrecondition);
+
+        registry
+            .forget_instance(Request::new(forget(&holder)))
+            .await
+            .expect("forgets");
+        let listed = registry
+            .list_instances(Request::new(ListInstancesRequest {
+                project_ids: vec![project.to_string()],
+                ..Default::default()
+            }))
+            .await
+            .expect("lists")
+            .into_inner();
+        assert_eq!(listed.total, 0, "the directory row is gone");
+        let due = registry
+            .due_alarms(Request::new(DueAlarmsRequest {
+                now_ms: 2_000,
+                limit: 10,
+            }))
+            .await
+            .expect("sweeps")
+            .into_inner();
+        assert!(due.alarms.is_empty(), "the mirrored alarm is gone");
+        let unheld = registry
+            .acquire_lease(Request::new(AcquireLeaseRequest {
+                object_id: object,
+                node_id: stranger,
+                ..Default::default()
+            }))
+            .await
+            .expect("claim answers")
+            .into_inner();
+        assert!(unheld.acquired, "the lease is gone");
+    }
 }
@@ -34,12 +34,15 @@ pub struct ScriptService {
 /// taken from input.
 #[derive(Clone, Copy)]
 enum ContractMember {
-    /// `on "queue:<name>"`, the queue's consumer.
+    /// `on "queue:<name>"`, the queue's consumer, or `on "topic:<name>"`,
+    /// one of a topic's subscribers.
     Events,
     /// `workflow "name"`, the definition's declarer.
     Workflows,
     /// `queue "name"`, a producer.
     Queues,
+    /// `topic "name"`, a publisher.
+    Topics,
     /// `database "name"`, a declarer.
     Databases,
     /// `object "Class" { ... }`, the class's declarer.
@@ -48,9 +51,11 @@ enum ContractMember {
 
 impl ContractMember {
     /// Resolution order per class: a queue's consumer outranks its
-    /// producers; databases and user classes read one member each.
-    /// [`None`] for platform classes never resolved this way (`__cron`
-    /// scopes to its script and never asks).
+    /// producers; a topic's publishers outrank its subscribers, since
+    /// the topic object only forwards; databases and user classes read
+    /// one member each. [`None`] for platform classes never resolved this
+    /// way (`__cron` scopes to its script and never asks; a subscription
+    /// names its subscriber).
     fn for_class(class: &str) -> Option<&'static [ContractMember]> {
         match class {
             actias_common::classes::QUEUE_CLASS => {
@@ -58,6 +63,9 @@ impl ContractMember {
             }
             actias_common::classes::DATABASE_CLASS => Some(&[ContractMember::Databases]),
             actias_common::classes::WORKFLOW_CLASS => Some(&[ContractMember::Workflows]),
+            actias_common::classes::TOPIC_CLASS => {
+                Some(&[ContractMember::Topics, ContractMember::Events])
+            }
             class if class.starts_with("__") => None,
             _ => Some(&[ContractMember::Objects]),
         }
@@ -69,6 +77,7 @@ impl ContractMember {
             ContractMember::Events => "events",
             ContractMember::Workflows => "workflows",
             ContractMember::Queues => "queues",
+            ContractMember::Topics => "topics",
             ContractMember::Databases => "databases",
             ContractMember::Objects => "objects",
         }
@@ -77,11 +86,16 @@ impl ContractMember {
     /// What the declaration reads as inside that member.
     fn needle(&self, class: &str, name: &str) -> String {
         match self {
+            ContractMember::Events if class == actias_common::classes::TOPIC_CLASS => {
+                format!("topic:{name}")
+            }
             ContractMember::Events => format!("queue:{name}"),
             // A workflow instance is `<definition>/<caller id>`; the
             // definition segment is what the contract declares.
             ContractMember::Workflows => name.split('/').next().unwrap_or_default().to_owned(),
-            ContractMember::Queues | ContractMember::Databases => name.to_owned(),
+            ContractMember::Queues | ContractMember::Topics | ContractMember::Databases => {
+                name.to_owned()
+            }
             ContractMember::Objects => class.to_owned(),
         }
     }
@@ -166,9 +180,12 @@ impl ScriptService {
 
     /// Refuses a derived contract colliding with a sibling script's
     /// current one: a queue has one consumer (`on "queue:<name>"`) and a
-    /// user class one declarer per project. Producers (`queue "name"`)
-    /// and databases repeat freely; the publishing script's own previous
-    /// revision never conflicts with itself.
+    /// user class one declarer per project. Producers (`queue "name"`),
+    /// topics on either side (`topic "name"`, `on "topic:<name>"`) and
+    /// databases repeat freely: a topic subscription is recorded in the
+    /// contract's events and each subscriber gets its own delivery. The
+    /// publishing script's own previous revision never conflicts with
+    /// itself.
     async fn refuse_contract_conflicts(
         &self,
         script_id: &Uuid,
@@ -294,6 +311,10 @@ impl ScriptService {
             queues: derived.queues,
             workflows: derived.workflows,
             workflow_steps: derived.workflow_steps,
+            queue_policies: derived.queue_policies,
+            topics: derived.topics,
+            workflow_schedules: derived.workflow_schedules,
+            workflow_retentions: derived.workflow_retentions,
         });
 
         // Identity is project-scoped, so single-owner declarations must be
@@ -865,6 +886,39 @@ impl script_service_server::ScriptService for ScriptService {
         let project_id = Uuid::from_str(&request.project_id)
             .map_err(|_| Status::invalid_argument("'project_id' was not a valid uuid"))?;
 
+        // A subscription is `<topic>/<subscriber script id>`, or
+        // `database/<database>/<subscriber script id>`: the name is the
+        // owner, provided the script still lives in this project. It keeps
+        // resolving after the subscriber drops the event, so its dead
+        // letters stay reachable.
+        if request.class == actias_common::classes::SUBSCRIPTION_CLASS {
+            let subscriber = request
+                .name
+                .rsplit_once('/')
+                .and_then(|(_, id)| Uuid::from_str(id).ok())
+                .ok_or_else(|| {
+                    Status::invalid_argument(
+                        "A subscription is named '<topic>/<script id>' or \
+                         'database/<database>/<script id>'.",
+                    )
+                })?;
+            let found: Option<Uuid> =
+                sqlx::query_scalar("SELECT id FROM scripts WHERE id = $1 AND project_id = $2")
+                    .bind(subscriber)
+                    .bind(project_id)
+                    .fetch_optional(&self.database)
+                    .await
+                    .map_err(|e| Status::internal(e.to_string()))?;
+            return match found {
+                Some(script_id) => Ok(Response::new(ClassOwner {
+                    script_id: script_id.to_string(),
+                })),
+                None => Err(Status::not_found(
+                    "The subscribing script is not in this project.",
+                )),
+            };
+        }
+
         let reads = ContractMember::for_class(&request.class).ok_or_else(|| {
             Status::invalid_argument("Platform class has no contract-derived owner.")
         })?;
@@ -904,6 +958,36 @@ impl script_service_server::ScriptService for ScriptService {
         }
     }
 
+    async fn list_subscribers(
+        &self,
+        request: tonic::Request<ListSubscribersRequest>,
+    ) -> Result<tonic::Response<Subscribers>, tonic::Status> {
+        let request = request.get_ref();
+        let project_id = Uuid::from_str(&request.project_id)
+            .map_err(|_| Status::invalid_argument("'project_id' was not a valid uuid"))?;
+
+        let script_ids: Vec<Uuid> = sqlx::query_scalar(
+            "SELECT s.id FROM scripts s
+             JOIN revisions r ON r.id = s.current_revision
+             WHERE s.project_id = $1
+               AND jsonb_exists(r.script_config->'capabilities'->'events', $2)
+             ORDER BY s.id",
+        )
+        .bind(project_id)
+        .bind(if request.event.is_empty() {
+            format!("topic:{}", request.topic)
+        } else {
+            request.event.clone()
+        })
+        .fetch_all(&self.database)
+        .await
+        .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(Subscribers {
+            script_ids: script_ids.iter().map(Uuid::to_string).collect(),
+        }))
+    }
+
     async fn set_alias(
         &self,
         request: tonic::Request<SetAliasRequest>,
@@ -1262,6 +1346,112 @@ mod tests {
         .expect("the owner republishes itself");
     }
 
+    #[tokio::test]
+    async fn a_topic_records_every_subscriber_instead_of_refusing_them() {
+        let harness = service().await;
+        let project = Uuid::new_v4();
+
+        let orders = insert_script(&harness.database, "orders", project).await;
+        let billing = insert_script(&harness.database, "billing", project).await;
+        let email = insert_script(&harness.database, "email", project).await;
+
+        publish_code(&harness, orders, "local orders = topic \"orders\"")
+            .await
+            .expect("the publisher publishes");
+        for subscriber in [billing, email] {
+            publish_code(
+                &harness,
+                subscriber,
+                "on \"topic:orders\" (function(order) end)",
+            )
+            .await
+            .expect("every subscriber publishes");
+        }
+
+        let subscribers = harness
+            .service
+            .list_subscribers(tonic::Request::new(ListSubscribersRequest {
+                project_id: project.to_string(),
+                topic: "orders".to_owned(),
+                event: String::new(),
+            }))
+            .await
+            .expect("lists");
+        let mut expected = vec![billing.to_string(), email.to_string()];
+        expected.sort();
+        assert_eq!(subscribers.get_ref().script_ids, expected);
+
+        let resolve = |class: &str, name: String| {
+            let request = ResolveClassOwnerRequest {
+                project_id: project.to_string(),
+                class: class.to_owned(),
+                name,
+            };
+            harness
+                .service
+                .resolve_class_owner(tonic::Request::new(request))
+        };
+
+        // The topic runs on its publisher; each subscription on the
+        // subscriber its name carries.
+        let owner = resolve("__topic", "orders".to_owned())
+            .await
+            .expect("resolves");
+        assert_eq!(owner.get_ref().script_id, orders.to_string());
+        let owner = resolve("__subscription", format!("orders/{email}"))
+            .await
+            .expect("resolves");
+        assert_eq!(owner.get_ref().script_id, email.to_string());
+
+        // A script outside the project owns no subscription here.
+        let missing = resolve("__subscription", format!("orders/{}", Uuid::new_v4())).await;
+        assert_eq!(
+            missing.expect_err("must not resolve").code(),
+            tonic::Code::NotFound
+        );
+    }
+
+    #[tokio::test]
+    async fn database_listeners_list_by_their_event() {
+        let harness = service().await;
+        let project = Uuid::new_v4();
+
+        let shop = insert_script(&harness.database, "shop", project).await;
+        let search = insert_script(&harness.database, "search", project).await;
+        publish_code(&harness, shop, "local orders = database \"orders\"")
+            .await
+            .expect("the declarer publishes");
+        publish_code(
+            &harness,
+            search,
+            "on \"database:orders\" (function(changes) end)",
+        )
+        .await
+        .expect("the listener publishes");
+
+        let subscribers = harness
+            .service
+            .list_subscribers(tonic::Request::new(ListSubscribersRequest {
+                project_id: project.to_string(),
+                topic: String::new(),
+                event: "database:orders".to_owned(),
+            }))
+            .await
+            .expect("lists");
+        assert_eq!(subscribers.get_ref().script_ids, vec![search.to_string()]);
+
+        let owner = harness
+            .service
+            .resolve_class_owner(tonic::Request::new(ResolveClassOwnerRequest {
+                project_id: project.to_string(),
+                class: "__subscription".to_owned(),
+                name: format!("database/orders/{search}"),
+            }))
+            .await
+            .expect("resolves");
+        assert_eq!(owner.get_ref().script_id, search.to_string());
+    }
+
     #[tokio::test]
     async fn class_owners_resolve_from_contracts_then_the_directory() {
         let harness = service().await;
@@ -1612,6 +1802,10 @@ mod tests {
                     queues: vec![],
                     workflows: vec![],
                     workflow_steps: vec![],
+                    queue_policies: vec![],
+                    topics: vec![],
+                    workflow_schedules: vec![],
+                    workflow_retentions: vec![],
                 }),
             }),
             bundle: Some(Bundle {
@@ -8,6 +8,7 @@ import { AxiosHttpRequest } from './core/AxiosHttpRequest';
 import { AclService } from './services/AclService';
 import { AdminService } from './services/AdminService';
 import { AuthService } from './services/AuthService';
+import { CronsService } from './services/CronsService';
 import { DatabasesService } from './services/DatabasesService';
 import { KvService } from './services/KvService';
 import { ObjectsService } from './services/ObjectsService';
@@ -27,6 +28,7 @@ export class ActiasClient {
     public readonly acl: AclService;
     public readonly admin: AdminService;
     public readonly auth: AuthService;
+    public readonly crons: CronsService;
     public readonly databases: DatabasesService;
     public readonly kv: KvService;
     public readonly objects: ObjectsService;
@@ -57,6 +59,7 @@ export class ActiasClient {
         this.acl = new AclService(this.request);
         this.admin = new AdminService(this.request);
         this.auth = new AuthService(this.request);
+        this.crons = new CronsService(this.request);
         this.databases = new DatabasesService(this.request);
         this.kv = new KvService(this.request);
         this.objects = new ObjectsService(this.request);
@@ -22,7 +22,12 @@ export type { CreateRevisionDto } from './models/CreateRevisionDto';
 export type { CreateScriptDto } from './models/CreateScriptDto';
 export type { CreateServiceTokenDto } from './models/CreateServiceTokenDto';
 export type { CreateUserDto } from './models/CreateUserDto';
+export type { CronDto } from './models/CronDto';
+export type { CronFireDto } from './models/CronFireDto';
+export type { CronTriggerDto } from './models/CronTriggerDto';
+export { DatabaseDumpDto } from './models/DatabaseDumpDto';
 export type { DatabaseOverviewDto } from './models/DatabaseOverviewDto';
+export type { DatabaseRestoredDto } from './models/DatabaseRestoredDto';
 export { FileDto } from './models/FileDto';
 export type { ListNamespaceDto } from './models/ListNamespaceDto';
 export type { LoginDto } from './models/LoginDto';
@@ -38,6 +43,7 @@ export { PairDto } from './models/PairDto';
 export type { ProjectDto } from './models/ProjectDto';
 export type { QueueEventDto } from './models/QueueEventDto';
 export type { QueueMessageDto } from './models/QueueMessageDto';
+export type { QueuePolicyDto } from './models/QueuePolicyDto';
 export type { QueueStatsDto } from './models/QueueStatsDto';
 export type { RegistrationCodeDto } from './models/RegistrationCodeDto';
 export type { RegistrationConfigDto } from './models/RegistrationConfigDto';
@@ -46,6 +52,7 @@ export type { RetriedDto } from './models/RetriedDto';
 export type { RevisionDataDto } from './models/RevisionDataDto';
 export type { RevisionFullDto } from './models/RevisionFullDto';
 export type { RunCancelDto } from './models/RunCancelDto';
+export type { RunMigrateDto } from './models/RunMigrateDto';
 export type { RunSignalDto } from './models/RunSignalDto';
 export type { RunStartDto } from './models/RunStartDto';
 export type { ScriptConfigDto } from './models/ScriptConfigDto';
@@ -70,6 +77,7 @@ export type { WorkflowRunDto } from './models/WorkflowRunDto';
 export { AclService } from './services/AclService';
 export { AdminService } from './services/AdminService';
 export { AuthService } from './services/AuthService';
+export { CronsService } from './services/CronsService';
 export { DatabasesService } from './services/DatabasesService';
 export { KvService } from './services/KvService';
 export { ObjectsService } from './services/ObjectsService';
@@ -0,0 +1,42 @@
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+
+import type { CronFireDto } from './CronFireDto';
+
+export type CronDto = {
+    /**
+     * The event, `cron:<expr>`.
+     */
+    event: string;
+    /**
+     * Public identifier of the declaring script.
+     */
+    declaredBy: string;
+    /**
+     * Workflows scheduled on the expression.
+     */
+    workflows: Array<string>;
+    /**
+     * The zone the expression reads; absent until first armed.
+     */
+    tz?: string | null;
+    /**
+     * all, latest or none.
+     */
+    catchUp?: string | null;
+    nextMs?: number | null;
+    /**
+     * The last occurrence the schedule fired.
+     */
+    lastMs?: number | null;
+    /**
+     * Occurrences the catch-up policy skipped.
+     */
+    missed: number;
+    /**
+     * Newest first.
+     */
+    fires: Array<CronFireDto>;
+};
+
@@ -0,0 +1,24 @@
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+
+export type CronFireDto = {
+    /**
+     * The occurrence the fire stood for; a manual fire stands for its own moment.
+     */
+    scheduledMs: number;
+    firedMs: number;
+    /**
+     * How long the listener and scheduled starts took together.
+     */
+    durationMs: number;
+    /**
+     * What failed, listener and starts joined.
+     */
+    error?: string | null;
+    /**
+     * Fired by hand rather than by the alarm.
+     */
+    manual: boolean;
+};
+
@@ -0,0 +1,15 @@
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+
+export type CronTriggerDto = {
+    /**
+     * The event to fire, `cron:<expr>`.
+     */
+    event: string;
+    /**
+     * Public identifier of the declaring script; needed only when several declare the event.
+     */
+    script?: string;
+};
+
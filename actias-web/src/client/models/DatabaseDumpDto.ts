@@ -0,0 +1,25 @@
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+
+export type DatabaseDumpDto = {
+    /**
+     * sql: schema and rows as statements; sqlite: the file itself, base64.
+     */
+    format: DatabaseDumpDto.format;
+    content: string;
+};
+
+export namespace DatabaseDumpDto {
+
+    /**
+     * sql: schema and rows as statements; sqlite: the file itself, base64.
+     */
+    export enum format {
+        SQL = 'sql',
+        SQLITE = 'sqlite',
+    }
+
+
+}
+
@@ -0,0 +1,15 @@
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+
+export type DatabaseRestoredDto = {
+    /**
+     * The restored file size in bytes.
+     */
+    sizeBytes: number;
+    /**
+     * The lease epoch the restored snapshot shipped at.
+     */
+    epoch: number;
+};
+
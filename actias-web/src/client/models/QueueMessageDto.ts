@@ -5,10 +5,14 @@
 export type QueueMessageDto = {
     id: number;
     /**
-     * pending, in-flight or dead.
+     * scheduled, pending, in-flight, blocked or dead.
      */
     state: string;
     attempts: number;
+    /**
+     * Attempts the policy allows before dead-lettering.
+     */
+    maxAttempts?: number;
     /**
      * Payload prefix.
      */
@@ -20,5 +24,13 @@ export type QueueMessageDto = {
     enqueuedMs: number;
     nextMs?: number | null;
     diedMs?: number | null;
+    /**
+     * The key that deduplicates repeats of this send.
+     */
+    dedupKey?: string;
+    /**
+     * Ordering group; delivers after its earlier messages.
+     */
+    group?: string;
 };
 
@@ -0,0 +1,19 @@
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+
+export type QueuePolicyDto = {
+    /**
+     * Deliveries attempted before dead-lettering.
+     */
+    maxAttempts: number;
+    /**
+     * First retry delay; doubles per attempt.
+     */
+    backoffMs: number;
+    /**
+     * Ceiling the retry delay doubles up to.
+     */
+    maxBackoffMs: number;
+};
+
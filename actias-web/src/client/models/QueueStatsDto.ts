@@ -2,6 +2,8 @@
 /* tslint:disable */
 /* eslint-disable */
 
+import type { QueuePolicyDto } from './QueuePolicyDto';
+
 export type QueueStatsDto = {
     /**
      * Every message still queued.
@@ -11,7 +13,19 @@ export type QueueStatsDto = {
      * Messages due now, in delivery.
      */
     inFlight: number;
+    /**
+     * Messages sent for later, not due yet.
+     */
+    scheduled: number;
+    /**
+     * Grouped messages waiting behind an older one in their group.
+     */
+    blocked: number;
     oldestPending?: number | null;
     deadLetters: number;
+    /**
+     * Absent until the queue has been dispatched to once.
+     */
+    policy?: QueuePolicyDto;
 };
 
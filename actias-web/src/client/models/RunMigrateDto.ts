@@ -0,0 +1,10 @@
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+
+export type RunMigrateDto = {
+    /**
+     * The revision id to move the run onto; omitted, the script's current revision.
+     */
+    revision?: string;
+};
@@ -6,10 +6,13 @@ export type WorkflowJournalRowDto = {
     seq: number;
     at: number;
     /**
-     * STARTED, INTENT, RESULT, TIMER, SIGNAL, CHILD, CANCEL, COMPLETED or AMBIENT.
+     * STARTED, INTENT, RESULT, TIMER, SIGNAL, CHILD, CANCEL, COMPLETED, AMBIENT, FAILED, UPDATE, PATCH or COMPENSATION.
      */
     kind: string;
     data: any;
+    /**
+     * Row format; negative on rows of the generation continue-as-new archived, which are never replayed.
+     */
     format: number;
 };
 
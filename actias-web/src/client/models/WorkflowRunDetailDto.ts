@@ -9,6 +9,10 @@ export type WorkflowRunDetailDto = {
     definition: string;
     status: string;
     detail: any;
+    /**
+     * The step or gate the run is at.
+     */
+    atStep: string;
     journal: Array<WorkflowJournalRowDto>;
 };
 
@@ -9,7 +9,7 @@ export type WorkflowRunDto = {
     id: string;
     definition: string;
     /**
-     * completed, cancelled, sleeping, awaiting, running or unstarted.
+     * completed, cancelled, failed, sleeping, awaiting, running or unstarted.
      */
     status: string;
     /**
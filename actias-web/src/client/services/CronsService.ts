@@ -0,0 +1,59 @@
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+import type { CronDto } from '../models/CronDto';
+import type { CronFireDto } from '../models/CronFireDto';
+import type { CronTriggerDto } from '../models/CronTriggerDto';
+
+import type { CancelablePromise } from '../core/CancelablePromise';
+import type { BaseHttpRequest } from '../core/BaseHttpRequest';
+
+export class CronsService {
+
+    constructor(public readonly httpRequest: BaseHttpRequest) {}
+
+    /**
+     * Every cron event a live contract declares, listeners and workflow
+     * schedules alike, with its instance's policy, next and last fire and
+     * recent history.
+     * @param project
+     * @returns CronDto
+     * @throws ApiError
+     */
+    public listCrons(
+        project: string,
+    ): CancelablePromise<Array<CronDto>> {
+        return this.httpRequest.request({
+            method: 'GET',
+            url: '/api/project/{project}/crons',
+            path: {
+                'project': project,
+            },
+        });
+    }
+
+    /**
+     * Fires the event's listener and scheduled workflows once, now,
+     * without touching the schedule; answers with the fire's history row.
+     * A bare expression is read as `cron:<expr>`.
+     * @param project
+     * @param requestBody
+     * @returns CronFireDto
+     * @throws ApiError
+     */
+    public triggerCron(
+        project: string,
+        requestBody: CronTriggerDto,
+    ): CancelablePromise<CronFireDto> {
+        return this.httpRequest.request({
+            method: 'POST',
+            url: '/api/project/{project}/crons/trigger',
+            path: {
+                'project': project,
+            },
+            body: requestBody,
+            mediaType: 'application/json',
+        });
+    }
+
+}
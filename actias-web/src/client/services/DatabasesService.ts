@@ -1,7 +1,9 @@
 /* istanbul ignore file */
 /* tslint:disable */
 /* eslint-disable */
+import type { DatabaseDumpDto } from '../models/DatabaseDumpDto';
 import type { DatabaseOverviewDto } from '../models/DatabaseOverviewDto';
+import type { DatabaseRestoredDto } from '../models/DatabaseRestoredDto';
 import type { ResourceInstanceDto } from '../models/ResourceInstanceDto';
 import type { SqlQueryDto } from '../models/SqlQueryDto';
 import type { SqlRowsDto } from '../models/SqlRowsDto';
@@ -77,6 +79,87 @@ export class DatabasesService {
         });
     }
 
+    /**
+     * Runs one read-only statement against the file itself, no vm: the
+     * nearest copy answers under the script-guard authorizer, so nothing
+     * touches the owner. What `actias sql shell` runs by default.
+     * @param project
+     * @param name
+     * @param requestBody
+     * @returns SqlRowsDto
+     * @throws ApiError
+     */
+    public readDatabase(
+        project: string,
+        name: string,
+        requestBody: SqlQueryDto,
+    ): CancelablePromise<SqlRowsDto> {
+        return this.httpRequest.request({
+            method: 'POST',
+            url: '/api/project/{project}/databases/{name}/read',
+            path: {
+                'project': project,
+                'name': name,
+            },
+            body: requestBody,
+            mediaType: 'application/json',
+        });
+    }
+
+    /**
+     * The whole database from the freshest copy: schema and rows as SQL,
+     * or with `raw`, the SQLite file itself, base64.
+     * @param project
+     * @param name
+     * @param raw
+     * @returns DatabaseDumpDto
+     * @throws ApiError
+     */
+    public dumpDatabase(
+        project: string,
+        name: string,
+        raw?: boolean,
+    ): CancelablePromise<DatabaseDumpDto> {
+        return this.httpRequest.request({
+            method: 'GET',
+            url: '/api/project/{project}/databases/{name}/dump',
+            path: {
+                'project': project,
+                'name': name,
+            },
+            query: {
+                'raw': raw,
+            },
+        });
+    }
+
+    /**
+     * Replaces the database with a dump, as a fenced takeover: the owner's
+     * vm drains, the new file ships at a fresh lease epoch, and the next
+     * call runs over it. A dump that does not load replaces nothing.
+     * @param project
+     * @param name
+     * @param requestBody
+     * @returns DatabaseRestoredDto
+     * @throws ApiError
+     */
+    public restoreDatabase(
+        project: string,
+        name: string,
+        requestBody: DatabaseDumpDto,
+    ): CancelablePromise<DatabaseRestoredDto> {
+        return this.httpRequest.request({
+            method: 'POST',
+            url: '/api/project/{project}/databases/{name}/restore',
+            path: {
+                'project': project,
+                'name': name,
+            },
+            body: requestBody,
+            mediaType: 'application/json',
+        });
+    }
+
     /**
      * Executes a statement through the owner, transactional, single-writer.
      * @param project
@@ -2,9 +2,11 @@
 /* tslint:disable */
 /* eslint-disable */
 import type { RunCancelDto } from '../models/RunCancelDto';
+import type { RunMigrateDto } from '../models/RunMigrateDto';
 import type { RunSignalDto } from '../models/RunSignalDto';
 import type { RunStartDto } from '../models/RunStartDto';
 import type { WorkflowDefinitionDto } from '../models/WorkflowDefinitionDto';
+import type { WorkflowJournalRowDto } from '../models/WorkflowJournalRowDto';
 import type { WorkflowRunDetailDto } from '../models/WorkflowRunDetailDto';
 import type { WorkflowRunDto } from '../models/WorkflowRunDto';
 
@@ -37,14 +39,17 @@ export class WorkflowsService {
     /**
      * The definition's runs, newest first, each with its journal-derived
      * status; the directory names them, the files answer for them.
+     * `status` keeps only runs in that state.
      * @param project
      * @param definition
+     * @param status
      * @returns WorkflowRunDto
      * @throws ApiError
      */
     public listRuns(
         project: string,
         definition: string,
+        status?: string,
     ): CancelablePromise<Array<WorkflowRunDto>> {
         return this.httpRequest.request({
             method: 'GET',
@@ -53,6 +58,9 @@ export class WorkflowsService {
                 'project': project,
                 'definition': definition,
             },
+            query: {
+                'status': status,
+            },
         });
     }
 
@@ -106,6 +114,36 @@ export class WorkflowsService {
         });
     }
 
+    /**
+     * The run's journal after `since`, oldest first: what a follower
+     * polls. Rows of an archived generation are included.
+     * @param project
+     * @param definition
+     * @param id
+     * @param since
+     * @returns WorkflowJournalRowDto
+     * @throws ApiError
+     */
+    public runJournal(
+        project: string,
+        definition: string,
+        id: string,
+        since?: number,
+    ): CancelablePromise<Array<WorkflowJournalRowDto>> {
+        return this.httpRequest.request({
+            method: 'GET',
+            url: '/api/project/{project}/workflows/{definition}/runs/{id}/journal',
+            path: {
+                'project': project,
+                'definition': definition,
+                'id': id,
+            },
+            query: {
+                'since': since,
+            },
+        });
+    }
+
     /**
      * Re-enters a failed run at its failed step with fresh attempts;
      * everything before it replays untouched.
@@ -131,6 +169,36 @@ export class WorkflowsService {
         });
     }
 
+    /**
+     * Moves the run onto another revision of its script, the current one
+     * unless named. Refused, with nothing changed, when the new code cannot
+     * replay the run's journal.
+     * @param project
+     * @param definition
+     * @param id
+     * @param requestBody
+     * @returns any
+     * @throws ApiError
+     */
+    public migrate(
+        project: string,
+        definition: string,
+        id: string,
+        requestBody: RunMigrateDto,
+    ): CancelablePromise<any> {
+        return this.httpRequest.request({
+            method: 'POST',
+            url: '/api/project/{project}/workflows/{definition}/runs/{id}/migrate',
+            path: {
+                'project': project,
+                'definition': definition,
+                'id': id,
+            },
+            body: requestBody,
+            mediaType: 'application/json',
+        });
+    }
+
     /**
      * Delivers a named signal into the run; a parked await resumes.
      * @param project
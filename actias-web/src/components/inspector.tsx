@@ -9,6 +9,9 @@ export const STATE_COLORS: Record<string, string> = {
   dropped: 'var(--err)',
   'in-flight': 'var(--viola)',
   pending: 'var(--warn)',
+  scheduled: 'var(--kind-kv)',
+  blocked: 'var(--warn)',
+  deduplicated: 'var(--ink-3)',
   retried: 'var(--warn)',
   delivered: 'var(--luna)',
   requeued: 'var(--luna)',
@@ -28,7 +28,14 @@ import classes from '../../../components/inspector.module.css';
 /** The message table's column template (design 03). */
 const COLUMNS = '188px 96px 74px minmax(0,1fr) 104px';
 
-type Tab = 'all' | 'pending' | 'in-flight' | 'delivered' | 'dead';
+type Tab =
+  | 'all'
+  | 'scheduled'
+  | 'pending'
+  | 'in-flight'
+  | 'blocked'
+  | 'delivered'
+  | 'dead';
 
 /** One table row: a live/dead message, or a delivered one reconstructed
  * from the journal (its row is gone; the journal is what remains). */
@@ -46,6 +53,8 @@ interface Row {
   enqueuedMs: number;
   nextMs?: number;
   producer?: string;
+  group?: string;
+  dedupKey?: string;
 }
 
 /** What the journal knows about one message GENERATION. Platform v2 ids
@@ -58,6 +67,10 @@ interface JournalInfo {
   preview?: string;
   size?: number;
   enqueuedMs?: number;
+  group?: string;
+  dedupKey?: string;
+  /** Repeat sends the dedup key suppressed while this message held it. */
+  duplicates: number;
   attempts: { label: string; at: number; error?: string }[];
   deliveredAt?: number;
   deliveredAttempt?: number;
@@ -76,7 +89,7 @@ function collectJournal(events: QueueEventDto[]): {
     if (!key) {
       key = `${id}#pre`;
       latest.set(id, key);
-      entries.set(key, { id, attempts: [] });
+      entries.set(key, { id, duplicates: 0, attempts: [] });
     }
     return entries.get(key)!;
   };
@@ -88,8 +101,11 @@ function collectJournal(events: QueueEventDto[]): {
       const key = `${id}#${event.seq}`;
       entries.set(key, {
         id,
+        duplicates: 0,
         attempts: [],
         preview: String(detail.preview ?? ''),
+        group: detail.group ? String(detail.group) : undefined,
+        dedupKey: detail.dedup_key ? String(detail.dedup_key) : undefined,
         size: Number(detail.size ?? 0),
         enqueuedMs: event.at,
         producer: detail.producer_script
@@ -105,6 +121,8 @@ function collectJournal(events: QueueEventDto[]): {
         at: event.at,
         error: detail.error ? String(detail.error) : undefined,
       });
+    } else if (event.kind === 'deduplicated') {
+      current(id).duplicates += 1;
     } else if (event.kind === 'delivered') {
       const entry = current(id);
       entry.deliveredAt = event.at;
@@ -234,12 +252,15 @@ function Queues({ project, write }: { project: ProjectDto; write: boolean }) {
         id: message.id,
         state: message.state,
         attempts: message.attempts,
+        maxAttempts: message.maxAttempts,
         preview: message.preview,
         payload: (message as QueueMessageDto & { payload?: string }).payload,
         size: message.size,
         enqueuedMs: message.enqueuedMs,
         nextMs: message.nextMs,
         producer: genKey ? journal.entries.get(genKey)?.producer : undefined,
+        group: message.group,
+        dedupKey: message.dedupKey,
       };
     });
     const liveGens = new Set(live.map((row) => row.genKey).filter(Boolean));
@@ -257,6 +278,8 @@ function Queues({ project, write }: { project: ProjectDto; write: boolean }) {
         size: info.size,
         enqueuedMs: info.enqueuedMs ?? info.deliveredAt,
         producer: info.producer,
+        group: info.group,
+        dedupKey: info.dedupKey,
       });
     });
     return [...live, ...delivered].sort((a, b) => b.enqueuedMs - a.enqueuedMs);
@@ -391,8 +414,18 @@ function Queues({ project, write }: { project: ProjectDto; write: boolean }) {
             onChange={setTab}
             options={[
               { value: 'all', label: 'All' },
+              {
+                value: 'scheduled',
+                label: 'Scheduled',
+                count: stats?.scheduled || undefined,
+              },
               { value: 'pending', label: 'Pending' },
               { value: 'in-flight', label: 'In flight' },
+              {
+                value: 'blocked',
+                label: 'Blocked',
+                count: stats?.blocked || undefined,
+              },
               { value: 'delivered', label: 'Delivered' },
               {
                 value: 'dead',
@@ -539,6 +572,17 @@ function Queues({ project, write }: { project: ProjectDto; write: boolean }) {
                 label="Consumer"
                 value={active.declaredBy || 'no live revision'}
               />
+              {selected.group && <Fact label="Group" value={selected.group} />}
+              {selected.dedupKey && (
+                <Fact
+                  label="Dedup key"
+                  value={
+                    selectedInfo?.duplicates
+                      ? `${selected.dedupKey} (${selectedInfo.duplicates} repeat(s) suppressed)`
+                      : selected.dedupKey
+                  }
+                />
+              )}
               <Fact
                 label="Enqueued"
                 value={timeAgo(selected.enqueuedMs)}
@@ -18,12 +18,13 @@ pub const FORBIDDEN: &str = "not available in workflow code, perform effects ins
 
 /// Where shimmed reads come from: recording on first execution,
 /// replaying from the journal afterwards. One per instance, installed as
-/// vm app data by whoever builds the workflow vm.
+/// vm app data by whoever builds the workflow vm. A read the journal
+/// disagrees with errors, and the shim raises it in the reading code.
 pub trait Determinism: Send + Sync {
     /// Unix seconds, journaled per read because time must advance.
-    fn time(&self) -> i64;
+    fn time(&self) -> Result<i64, String>;
     /// A v4-shaped id, journaled per call.
-    fn uuid(&self) -> String;
+    fn uuid(&self) -> Result<String, String>;
     /// A uniform draw in [0, 1) from the instance's own generator,
     /// seeded once in STARTED; engine-independent so a Luau upgrade can
     /// never bend a replay.
@@ -56,7 +57,9 @@ impl LuaExtension for JournaledUuidExtension {
         let uuid = lua.create_table()?;
         uuid.set(
             "v4",
-            lua.create_function(|lua, _: ()| Ok(source(lua)?.0.uuid()))?,
+            lua.create_function(|lua, _: ()| {
+                source(lua)?.0.uuid().map_err(mlua::Error::RuntimeError)
+            })?,
         )?;
         Ok(mlua::Value::Table(uuid))
     }
@@ -114,11 +117,16 @@ pub fn shim_stdlib(lua: &mlua::Lua) -> mlua::Result<()> {
     os.set_readonly(false);
     os.set(
         "time",
-        lua.create_function(|lua, _: mlua::MultiValue| Ok(source(lua)?.0.time()))?,
+        lua.create_function(|lua, _: mlua::MultiValue| {
+            source(lua)?.0.time().map_err(mlua::Error::RuntimeError)
+        })?,
     )?;
     os.set(
         "clock",
-        lua.create_function(|lua, _: ()| Ok(source(lua)?.0.time() as f64))?,
+        lua.create_function(|lua, _: ()| {
+            let seconds = source(lua)?.0.time().map_err(mlua::Error::RuntimeError)?;
+            Ok(seconds as f64)
+        })?,
     )?;
     os.set(
         "date",
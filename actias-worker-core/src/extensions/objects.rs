@@ -23,16 +23,23 @@ use crate::runtime::{ActiasRuntime, ContractKind};
 /// Registry key of the class-name-to-methods table in this vm.
 const CLASSES_KEY: &str = "object_classes";
 
+/// Class-table field listing the methods that only read: `readonly = {
+/// "topic", "members" }`. Only these may be served from a replica; every
+/// other method needs the lease holder.
+const READONLY_KEY: &str = "readonly";
+
 /// The built-in class names live in [`actias_common::classes`], because
 /// object identities cross service boundaries; re-exported here where the
 /// runtime consumes them. The router special-cases [`DATABASE_CLASS`]'s
 /// read methods for the mailbox bypass.
-pub use actias_common::classes::{CRON_CLASS, DATABASE_CLASS, QUEUE_CLASS, WORKFLOW_CLASS};
-
-/// Milliseconds until a cron event's next occurrence. The expression is
-/// whatever follows `cron:`; classic five-field expressions gain a seconds
-/// column, since the parser wants six.
-pub fn cron_delay_ms(event: &str) -> Result<i64, String> {
+pub use actias_common::classes::{
+    CRON_CLASS, DATABASE_CLASS, QUEUE_CLASS, SUBSCRIPTION_CLASS, TOPIC_CLASS, WORKFLOW_CLASS,
+};
+
+/// Parses a cron event's schedule. The expression is whatever follows
+/// `cron:`; classic five-field expressions gain a seconds column, since
+/// the parser wants six.
+fn cron_schedule(event: &str) -> Result<(cron::Schedule, &str), String> {
     use std::str::FromStr;
 
     let expr = event.strip_prefix("cron:").unwrap_or(event).trim();
@@ -44,12 +51,118 @@ pub fn cron_delay_ms(event: &str) -> Result<i64, String> {
 
     let schedule = cron::Schedule::from_str(&normalized)
         .map_err(|e| format!("'{expr}' is not a cron expression: {e}"))?;
-    let next = schedule
-        .upcoming(chrono::Utc)
-        .next()
-        .ok_or_else(|| format!("'{expr}' never occurs"))?;
+    Ok((schedule, expr))
+}
+
+/// Milliseconds until a cron event's next occurrence, in UTC; the
+/// declaration check, which only needs the expression to parse.
+pub fn cron_delay_ms(event: &str) -> Result<i64, String> {
+    Ok((cron_next_ms(event, chrono_tz::UTC)? - unix_now_ms()).max(1000))
+}
+
+/// Unix milliseconds of a cron event's next occurrence on `tz`'s clocks.
+pub fn cron_next_ms(event: &str, tz: chrono_tz::Tz) -> Result<i64, String> {
+    let (_, expr) = cron_schedule(event)?;
+    cron_occurrences_ms(event, tz, unix_now_ms(), i64::MAX, 1)?
+        .first()
+        .copied()
+        .ok_or_else(|| format!("'{expr}' never occurs"))
+}
+
+/// Unix milliseconds of a cron event's latest occurrence at or before
+/// now: the fire a late alarm stands for, whenever it actually ran.
+pub fn cron_occurrence_ms(event: &str, tz: chrono_tz::Tz) -> Result<i64, String> {
+    let (schedule, expr) = cron_schedule(event)?;
+    let now = unix_now_ms();
+    let start = wall_clock(tz, now + 1)?;
+    schedule
+        .after(&start)
+        .rev()
+        .map(|local| wall_clock_instant(tz, local.naive_utc()))
+        .find(|at| *at <= now)
+        .ok_or_else(|| format!("'{expr}' never occurred"))
+}
+
+/// Up to `limit` occurrences after `after_ms` and at or before
+/// `until_ms`, in order: the fires a node that was down for that window
+/// missed. Each instant occurs once however the clocks moved.
+pub fn cron_occurrences_ms(
+    event: &str,
+    tz: chrono_tz::Tz,
+    after_ms: i64,
+    until_ms: i64,
+    limit: usize,
+) -> Result<Vec<i64>, String> {
+    let (schedule, _) = cron_schedule(event)?;
+    let mut occurrences: Vec<i64> = Vec::new();
+    for local in schedule.after(&wall_clock(tz, after_ms)?) {
+        let at = wall_clock_instant(tz, local.naive_utc());
+        if at > until_ms || occurrences.len() >= limit {
+            break;
+        }
+        // A repeated hour's second pass, and every time a skipped hour
+        // resumes onto, stand for an instant already taken.
+        if at > after_ms && occurrences.last().is_none_or(|last| at > *last) {
+            occurrences.push(at);
+        }
+    }
+    Ok(occurrences)
+}
+
+/// Up to `limit` occurrences after `after_ms` and before `before_ms`,
+/// the newest ones, in order: the fires worth running after an outage
+/// too long to replay whole. Each instant occurs once, as for
+/// [`cron_occurrences_ms`].
+pub fn cron_latest_occurrences_ms(
+    event: &str,
+    tz: chrono_tz::Tz,
+    after_ms: i64,
+    before_ms: i64,
+    limit: usize,
+) -> Result<Vec<i64>, String> {
+    let (schedule, _) = cron_schedule(event)?;
+    let mut occurrences: Vec<i64> = Vec::new();
+    for local in schedule.after(&wall_clock(tz, before_ms)?).rev() {
+        let at = wall_clock_instant(tz, local.naive_utc());
+        if at <= after_ms || occurrences.len() >= limit {
+            break;
+        }
+        if at < before_ms && occurrences.last().is_none_or(|last| at < *last) {
+            occurrences.push(at);
+        }
+    }
+    occurrences.reverse();
+    Ok(occurrences)
+}
 
-    Ok((next.timestamp_millis() - unix_now_ms()).max(1000))
+/// The wall-clock reading of `tz` at `at_ms`, carried as a UTC value:
+/// cron expressions match clock readings, so the schedule iterates them
+/// and [`wall_clock_instant`] maps each back to a real instant.
+fn wall_clock(tz: chrono_tz::Tz, at_ms: i64) -> Result<chrono::DateTime<chrono::Utc>, String> {
+    let at = chrono::DateTime::from_timestamp_millis(at_ms)
+        .ok_or_else(|| "the clock is out of range".to_owned())?;
+    Ok(at.with_timezone(&tz).naive_local().and_utc())
+}
+
+/// The instant a clock reading stands for in `tz`. A reading the clocks
+/// repeat when they fall back fires on its first pass; one they skip when
+/// they spring forward fires as they resume, so a 02:30 job still runs on
+/// the night 02:30 never happens.
+fn wall_clock_instant(tz: chrono_tz::Tz, local: chrono::NaiveDateTime) -> i64 {
+    use chrono::TimeZone;
+
+    if let Some(at) = tz.from_local_datetime(&local).earliest() {
+        return at.timestamp_millis();
+    }
+    let mut resumed = local - chrono::Duration::seconds(local.and_utc().timestamp() % 60);
+    // No zone skips more than a day; the bound only keeps the loop finite.
+    for _ in 0..24 * 60 {
+        resumed += chrono::Duration::minutes(1);
+        if let Some(at) = tz.from_local_datetime(&resumed).earliest() {
+            return at.timestamp_millis();
+        }
+    }
+    local.and_utc().timestamp_millis()
 }
 
 /// Registry key of the object's state table; exists only in pinned vms,
@@ -79,6 +192,33 @@ pub struct ObjectTarget {
     /// Filled by the routing layer, which knows whose vm the call left;
     /// [`None`] for calls with no script behind them (dashboard reads).
     pub caller: Option<CallerIdentity>,
+    /// What the caller will accept instead of the lease holder; [`None`]
+    /// is a strong call. A router answering a target that carries this
+    /// replies `{ value, staleness_ms }`, so the handle can tell its
+    /// caller how old the answer may be.
+    pub consistency: Option<ReadConsistency>,
+    /// When the caller stops waiting: the tighter of the handle's own
+    /// `timeout` and the deadline of the call this one runs inside, so a
+    /// budget only ever shrinks down a chain. [`None`] waits as long as
+    /// the callee runs.
+    pub deadline: Option<std::time::Instant>,
+}
+
+/// The text every lapsed object call fails with, wherever along the chain
+/// it lapsed; scripts can tell a timeout from the callee's own failure by
+/// it.
+pub const CALL_TIMED_OUT: &str = "Object call timed out";
+
+/// `Class:get(name, { consistency = "replica", max_staleness = "5s" })`:
+/// a read-only method may be answered from a snapshot replica no older
+/// than the bound. A method its class did not mark read-only, or an object
+/// nothing has shipped yet, still goes to the holder; the option only ever
+/// widens where a read may be served, never what a call may do.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReadConsistency {
+    /// Oldest replica the caller accepts; [`None`] takes the node's
+    /// replica ttl.
+    pub max_staleness_ms: Option<i64>,
 }
 
 /// The call chain the currently dispatched method arrived on; app data in
@@ -86,6 +226,12 @@ pub struct ObjectTarget {
 /// vm runs exactly one call at a time.
 pub struct CallChain(pub Vec<String>);
 
+/// The deadline the currently running call must answer by; app data in
+/// pinned vms (set per call by the mailbox loop) and request vms (the
+/// request timeout). Outbound calls inherit it, which is how one caller's
+/// budget travels down the whole chain.
+pub struct CallDeadline(pub Option<std::time::Instant>);
+
 #[derive(Clone)]
 pub struct PendingAlarm {
     /// Unix milliseconds the alarm is due at.
@@ -119,33 +265,8 @@ pub fn unix_now_ms() -> i64 {
         + TEST_CLOCK_OFFSET_MS.load(std::sync::atomic::Ordering::Relaxed)
 }
 
-/// A duration written the way scripts write them: "500ms", "30s", "10m",
-/// "24h", "7d", or a bare number of seconds.
-pub fn parse_duration_ms(raw: &str) -> Result<i64, String> {
-    let raw = raw.trim();
-    if let Ok(seconds) = raw.parse::<f64>() {
-        return Ok((seconds * 1000.0) as i64);
-    }
-
-    let split = raw
-        .find(|c: char| !c.is_ascii_digit() && c != '.')
-        .ok_or_else(|| format!("'{raw}' is not a duration."))?;
-    let (number, unit) = raw.split_at(split);
-    let number: f64 = number
-        .parse()
-        .map_err(|_| format!("'{raw}' is not a duration."))?;
-
-    let factor = match unit.trim() {
-        "ms" => 1.0,
-        "s" => 1000.0,
-        "m" => 60.0 * 1000.0,
-        "h" => 3600.0 * 1000.0,
-        "d" => 86400.0 * 1000.0,
-        other => return Err(format!("Unknown duration unit '{other}'.")),
-    };
-
-    Ok((number * factor) as i64)
-}
+/// Durations read the same at publish and at run time.
+pub use actias_declarations::parse_duration_ms;
 
 /// What `__dispatch` receives from the mailbox, mirroring [`ObjectTarget`]
 /// minus the name, which the pinned vm embodies rather than reads.
@@ -162,6 +283,10 @@ struct DispatchCall {
     /// method's outbound calls to extend.
     #[serde(default)]
     chain: Vec<String>,
+    /// Set when the call runs against a replica: only methods the class
+    /// marked `readonly` may run, and `init` never does.
+    #[serde(default)]
+    read_only: bool,
 }
 
 /// How a method call leaves this vm: the worker supplies the routing (id
@@ -201,14 +326,37 @@ impl LuaExtension for ObjectExtension {
 
         // `queue "name"`: a durable message queue, sugar over an object of
         // the built-in class. `:send` enqueues; the revision declaring
-        // `on "queue:<name>"` consumes, driven by the object's alarm.
+        // `on "queue:<name>"` consumes, driven by the object's alarm. The
+        // optional curried table, `queue "name" { max_attempts = 10 }`, is
+        // the delivery policy; publish already resolved it into the
+        // contract, so here it is only absorbed and the handle returned.
         lua.globals().set(
             "queue",
             lua.create_function(|lua, name: String| {
                 ActiasRuntime::assert_declaration_phase(lua, "queue")?;
                 ActiasRuntime::assert_contract_allows(lua, ContractKind::Queue, &name)?;
                 ActiasRuntime::record_queue_declaration(lua, &name);
-                instance_handle(lua, QUEUE_CLASS.to_owned(), name)
+                let handle = instance_handle(lua, QUEUE_CLASS.to_owned(), name)?;
+                if let Some(meta) = handle.metatable() {
+                    meta.set(
+                        "__call",
+                        lua.create_function(|_, (this, _policy): (Table, mlua::Value)| Ok(this))?,
+                    )?;
+                }
+                Ok(handle)
+            })?,
+        )?;
+
+        // `topic "name"`: a fan-out log, sugar over an object of the
+        // built-in class. `:publish` appends once; every script declaring
+        // `on "topic:<name>"` receives it through its own subscription.
+        lua.globals().set(
+            "topic",
+            lua.create_function(|lua, name: String| {
+                ActiasRuntime::assert_declaration_phase(lua, "topic")?;
+                ActiasRuntime::assert_contract_allows(lua, ContractKind::Topic, &name)?;
+                ActiasRuntime::record_topic_declaration(lua, &name);
+                instance_handle(lua, TOPIC_CLASS.to_owned(), name)
             })?,
         )?;
 
@@ -264,22 +412,103 @@ impl LuaExtension for ObjectExtension {
     }
 }
 
-/// The class handle: `Class:get(name)` mints an instance handle.
+/// The class handle: `Class:get(name)` mints an instance handle;
+/// `Class:get(name, { consistency = "replica", max_staleness = "5s" })`
+/// mints one whose read-only methods may be served from a replica, and
+/// `{ timeout = "2s" }` bounds how long each of its calls may take.
 fn class_handle(lua: &Lua, class: String) -> mlua::Result<Table> {
     let handle = lua.create_table()?;
     handle.set("__class", class)?;
 
     handle.set(
         "get",
-        lua.create_function(|lua, (this, name): (Table, String)| {
-            let class: String = this.get("__class")?;
-            instance_handle(lua, class, name)
-        })?,
+        lua.create_function(
+            |lua, (this, name, options): (Table, String, Option<Table>)| {
+                let class: String = this.get("__class")?;
+                let instance = instance_handle(lua, class, name)?;
+                if let Some(options) = &options {
+                    instance.set("__timeout_ms", duration_option(options, "timeout")?)?;
+                }
+                if let Some(consistency) = read_consistency(options)? {
+                    instance.set("__consistency", "replica")?;
+                    instance.set("__max_staleness_ms", consistency.max_staleness_ms)?;
+                }
+                Ok(instance)
+            },
+        )?,
     )?;
 
     Ok(handle)
 }
 
+/// The options table `Class:get` takes. `consistency = "strong"` (the
+/// default) reads as [`None`]; `"replica"` takes an optional
+/// `max_staleness`, written like any other duration.
+fn read_consistency(options: Option<Table>) -> mlua::Result<Option<ReadConsistency>> {
+    let Some(options) = options else {
+        return Ok(None);
+    };
+
+    let consistency: Option<String> = options.get("consistency")?;
+    match consistency.as_deref() {
+        None | Some("strong") => Ok(None),
+        Some("replica") => {
+            let max_staleness_ms = duration_option(&options, "max_staleness")?;
+            Ok(Some(ReadConsistency { max_staleness_ms }))
+        }
+        Some(other) => Err(mlua::Error::RuntimeError(format!(
+            "consistency is \"strong\" or \"replica\", not '{other}'."
+        ))),
+    }
+}
+
+/// One duration field of an options table, written like any other
+/// duration; [`None`] when absent.
+fn duration_option(options: &Table, field: &str) -> mlua::Result<Option<i64>> {
+    let ms = match options.get::<mlua::Value>(field)? {
+        mlua::Value::Nil => return Ok(None),
+        mlua::Value::String(raw) => {
+            parse_duration_ms(&raw.to_str()?).map_err(mlua::Error::RuntimeError)?
+        }
+        mlua::Value::Integer(seconds) => seconds.saturating_mul(1000),
+        // Clamped first, so a script's huge or infinite number saturates
+        // the way the integer does rather than by the cast's accident.
+        mlua::Value::Number(seconds) => {
+            (seconds * 1000.0).clamp(i64::MIN as f64, i64::MAX as f64) as i64
+        }
+        _ => {
+            return Err(mlua::Error::RuntimeError(format!(
+                "{field} takes a duration: \"5s\", \"500ms\" or seconds."
+            )));
+        }
+    };
+    if ms < 0 {
+        return Err(mlua::Error::RuntimeError(format!(
+            "{field} cannot be negative."
+        )));
+    }
+    Ok(Some(ms))
+}
+
+/// Whether `class` in this vm lists `method` under `readonly`; false for
+/// classes this vm never declared.
+pub fn is_read_only_method(lua: &Lua, class: &str, method: &str) -> bool {
+    let Ok(classes) = lua.named_registry_value::<Table>(CLASSES_KEY) else {
+        return false;
+    };
+    let Ok(Some(readonly)) = classes
+        .get::<Table>(class)
+        .and_then(|class| class.get::<Option<Table>>(READONLY_KEY))
+    else {
+        return false;
+    };
+
+    readonly
+        .sequence_values::<String>()
+        .flatten()
+        .any(|listed| listed == method)
+}
+
 /// The workflow definition handle `workflow "name" (fn)` returns and
 /// `workflows "name"` looks up: `start` mints a run and kicks its first
 /// attempt, `get` addresses an existing run. Run handles are ordinary
@@ -349,7 +578,9 @@ pub(crate) fn workflow_definition_handle(lua: &Lua, definition: String) -> mlua:
     Ok(handle)
 }
 
-/// The instance handle: any method name resolves to a routed call.
+/// The instance handle: any method name resolves to a routed call. A
+/// handle minted with replica consistency returns the value and the
+/// staleness served, in milliseconds (zero when the holder answered).
 fn instance_handle(lua: &Lua, class: String, name: String) -> mlua::Result<Table> {
     let handle = lua.create_table()?;
     handle.set("__class", class)?;
@@ -361,11 +592,21 @@ fn instance_handle(lua: &Lua, class: String, name: String) -> mlua::Result<Table
         lua.create_function(|lua, (this, method): (Table, String)| {
             let class: String = this.get("__class")?;
             let name: String = this.get("__name")?;
+            // Raw reads: a missing field must not route through this very
+            // metamethod and come back as a method call.
+            let consistency = match this.raw_get::<Option<String>>("__consistency")? {
+                Some(_) => Some(ReadConsistency {
+                    max_staleness_ms: this.raw_get("__max_staleness_ms")?,
+                }),
+                None => None,
+            };
+            let timeout_ms: Option<i64> = this.raw_get("__timeout_ms")?;
 
             lua.create_async_function(move |lua, args: mlua::MultiValue| {
                 let class = class.clone();
                 let name = name.clone();
                 let method = method.clone();
+                let consistency = consistency.clone();
 
                 async move {
                     // In workflow vms, effects live inside steps alone;
@@ -380,7 +621,7 @@ fn instance_handle(lua: &Lua, class: String, name: String) -> mlua::Result<Table
                         arguments.push(lua.from_value::<serde_json::Value>(value)?);
                     }
 
-                    let (router, chain) = {
+                    let (router, chain, inherited) = {
                         let Some(router) = lua.app_data_ref::<ObjectRouter>() else {
                             return Err(mlua::Error::RuntimeError(
                                 "Objects are not available in this runtime.".to_owned(),
@@ -390,9 +631,20 @@ fn instance_handle(lua: &Lua, class: String, name: String) -> mlua::Result<Table
                             .app_data_ref::<CallChain>()
                             .map(|chain| chain.0.clone())
                             .unwrap_or_default();
-                        (router.clone(), chain)
+                        let inherited = lua
+                            .app_data_ref::<CallDeadline>()
+                            .and_then(|deadline| deadline.0);
+                        (router.clone(), chain, inherited)
+                    };
+                    let own = timeout_ms.map(|ms| {
+                        std::time::Instant::now() + std::time::Duration::from_millis(ms as u64)
+                    });
+                    let deadline = match (own, inherited) {
+                        (Some(own), Some(inherited)) => Some(own.min(inherited)),
+                        (own, inherited) => own.or(inherited),
                     };
 
+                    let replica = consistency.is_some();
                     let result = router(ObjectTarget {
                         class,
                         name,
@@ -401,11 +653,22 @@ fn instance_handle(lua: &Lua, class: String, name: String) -> mlua::Result<Table
                         chain,
                         // The router knows whose vm this is; it fills this.
                         caller: None,
+                        consistency,
+                        deadline,
                     })
                     .await
                     .map_err(mlua::Error::RuntimeError)?;
 
-                    lua.to_value(&result)
+                    let mut values = mlua::MultiValue::new();
+                    if replica {
+                        values.push_back(lua.to_value(&result["value"])?);
+                        values.push_back(mlua::Value::Integer(
+                            result["staleness_ms"].as_i64().unwrap_or(0),
+                        ));
+                    } else {
+                        values.push_back(lua.to_value(&result)?);
+                    }
+                    Ok(values)
                 }
             })
         })?,
@@ -429,8 +692,10 @@ fn set_alarm(lua: &Lua, (_this, duration): (Table, mlua::Value)) -> mlua::Result
         mlua::Value::String(raw) => {
             parse_duration_ms(&raw.to_str()?).map_err(mlua::Error::RuntimeError)?
         }
-        mlua::Value::Integer(seconds) => seconds * 1000,
-        mlua::Value::Number(seconds) => (seconds * 1000.0) as i64,
+        mlua::Value::Integer(seconds) => seconds.saturating_mul(1000),
+        mlua::Value::Number(seconds) => {
+            (seconds * 1000.0).clamp(i64::MIN as f64, i64::MAX as f64) as i64
+        }
         _ => {
             return Err(mlua::Error::RuntimeError(
                 "set_alarm takes a duration: \"30s\", \"24h\" or seconds.".to_owned(),
@@ -450,7 +715,7 @@ fn set_alarm(lua: &Lua, (_this, duration): (Table, mlua::Value)) -> mlua::Result
         .unwrap_or_default();
 
     let alarm = PendingAlarm {
-        due_ms: unix_now_ms() + delay_ms,
+        due_ms: unix_now_ms().saturating_add(delay_ms),
         class,
         name,
         own_key,
@@ -575,6 +840,13 @@ fn install_dispatch(lua: &Lua) -> mlua::Result<()> {
                     call.class, call.method
                 ))
             })?;
+            if call.read_only && !is_read_only_method(&lua, &call.class, &call.method) {
+                return Err(mlua::Error::RuntimeError(format!(
+                    "Method '{}' of '{}' is not marked readonly; only the object's home \
+                     may run it.",
+                    call.method, call.class
+                )));
+            }
 
             // The state table is the object's identity surface: plain keys
             // are in-memory and live as long as the pinned vm; `state.sql`
@@ -603,7 +875,8 @@ fn install_dispatch(lua: &Lua) -> mlua::Result<()> {
             // `init` runs exactly once per object: for stored objects the
             // file is the record (a failed init retries next call); without
             // storage, once per vm life, which is when state is fresh too.
-            if state_is_new && call.method != "init" {
+            // A replica cannot record it, so a read-only call never runs it.
+            if state_is_new && !call.read_only && call.method != "init" {
                 // Cloned out rather than borrowed: the app-data guard must
                 // not live across the init await below.
                 let home = lua
@@ -638,23 +911,3 @@ fn install_dispatch(lua: &Lua) -> mlua::Result<()> {
 
     Ok(())
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn durations_read_the_way_scripts_write_them() {
-        assert_eq!(parse_duration_ms("500ms").unwrap(), 500);
-        assert_eq!(parse_duration_ms("30s").unwrap(), 30_000);
-        assert_eq!(parse_duration_ms("10m").unwrap(), 600_000);
-        assert_eq!(parse_duration_ms("24h").unwrap(), 86_400_000);
-        assert_eq!(parse_duration_ms("7d").unwrap(), 604_800_000);
-        assert_eq!(parse_duration_ms("1.5s").unwrap(), 1500);
-        // A bare number is seconds.
-        assert_eq!(parse_duration_ms("2").unwrap(), 2000);
-
-        assert!(parse_duration_ms("soon").is_err());
-        assert!(parse_duration_ms("10 fortnights").is_err());
-    }
-}
@@ -20,6 +20,10 @@ use crate::runtime::ActiasRuntime;
 /// never a drop policy.
 const MAILBOX_DEPTH: usize = 128;
 
+/// What a spawn on a closed host fails with; the object's next home is
+/// wherever the caller claims it after this node lets go.
+pub const NODE_DRAINING: &str = "This node is draining";
+
 /// Why a call did not return a value.
 #[derive(Debug)]
 pub enum ObjectError {
@@ -29,6 +33,9 @@ pub enum ObjectError {
     /// The object's task is gone; the caller should resolve the object
     /// again rather than retry blindly.
     Gone,
+    /// The call's deadline lapsed, queued or running. A queued call never
+    /// ran; a running one may still finish and commit, unheard.
+    TimedOut,
 }
 
 impl std::fmt::Display for ObjectError {
@@ -36,6 +43,7 @@ impl std::fmt::Display for ObjectError {
         match self {
             ObjectError::Call(message) => f.write_str(message),
             ObjectError::Gone => f.write_str("the object's task is gone"),
+            ObjectError::TimedOut => f.write_str(crate::extensions::objects::CALL_TIMED_OUT),
         }
     }
 }
@@ -46,6 +54,8 @@ impl std::error::Error for ObjectError {}
 struct ObjectCall {
     method: String,
     payload: serde_json::Value,
+    /// Past this, nobody is listening: the loop drops the call unrun.
+    deadline: Option<std::time::Instant>,
     reply: oneshot::Sender<Result<serde_json::Value, ObjectError>>,
 }
 
@@ -53,6 +63,11 @@ struct ObjectCall {
 #[derive(Clone)]
 pub struct ObjectHandle {
     sender: mpsc::Sender<ObjectCall>,
+    /// Calls sent since the host last sampled; what makes an object hot.
+    calls: Arc<std::sync::atomic::AtomicU64>,
+    /// Never written: the task drops its sender on exit, which is the
+    /// one signal a handoff waits for.
+    ended: tokio::sync::watch::Receiver<()>,
 }
 
 impl ObjectHandle {
@@ -66,19 +81,51 @@ impl ObjectHandle {
         &self,
         method: &str,
         payload: serde_json::Value,
+    ) -> Result<serde_json::Value, ObjectError> {
+        self.call_until(method, payload, None).await
+    }
+
+    /// [`ObjectHandle::call`] with a deadline: the wait for mailbox room
+    /// and for the answer both end there, and a call still queued when it
+    /// lapses is dropped by the object's loop instead of running for a
+    /// caller that already left. A running call's own budget shrinks to
+    /// fit, so the vm is not held past it either.
+    ///
+    /// # Errors
+    /// Returns [`ObjectError::TimedOut`] when the deadline lapses, plus
+    /// everything [`ObjectHandle::call`] returns.
+    pub async fn call_until(
+        &self,
+        method: &str,
+        payload: serde_json::Value,
+        deadline: Option<std::time::Instant>,
     ) -> Result<serde_json::Value, ObjectError> {
         let (reply, response) = oneshot::channel();
+        self.calls
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+
+        let exchange = async {
+            self.sender
+                .send(ObjectCall {
+                    method: method.to_owned(),
+                    payload,
+                    deadline,
+                    reply,
+                })
+                .await
+                .map_err(|_| ObjectError::Gone)?;
 
-        self.sender
-            .send(ObjectCall {
-                method: method.to_owned(),
-                payload,
-                reply,
-            })
-            .await
-            .map_err(|_| ObjectError::Gone)?;
+            response.await.map_err(|_| ObjectError::Gone)?
+        };
 
-        response.await.map_err(|_| ObjectError::Gone)?
+        match deadline {
+            Some(deadline) => {
+                tokio::time::timeout_at(tokio::time::Instant::from_std(deadline), exchange)
+                    .await
+                    .map_err(|_| ObjectError::TimedOut)?
+            }
+            None => exchange.await,
+        }
     }
 }
 
@@ -98,6 +145,22 @@ pub type AfterWrite =
 /// dangerous direction (a missing row) is healed by the spawn-time sync.
 pub type AlarmSync = Arc<dyn Fn(Option<i64>) + Send + Sync>;
 
+/// What a settled object asked its host to do once its task has ended:
+/// forget it everywhere, keeping `archive` (the JSON it wants preserved)
+/// in the blob store first when there is one. `due_ms` is when the purge
+/// came due; a host that cannot finish re-arms the object's alarm to try
+/// again, waiting longer the longer the purge is overdue.
+pub struct PurgeRequest {
+    pub archive: Option<serde_json::Value>,
+    pub due_ms: i64,
+}
+
+/// Carries out a [`PurgeRequest`]: the file, the shipped snapshot and
+/// the directory row go. Runs after the vm and its storage dropped, so
+/// nothing holds the file any more.
+pub type OnPurge =
+    Arc<dyn Fn(PurgeRequest) -> std::pin::Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>;
+
 /// Everything the pinned task owns about its object, in one place: the
 /// task is the owner, and the vm holds a clone of the [`Arc`] as app data
 /// so the Lua extension surface (`state.sql`, `state:set_alarm`) reaches
@@ -111,6 +174,8 @@ pub struct ObjectHome {
     alarm: std::sync::Mutex<Option<crate::extensions::objects::PendingAlarm>>,
     ship_mark: std::sync::atomic::AtomicI64,
     migrations_checked: std::sync::atomic::AtomicBool,
+    retiring: std::sync::atomic::AtomicBool,
+    purge: std::sync::Mutex<Option<PurgeRequest>>,
     queue_policy: crate::platform::queue::QueuePolicy,
     revision: Option<Arc<crate::runtime::PreparedRevision>>,
     /// The registry mirror, when the host wired one; invoked wherever the
@@ -131,6 +196,8 @@ impl ObjectHome {
             alarm: std::sync::Mutex::new(pending),
             ship_mark: std::sync::atomic::AtomicI64::new(0),
             migrations_checked: std::sync::atomic::AtomicBool::new(false),
+            retiring: std::sync::atomic::AtomicBool::new(false),
+            purge: std::sync::Mutex::new(None),
             queue_policy,
             revision,
             alarm_sync,
@@ -254,6 +321,32 @@ impl ObjectHome {
             .store(true, std::sync::atomic::Ordering::Relaxed);
     }
 
+    /// Ends the task once the calls already queued are answered: the
+    /// mailbox closes after the current call, and the next touch builds
+    /// a fresh vm, exactly as after hibernation. For a call whose write
+    /// changes what the vm must be built from.
+    pub fn retire(&self) {
+        self.retiring
+            .store(true, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    /// Retires the task for good: once the queued calls are answered the
+    /// host's purge hook deletes the object. A touch meanwhile waits for
+    /// the hook; one after it finds nothing and starts from empty.
+    pub fn purge(&self, request: PurgeRequest) {
+        *lock_unpoisoned(&self.purge) = Some(request);
+        self.retire();
+    }
+
+    fn take_purge(&self) -> Option<PurgeRequest> {
+        lock_unpoisoned(&self.purge).take()
+    }
+
+    /// Whether [`ObjectHome::retire`] has run this vm life.
+    fn is_retiring(&self) -> bool {
+        self.retiring.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
     /// Delivery limits for `__queue` instances.
     pub fn queue_policy(&self) -> &crate::platform::queue::QueuePolicy {
         &self.queue_policy
@@ -296,6 +389,9 @@ pub struct TaskOptions {
     /// Delivery limits for `__queue` instances; the default is the
     /// production policy.
     pub queue: crate::platform::queue::QueuePolicy,
+    /// Deletes the object when it asks to be purged; [`None`] only ends
+    /// the task (tests, embedded runs).
+    pub on_purge: Option<OnPurge>,
 }
 
 pub fn spawn_object_task(runtime: ActiasRuntime, options: TaskOptions) -> ObjectHandle {
@@ -308,9 +404,11 @@ pub fn spawn_object_task(runtime: ActiasRuntime, options: TaskOptions) -> Object
         after_write,
         alarm_sync,
         queue,
+        on_purge,
     } = options;
 
     let (sender, mut receiver) = mpsc::channel::<ObjectCall>(MAILBOX_DEPTH);
+    let (ended_signal, ended) = tokio::sync::watch::channel(());
 
     // A persisted alarm re-arms the moment the object is resident again;
     // past-due fires immediately. (A cold object with a due alarm still
@@ -348,6 +446,10 @@ pub fn spawn_object_task(runtime: ActiasRuntime, options: TaskOptions) -> Object
     runtime.set_app_data(home.clone());
 
     tokio::spawn(async move {
+        // Held for the task's life; dropping it tells a handoff the last
+        // queued call has answered.
+        let _ended_signal = ended_signal;
+
         // Popping only after the previous call finished is the input gate;
         // there is deliberately no concurrency inside this loop. A due
         // alarm is just one more message source, so it serializes with
@@ -367,6 +469,9 @@ pub fn spawn_object_task(runtime: ActiasRuntime, options: TaskOptions) -> Object
                     _ = tokio::time::sleep(std::time::Duration::from_millis(wait as u64)) => {
                         fire_alarm(&runtime, &home, alarm, call_budget, after_write.as_ref())
                             .await;
+                        if home.is_retiring() {
+                            receiver.close();
+                        }
                         continue;
                     }
                 }
@@ -387,12 +492,24 @@ pub fn spawn_object_task(runtime: ActiasRuntime, options: TaskOptions) -> Object
                 }
             };
 
+            // The caller already gave up: running it now would only hold
+            // the mailbox for work nobody hears back about.
+            if call
+                .deadline
+                .is_some_and(|deadline| deadline <= std::time::Instant::now())
+            {
+                let _ = call.reply.send(Err(ObjectError::TimedOut));
+                continue;
+            }
+
+            // Outbound calls made by this one inherit its deadline.
+            runtime.set_app_data(crate::extensions::objects::CallDeadline(call.deadline));
             let result = guarded_dispatch(
                 &runtime,
                 &home,
                 &call.method,
                 call.payload,
-                call_budget,
+                budget_within(call_budget, call.deadline),
                 after_write.as_ref(),
             )
             .await;
@@ -400,10 +517,31 @@ pub fn spawn_object_task(runtime: ActiasRuntime, options: TaskOptions) -> Object
             // A caller that stopped waiting is its own problem; the state
             // change it asked for has already happened either way.
             let _ = call.reply.send(result);
+
+            // Closing refuses new calls (the host respawns on the next
+            // touch) while the ones already queued drain here first.
+            if home.is_retiring() {
+                receiver.close();
+            }
+        }
+
+        // The vm and the storage it holds drop first, so the hook deletes
+        // a file nothing has open.
+        if let Some(request) = home.take_purge() {
+            drop(runtime);
+            drop(home);
+            match &on_purge {
+                Some(on_purge) => on_purge(request).await,
+                None => actias_common::tracing::debug!("purged object has no host hook"),
+            }
         }
     });
 
-    ObjectHandle { sender }
+    ObjectHandle {
+        sender,
+        calls: Arc::default(),
+        ended,
+    }
 }
 
 /// Runs one due alarm: cleared before dispatch, so a handler that sets the
@@ -417,6 +555,8 @@ async fn fire_alarm(
     after_write: Option<&AfterWrite>,
 ) {
     home.clear_alarm();
+    // Nobody waits on an alarm; only the call budget bounds it.
+    runtime.set_app_data(crate::extensions::objects::CallDeadline(None));
 
     let result = guarded_dispatch(
         runtime,
@@ -439,6 +579,22 @@ async fn fire_alarm(
     }
 }
 
+/// The budget one call runs under: the task's own, shrunk to the whole
+/// seconds left before the caller's deadline (never below one, so a call
+/// that made it out of the queue gets to start).
+fn budget_within(call_budget: Option<u64>, deadline: Option<std::time::Instant>) -> Option<u64> {
+    let Some(deadline) = deadline else {
+        return call_budget;
+    };
+    let left = deadline
+        .saturating_duration_since(std::time::Instant::now())
+        .as_secs_f64()
+        .ceil()
+        .max(1.0) as u64;
+
+    Some(call_budget.map_or(left, |budget| budget.min(left)))
+}
+
 /// One dispatched call, fully guarded: its own budget, its own
 /// transaction (a failed method persists nothing partial), and the
 /// checkpoint before any caller hears the result.
@@ -505,6 +661,47 @@ async fn guarded_dispatch(
     result
 }
 
+/// Runs one call against a read-only copy of an object's file, outside any
+/// mailbox: the replica half of `consistency = "replica"`. There is no
+/// lease, no alarm and no output gate, because nothing here may change;
+/// the connection refuses writes and the dispatch refuses any method its
+/// class did not mark `readonly`. One read transaction spans the call, so
+/// the method sees a single snapshot however many statements it runs.
+///
+/// # Errors
+/// Returns [`ObjectError::Call`] for a refused or failed method, exactly
+/// like a mailbox call would.
+pub async fn dispatch_read_only(
+    runtime: &ActiasRuntime,
+    storage: crate::storage::SqliteStorage,
+    mut payload: serde_json::Value,
+    call_budget: Option<u64>,
+) -> Result<serde_json::Value, ObjectError> {
+    let home = Arc::new(ObjectHome::new(
+        Some(storage),
+        None,
+        crate::platform::queue::QueuePolicy::default(),
+        runtime
+            .app_data_ref::<Arc<crate::runtime::PreparedRevision>>()
+            .map(|revision| revision.clone()),
+        None,
+    ));
+    runtime.set_app_data(home.clone());
+    payload["read_only"] = serde_json::Value::Bool(true);
+
+    home.with_storage(|storage| storage.begin())
+        .map_err(|error| ObjectError::Call(format!("The replica could not be read: {error}")))?;
+    if let Some(seconds) = call_budget {
+        runtime.begin_call_budget(seconds);
+    }
+    let result = dispatch(runtime, "__dispatch", payload).await;
+    runtime.end_call_budget();
+    // Nothing was written; ending the read is all either branch needs.
+    let _ = home.with_storage(|storage| storage.rollback());
+
+    result
+}
+
 /// Extends a call chain onto `key`, refusing cycles.
 ///
 /// Every routed call carries the keys already on its stack; a target that
@@ -556,6 +753,31 @@ async fn dispatch(
 #[derive(Default)]
 pub struct ObjectHost {
     tasks: Mutex<HashMap<String, (String, ObjectHandle)>>,
+    /// Objects mid-handoff; anyone resolving one waits for the drain to
+    /// finish, so a second vm never opens the file while the first is
+    /// still answering its queue.
+    draining: std::sync::Mutex<HashMap<String, tokio::sync::watch::Receiver<()>>>,
+    /// Set once, at shutdown: resident objects keep answering until they
+    /// are drained, but nothing new is built here again.
+    closed: std::sync::atomic::AtomicBool,
+}
+
+/// An object drained out of this node and not yet released: resolving it
+/// waits until this drops. What the holder does in between (ship, release
+/// the lease) is the whole handoff.
+pub struct Handoff<'a> {
+    host: &'a ObjectHost,
+    id: String,
+    /// Never written; dropping it wakes the waiters.
+    _signal: tokio::sync::watch::Sender<()>,
+}
+
+impl Drop for Handoff<'_> {
+    fn drop(&mut self) {
+        // The entry goes first, the signal (a field) right after, so a
+        // woken waiter never finds a stale entry and waits again.
+        lock_unpoisoned(&self.host.draining).remove(&self.id);
+    }
 }
 
 impl ObjectHost {
@@ -568,8 +790,14 @@ impl ObjectHost {
     /// never both build a vm for one object; correctness first, and object
     /// construction is rare next to calls.
     ///
+    /// A retired task that is still finishing (answering its queued
+    /// calls, or running its purge hook) fences `id` the way a handoff
+    /// does: the fresh vm waits for it to end, so it never opens a file
+    /// the hook is about to delete.
+    ///
     /// # Errors
     /// Returns whatever the factory failed with; nothing is registered.
+    /// A closed host fails with [`NODE_DRAINING`] without running it.
     pub async fn get_or_spawn<F, Fut>(
         &self,
         id: &str,
@@ -580,6 +808,19 @@ impl ObjectHost {
         F: FnOnce() -> Fut,
         Fut: Future<Output = mlua::Result<(ActiasRuntime, TaskOptions)>>,
     {
+        self.settled(id).await;
+        let ending = self
+            .tasks
+            .lock()
+            .await
+            .get(id)
+            .filter(|(_, handle)| handle.sender.is_closed())
+            .map(|(_, handle)| handle.ended.clone());
+        if let Some(mut ended) = ending {
+            // Immediate for a task that already ended, hibernated ones
+            // included; the registry lock is not held meanwhile.
+            let _ = ended.changed().await;
+        }
         let mut tasks = self.tasks.lock().await;
 
         // A hibernated task's sender reads closed; it respawns exactly
@@ -590,6 +831,9 @@ impl ObjectHost {
         {
             return Ok(handle.clone());
         }
+        if self.is_closed() {
+            return Err(mlua::Error::RuntimeError(format!("{NODE_DRAINING}.")));
+        }
 
         let (runtime, options) = factory().await?;
         let handle = spawn_object_task(runtime, options);
@@ -619,11 +863,122 @@ impl ObjectHost {
             .is_some_and(|(_, handle)| !handle.sender.is_closed())
     }
 
+    /// Every object with a live task, in no particular order.
+    pub async fn resident_ids(&self) -> Vec<String> {
+        self.tasks
+            .lock()
+            .await
+            .iter()
+            .filter(|(_, (_, handle))| !handle.sender.is_closed())
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+
+    /// Stops this host from building objects, for good. Resident ones
+    /// keep serving their callers until they are handed off.
+    pub fn close(&self) {
+        self.closed.store(true, std::sync::atomic::Ordering::SeqCst);
+    }
+
+    /// Whether [`ObjectHost::close`] has run.
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(std::sync::atomic::Ordering::SeqCst)
+    }
+
     /// Drops an object's registry entry; its task ends once in-flight
     /// callers finish. The next access builds a fresh vm.
     pub async fn evict(&self, id: &str) {
         self.tasks.lock().await.remove(id);
     }
+
+    /// Takes `id` out of service for a handoff and waits until its task
+    /// has answered every call already queued: nothing in flight is ever
+    /// dropped, and every write among them has passed the output gate.
+    /// [`None`] when the object is not resident (or already handing off),
+    /// leaving nothing to do.
+    ///
+    /// Until the returned guard drops, resolving `id` waits, so the lease
+    /// can be released before anyone here builds a fresh vm.
+    pub async fn begin_handoff(&self, id: &str) -> Option<Handoff<'_>> {
+        let (handoff, resident) = self.take_out_of_service(id).await?;
+        resident.then_some(handoff)
+    }
+
+    /// Like [`ObjectHost::begin_handoff`], but holds `id` whether or not
+    /// it was resident: a restore swaps the file underneath, and no vm
+    /// may be built over the old one meanwhile. [`None`] only when a
+    /// handoff is already in progress.
+    pub async fn begin_replace(&self, id: &str) -> Option<Handoff<'_>> {
+        self.take_out_of_service(id)
+            .await
+            .map(|(handoff, _)| handoff)
+    }
+
+    /// The guard plus whether a live task was drained under it.
+    async fn take_out_of_service(&self, id: &str) -> Option<(Handoff<'_>, bool)> {
+        let (signal, waiters) = tokio::sync::watch::channel(());
+        {
+            let mut draining = lock_unpoisoned(&self.draining);
+            if draining.contains_key(id) {
+                return None;
+            }
+            draining.insert(id.to_owned(), waiters);
+        }
+        let handoff = Handoff {
+            host: self,
+            id: id.to_owned(),
+            _signal: signal,
+        };
+
+        let Some((_, handle)) = self
+            .tasks
+            .lock()
+            .await
+            .remove(id)
+            .filter(|(_, handle)| !handle.sender.is_closed())
+        else {
+            return Some((handoff, false));
+        };
+        let mut ended = handle.ended.clone();
+        // The registry's handle was one sender; callers mid-call hold the
+        // rest, and the task ends once they have all been answered.
+        drop(handle);
+        let _ = ended.changed().await;
+
+        Some((handoff, true))
+    }
+
+    /// Waits out a handoff in progress for `id`; immediate otherwise.
+    pub async fn settled(&self, id: &str) {
+        let draining = lock_unpoisoned(&self.draining).get(id).cloned();
+        if let Some(mut draining) = draining {
+            // The guard never sends; its drop is the wake.
+            let _ = draining.changed().await;
+        }
+    }
+
+    /// The busiest resident objects since the last sample, busiest first,
+    /// at most `limit`; sampling resets every counter, so each report
+    /// covers one interval. Idle objects never appear.
+    pub async fn take_hottest(&self, limit: usize) -> Vec<String> {
+        let mut counted: Vec<(u64, String)> = self
+            .tasks
+            .lock()
+            .await
+            .iter()
+            .filter(|(_, (_, handle))| !handle.sender.is_closed())
+            .map(|(id, (_, handle))| {
+                (
+                    handle.calls.swap(0, std::sync::atomic::Ordering::Relaxed),
+                    id.clone(),
+                )
+            })
+            .filter(|(calls, _)| *calls > 0)
+            .collect();
+        counted.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+
+        counted.into_iter().take(limit).map(|(_, id)| id).collect()
+    }
 }
 
 #[cfg(test)]
@@ -801,6 +1156,127 @@ mod tests {
         assert_eq!(value, serde_json::json!(2));
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn a_handoff_answers_every_queued_call_before_it_lets_go() {
+        let host = Arc::new(ObjectHost::default());
+        let source = r#"
+            count = 0
+            function bump() sleep_ms(30) count = count + 1 return count end
+        "#;
+        let handle = host
+            .get_or_spawn("obj-1", "r1", || async {
+                Ok((runtime_with(source).await, TaskOptions::default()))
+            })
+            .await
+            .expect("spawns");
+
+        // Three calls queue up; the handoff starts while they wait.
+        let calls: Vec<_> = (0..3)
+            .map(|_| {
+                let handle = handle.clone();
+                tokio::spawn(async move { handle.call("bump", serde_json::Value::Null).await })
+            })
+            .collect();
+        drop(handle);
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+
+        let handoff = host.begin_handoff("obj-1").await.expect("was resident");
+        let mut answers = Vec::new();
+        for call in calls {
+            answers.push(call.await.expect("joins").expect("never dropped"));
+        }
+        answers.sort_by_key(|value| value.as_i64());
+        assert_eq!(
+            answers,
+            vec![
+                serde_json::json!(1),
+                serde_json::json!(2),
+                serde_json::json!(3)
+            ]
+        );
+        assert!(!host.is_resident("obj-1").await);
+
+        // Resolving waits for the guard, then builds afresh.
+        let respawn = {
+            let host = host.clone();
+            tokio::spawn(async move {
+                host.get_or_spawn("obj-1", "r1", || async {
+                    Ok((runtime_with(source).await, TaskOptions::default()))
+                })
+                .await
+                .map(|_| ())
+            })
+        };
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        assert!(!respawn.is_finished(), "must wait out the handoff");
+        drop(handoff);
+        respawn.await.expect("joins").expect("respawns");
+
+        // Not resident at all: nothing to hand off.
+        assert!(host.begin_handoff("obj-2").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn a_closed_host_serves_what_it_holds_and_builds_nothing() {
+        let host = ObjectHost::default();
+        let source = "function ping() return 'pong' end";
+        host.get_or_spawn("obj-1", "r1", || async {
+            Ok((runtime_with(source).await, TaskOptions::default()))
+        })
+        .await
+        .expect("spawns");
+
+        host.close();
+        assert_eq!(host.resident_ids().await, vec!["obj-1".to_owned()]);
+
+        let resident = host
+            .get_or_spawn("obj-1", "r1", || async {
+                Err(mlua::Error::RuntimeError("rebuilt".to_owned()))
+            })
+            .await
+            .expect("still served");
+        assert_eq!(
+            resident
+                .call("ping", serde_json::Value::Null)
+                .await
+                .expect("answers"),
+            serde_json::json!("pong")
+        );
+
+        let refused = host
+            .get_or_spawn("obj-2", "r1", || async {
+                Err(mlua::Error::RuntimeError("built".to_owned()))
+            })
+            .await
+            .err()
+            .expect("refused");
+        assert!(refused.to_string().contains(NODE_DRAINING));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn the_hottest_objects_are_sampled_per_interval() {
+        let host = ObjectHost::default();
+        let source = "function ping() return 1 end";
+        for (id, calls) in [("warm", 2), ("hot", 5), ("idle", 0)] {
+            let handle = host
+                .get_or_spawn(id, "r1", || async {
+                    Ok((runtime_with(source).await, TaskOptions::default()))
+                })
+                .await
+                .expect("spawns");
+            for _ in 0..calls {
+                handle
+                    .call("ping", serde_json::Value::Null)
+                    .await
+                    .expect("pings");
+            }
+        }
+
+        assert_eq!(host.take_hottest(8).await, vec!["hot", "warm"]);
+        // The sample reset every counter.
+        assert!(host.take_hottest(8).await.is_empty());
+    }
+
     /// The whole object story in one process: two separate "request" vms
     /// route method calls through one host, whose pinned vm holds state.
     #[tokio::test(flavor = "multi_thread")]
@@ -901,6 +1377,10 @@ mod tests {
                     queues: vec![],
                     workflows: vec![],
                     workflow_steps: vec![],
+                    queue_policies: vec![],
+                    topics: vec![],
+                    workflow_schedules: vec![],
+                    workflow_retentions: vec![],
                 }),
             }),
             ..Default::default()
@@ -990,6 +1470,59 @@ mod tests {
         assert_eq!(value, serde_json::json!(1));
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn a_call_expired_in_the_queue_never_runs() {
+        let runtime = runtime_with(
+            r#"
+            ran = {}
+            function slow(tag) sleep_ms(200) table.insert(ran, tag) end
+            function quick(tag) table.insert(ran, tag) end
+            function read() return ran end
+            "#,
+        )
+        .await;
+        let handle = spawn_object_task(runtime, TaskOptions::default());
+
+        // The slow call holds the mailbox well past the quick one's
+        // deadline; its caller hears the timeout, and the loop later
+        // drops it instead of running it for nobody.
+        let deadline = std::time::Instant::now() + std::time::Duration::from_millis(50);
+        let (slow, quick) = tokio::join!(handle.call("slow", serde_json::json!("slow")), async {
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            handle
+                .call_until("quick", serde_json::json!("quick"), Some(deadline))
+                .await
+        },);
+        slow.expect("the slow call has no deadline");
+        assert!(matches!(quick, Err(ObjectError::TimedOut)), "{quick:?}");
+        assert!(
+            quick
+                .expect_err("timed out")
+                .to_string()
+                .starts_with(crate::extensions::objects::CALL_TIMED_OUT)
+        );
+
+        let ran = handle
+            .call("read", serde_json::Value::Null)
+            .await
+            .expect("reads");
+        assert_eq!(ran, serde_json::json!(["slow"]));
+    }
+
+    #[test]
+    fn a_deadline_shrinks_the_call_budget() {
+        let soon = std::time::Instant::now() + std::time::Duration::from_millis(2500);
+        assert_eq!(budget_within(Some(10), Some(soon)), Some(3));
+        assert_eq!(budget_within(Some(2), Some(soon)), Some(2));
+        assert_eq!(budget_within(None, Some(soon)), Some(3));
+        assert_eq!(budget_within(Some(10), None), Some(10));
+        // A lapsed deadline still leaves the call its one second to start.
+        assert_eq!(
+            budget_within(Some(10), Some(std::time::Instant::now())),
+            Some(1)
+        );
+    }
+
     #[test]
     fn a_cycle_in_the_call_chain_is_refused() {
         let chain = extend_call_chain(&[], "a").expect("first hop");
@@ -1200,6 +1733,148 @@ mod tests {
         );
     }
 
+    /// A replica read runs only marked methods, and only reads: the holder
+    /// writes the file, a read-only copy answers without a mailbox.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn a_replica_serves_readonly_methods_and_nothing_else() {
+        const SOURCE: &str = r#"
+            local Room = object "Room" {
+                readonly = { "topic", "sneaky" },
+                set_topic = function(state, topic)
+                    state.sql:exec("CREATE TABLE IF NOT EXISTS room (topic TEXT)")
+                    state.sql:exec("DELETE FROM room")
+                    state.sql:exec("INSERT INTO room VALUES (?)", { topic })
+                end,
+                topic = function(state)
+                    return state.sql:query_one("SELECT topic FROM room").topic
+                end,
+                sneaky = function(state)
+                    state.sql:exec("DELETE FROM room")
+                end,
+            }
+        "#;
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("room.db");
+        let call = |method: &str, args: serde_json::Value| {
+            serde_json::json!({
+                "class": "Room",
+                "name": "lobby",
+                "method": method,
+                "args": args,
+            })
+        };
+
+        let holder = spawn_object_task(
+            runtime_with(SOURCE).await,
+            TaskOptions {
+                storage: Some(crate::storage::SqliteStorage::open(&path).expect("opens")),
+                ..Default::default()
+            },
+        );
+        holder
+            .call("__dispatch", call("set_topic", serde_json::json!(["rust"])))
+            .await
+            .expect("writes");
+
+        let read_only = || crate::storage::SqliteStorage::open_read_only(&path).expect("opens");
+        let replica = runtime_with(SOURCE).await;
+
+        let topic = dispatch_read_only(
+            &replica,
+            read_only(),
+            call("topic", serde_json::json!([])),
+            None,
+        )
+        .await
+        .expect("a marked read is served");
+        assert_eq!(topic, serde_json::json!("rust"));
+
+        let refused = dispatch_read_only(
+            &replica,
+            read_only(),
+            call("set_topic", serde_json::json!(["go"])),
+            None,
+        )
+        .await
+        .expect_err("an unmarked method must refuse");
+        assert!(refused.to_string().contains("readonly"), "{refused}");
+
+        // A mislabelled method still cannot write: the connection refuses.
+        dispatch_read_only(
+            &replica,
+            read_only(),
+            call("sneaky", serde_json::json!([])),
+            None,
+        )
+        .await
+        .expect_err("a replica must never write");
+        assert_eq!(
+            holder
+                .call("__dispatch", call("topic", serde_json::json!([])))
+                .await
+                .expect("reads"),
+            serde_json::json!("rust")
+        );
+    }
+
+    /// `Class:get(name, { consistency = "replica" })` reaches the router
+    /// with its bound, and the caller hears the staleness served.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn a_replica_handle_carries_its_bound_and_reports_staleness() {
+        use crate::extensions::objects::{ObjectRouter, ObjectTarget, ReadConsistency};
+
+        const SOURCE: &str = r#"
+            local Room = object "Room" {}
+
+            on "fetch" (function()
+                local strong = Room:get("lobby"):topic()
+                local topic, stale_ms = Room:get("lobby", {
+                    consistency = "replica",
+                    max_staleness = "5s",
+                }):topic()
+                return { strong = strong, topic = topic, stale_ms = stale_ms }
+            end)
+        "#;
+
+        let seen: Arc<std::sync::Mutex<Vec<Option<ReadConsistency>>>> = Arc::default();
+        let recorder = seen.clone();
+        let router: ObjectRouter = Arc::new(move |target: ObjectTarget| {
+            let recorder = recorder.clone();
+            Box::pin(async move {
+                recorder
+                    .lock()
+                    .expect("no poison")
+                    .push(target.consistency.clone());
+                Ok(match target.consistency {
+                    Some(_) => serde_json::json!({ "value": "replica", "staleness_ms": 1200 }),
+                    None => serde_json::json!("holder"),
+                })
+            })
+        });
+
+        let runtime = runtime_with(SOURCE).await;
+        runtime.set_app_data::<ObjectRouter>(router);
+        let listener = runtime
+            .listener(ActiasRuntime::FETCH_EVENT)
+            .expect("handler registered");
+        let answer: mlua::Value = listener.call_async(()).await.expect("handler runs");
+        let answer: serde_json::Value = runtime.from_value(answer).expect("converts");
+
+        assert_eq!(answer["strong"], "holder");
+        assert_eq!(answer["topic"], "replica");
+        assert_eq!(answer["stale_ms"], 1200);
+        assert_eq!(
+            *seen.lock().expect("no poison"),
+            vec![
+                None,
+                Some(ReadConsistency {
+                    max_staleness_ms: Some(5000)
+                })
+            ]
+        );
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn the_alarm_mirror_sees_every_cell_change() {
         let dir = tempfile::tempdir().expect("tempdir");
@@ -1420,20 +2095,201 @@ mod tests {
         assert_eq!(count, serde_json::json!({ "n": 1 }));
     }
 
-    /// The transaction guard: a method that errors after writing must
-    /// leave nothing behind.
+    /// An applied migration edited afterwards refuses the database with
+    /// a message naming the file, instead of running with a schema a
+    /// fresh database would not have.
     #[tokio::test(flavor = "multi_thread")]
-    async fn a_failed_method_persists_nothing_partial() {
-        const SOURCE: &str = r#"
-            local Ledger = object "Ledger" {
-                init = function(state)
-                    state.sql:exec("CREATE TABLE entries (n INTEGER)")
-                end,
-
-                good = function(state)
-                    state.sql:exec("INSERT INTO entries VALUES (1)")
-                    return true
-                end,
+    async fn an_edited_applied_migration_refuses_the_database() {
+        const MAIN: &str = r#"local db = database "main""#;
+        let call = serde_json::json!({
+            "class": "__database", "name": "main", "method": "query",
+            "args": ["SELECT COUNT(*) AS n FROM visits"],
+        });
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("main.db");
+
+        let original = spawn_object_task(
+            runtime_with_files(&[
+                ("main.lua", MAIN),
+                (
+                    "migrations/main/0001_init.sql",
+                    "CREATE TABLE visits (at INTEGER);",
+                ),
+            ])
+            .await,
+            TaskOptions {
+                storage: Some(crate::storage::SqliteStorage::open(&path).expect("opens")),
+                ..Default::default()
+            },
+        );
+        original
+            .call("__dispatch", call.clone())
+            .await
+            .expect("the migration applied");
+        drop(original);
+
+        let edited = spawn_object_task(
+            runtime_with_files(&[
+                ("main.lua", MAIN),
+                (
+                    "migrations/main/0001_init.sql",
+                    "CREATE TABLE visits (at INTEGER, path TEXT);",
+                ),
+            ])
+            .await,
+            TaskOptions {
+                storage: Some(crate::storage::SqliteStorage::open(&path).expect("reopens")),
+                ..Default::default()
+            },
+        );
+        let refused = edited.call("__dispatch", call).await;
+        assert!(
+            matches!(&refused, Err(ObjectError::Call(message))
+                if message.contains("0001_init.sql") && message.contains("edited")),
+            "{refused:?}"
+        );
+    }
+
+    /// A dump restores into a copy that answers like the original and
+    /// does not migrate twice, whether it travelled as SQL or as the file;
+    /// generated columns compute again and full-text tables index again.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn a_dumped_database_restores_without_migrating_again() {
+        use crate::platform::database::{Dump, load_dump};
+
+        const MAIN: &str = r#"local db = database "main""#;
+        let files = [
+            ("main.lua", MAIN),
+            (
+                "migrations/main/0001_init.sql",
+                "CREATE TABLE visits (id INTEGER PRIMARY KEY AUTOINCREMENT, path TEXT, at REAL, \
+                 depth INTEGER GENERATED ALWAYS AS (length(path)) VIRTUAL); \
+                 CREATE VIRTUAL TABLE notes USING fts5(body);",
+            ),
+        ];
+        let call = |method: &str, sql: &str| {
+            serde_json::json!({
+                "class": "__database", "name": "main", "method": method, "args": [sql],
+            })
+        };
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("main.db");
+        let original = spawn_object_task(
+            runtime_with_files(&files).await,
+            TaskOptions {
+                storage: Some(crate::storage::SqliteStorage::open(&path).expect("opens")),
+                ..Default::default()
+            },
+        );
+        original
+            .call(
+                "__dispatch",
+                call(
+                    "exec",
+                    "INSERT INTO visits (path, at) VALUES ('/it''s', 1.0), (NULL, 2.5)",
+                ),
+            )
+            .await
+            .expect("the rows land");
+        original
+            .call(
+                "__dispatch",
+                call("exec", "INSERT INTO notes (body) VALUES ('hello there')"),
+            )
+            .await
+            .expect("the note lands");
+        drop(original);
+
+        for raw in [false, true] {
+            let dumped = crate::platform::PlatformRead::Dump { raw }
+                .run(&path)
+                .expect("the dump reads");
+            let content = dumped["content"].as_str().expect("content").to_owned();
+            let dump = if raw {
+                use base64::Engine;
+                Dump::File(
+                    base64::engine::general_purpose::STANDARD
+                        .decode(content)
+                        .expect("base64"),
+                )
+            } else {
+                Dump::Sql(content)
+            };
+
+            let restored = dir.path().join(format!("restored-{raw}.db"));
+            load_dump(&restored, &dump, 1 << 20).expect("the dump loads");
+
+            let copy = spawn_object_task(
+                runtime_with_files(&files).await,
+                TaskOptions {
+                    storage: Some(crate::storage::SqliteStorage::open(&restored).expect("opens")),
+                    ..Default::default()
+                },
+            );
+            copy.call(
+                "__dispatch",
+                call("exec", "INSERT INTO visits (path) VALUES ('/')"),
+            )
+            .await
+            .expect("the copy takes writes without migrating again");
+            let rows = copy
+                .call(
+                    "__dispatch",
+                    call(
+                        "query",
+                        "SELECT id, path, at, depth FROM visits ORDER BY id",
+                    ),
+                )
+                .await
+                .expect("the copy reads");
+            assert_eq!(
+                rows,
+                serde_json::json!([
+                    { "id": 1, "path": "/it's", "at": 1.0, "depth": 5 },
+                    { "id": 2, "path": null, "at": 2.5, "depth": null },
+                    { "id": 3, "path": "/", "at": null, "depth": 1 },
+                ]),
+                "raw: {raw}"
+            );
+            let found = copy
+                .call(
+                    "__dispatch",
+                    call("query", "SELECT body FROM notes WHERE notes MATCH 'hello'"),
+                )
+                .await
+                .expect("the copy searches");
+            assert_eq!(
+                found,
+                serde_json::json!([{ "body": "hello there" }]),
+                "raw: {raw}"
+            );
+        }
+
+        // A dump that reaches past its own file never loads.
+        let refused = load_dump(
+            &dir.path().join("attached.db"),
+            &Dump::Sql("ATTACH 'elsewhere.db' AS other;".to_owned()),
+            1 << 20,
+        );
+        assert!(refused.is_err_and(|error| error.contains("did not load")));
+    }
+
+    /// The transaction guard: a method that errors after writing must
+    /// leave nothing behind.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn a_failed_method_persists_nothing_partial() {
+        const SOURCE: &str = r#"
+            local Ledger = object "Ledger" {
+                init = function(state)
+                    state.sql:exec("CREATE TABLE entries (n INTEGER)")
+                end,
+
+                good = function(state)
+                    state.sql:exec("INSERT INTO entries VALUES (1)")
+                    return true
+                end,
 
                 bad = function(state)
                     state.sql:exec("INSERT INTO entries VALUES (2)")
@@ -1699,49 +2555,1135 @@ mod tests {
 
         let dir = tempfile::tempdir().expect("tempdir");
         let handle = spawn_object_task(
-            runtime_with(SOURCE).await,
+            runtime_with(SOURCE).await,
+            TaskOptions {
+                storage: Some(
+                    crate::storage::SqliteStorage::open(&dir.path().join("cron.db"))
+                        .expect("opens"),
+                ),
+                ..Default::default()
+            },
+        );
+
+        handle
+            .call(
+                "__dispatch",
+                serde_json::json!({
+                    "class": "__cron", "name": EVENT, "method": "ensure", "args": [EVENT],
+                }),
+            )
+            .await
+            .expect("ensure arms");
+
+        // An every-second schedule (clamped to 1s minimum) must have fired
+        // at least twice in 2.6s, proving the alarm re-arms itself.
+        tokio::time::sleep(std::time::Duration::from_millis(2600)).await;
+        let marks = handle
+            .call("get_marks", serde_json::Value::Null)
+            .await
+            .expect("marks read");
+        let marks = marks.as_i64().unwrap_or(0);
+        assert!(marks >= 2, "the schedule must self-perpetuate: {marks}");
+    }
+
+    /// A declared zone and catch-up policy reach the instance, which
+    /// reports them with its last and next fire.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn a_cron_object_reports_its_policy_and_fires() {
+        const EVENT: &str = "cron:* * * * * *";
+        const SOURCE: &str = r#"
+            on "cron:* * * * * *" { tz = "Asia/Kolkata", catch_up = "none" } (function(event)
+            end)
+        "#;
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let handle = spawn_object_task(
+            runtime_with(SOURCE).await,
+            TaskOptions {
+                storage: Some(
+                    crate::storage::SqliteStorage::open(&dir.path().join("cron.db"))
+                        .expect("opens"),
+                ),
+                ..Default::default()
+            },
+        );
+        let call = |method: &str| {
+            serde_json::json!({
+                "class": "__cron", "name": EVENT, "method": method, "args": [EVENT],
+            })
+        };
+
+        handle
+            .call("__dispatch", call("ensure"))
+            .await
+            .expect("ensure arms");
+        let armed = handle
+            .call("__dispatch", call("state"))
+            .await
+            .expect("state reads");
+        assert_eq!(armed["tz"], "Asia/Kolkata", "{armed}");
+        assert_eq!(armed["catch_up"], "none");
+        assert!(armed["next_ms"].is_i64(), "{armed}");
+        assert!(armed["last_ms"].is_null(), "{armed}");
+
+        tokio::time::sleep(std::time::Duration::from_millis(1600)).await;
+        let fired = handle
+            .call("__dispatch", call("state"))
+            .await
+            .expect("state reads");
+        assert!(fired["last_ms"].is_i64(), "{fired}");
+        assert!(
+            fired["next_ms"].as_i64() > fired["last_ms"].as_i64(),
+            "{fired}"
+        );
+        assert_eq!(fired["missed"], 0, "an on-time fire skips nothing");
+    }
+
+    /// A fire asked for by hand runs the listener now, leaves a history
+    /// row with its failure, and the file answers the dashboard read
+    /// without the vm.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn a_manual_cron_fire_is_recorded_in_the_history() {
+        const EVENT: &str = "cron:0 0 0 1 1 *";
+        const SOURCE: &str = r#"
+            on "cron:0 0 0 1 1 *" { tz = "Europe/Paris" } (function(event)
+                error("boom")
+            end)
+        "#;
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("cron.db");
+        let handle = spawn_object_task(
+            runtime_with(SOURCE).await,
+            TaskOptions {
+                storage: Some(crate::storage::SqliteStorage::open(&path).expect("opens")),
+                ..Default::default()
+            },
+        );
+        let call = |method: &str| {
+            serde_json::json!({
+                "class": "__cron", "name": EVENT, "method": method, "args": [EVENT],
+            })
+        };
+
+        handle
+            .call("__dispatch", call("ensure"))
+            .await
+            .expect("ensure arms");
+        let fire = handle
+            .call("__dispatch", call("fire"))
+            .await
+            .expect("a failing listener still records its fire");
+        assert_eq!(fire["manual"], true, "{fire}");
+        assert!(
+            fire["error"].as_str().is_some_and(|e| e.contains("boom")),
+            "{fire}"
+        );
+
+        let state = crate::platform::PlatformRead::stats_for_class("__cron")
+            .expect("cron has a read")
+            .run(&path)
+            .expect("reads");
+        assert_eq!(state["tz"], "Europe/Paris", "{state}");
+        assert_eq!(state["catch_up"], "latest");
+        assert!(state["next_ms"].is_i64(), "{state}");
+        assert!(
+            state["last_ms"].is_null(),
+            "a manual fire is not an occurrence: {state}"
+        );
+        assert_eq!(state["fires"].as_array().map(Vec::len), Some(1), "{state}");
+        assert_eq!(state["fires"][0]["scheduled_ms"], fire["scheduled_ms"]);
+    }
+
+    /// Three workflows scheduled on one expression, one per overlap
+    /// policy, against a router whose runs stay running until told
+    /// otherwise: `allow` starts every fire, `skip` only the first,
+    /// `buffer-one` the first and then the held fire once it settles.
+    /// Each step waits on the starts it expects, never on the clock.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn scheduled_workflows_honour_their_overlap_policies() {
+        use crate::extensions::objects::{ObjectRouter, ObjectTarget};
+        use crate::proto::script_service::{Capabilities, ScriptConfig, WorkflowSchedule};
+
+        const EVENT: &str = "cron:* * * * * *";
+        let schedule = |workflow: &str, overlap: &str| WorkflowSchedule {
+            workflow: workflow.to_owned(),
+            schedule: "* * * * * *".to_owned(),
+            overlap: overlap.to_owned(),
+        };
+        let revision = Revision {
+            bundle: Some(Bundle {
+                entry_point: "main.lua".to_owned(),
+                files: vec![File {
+                    file_path: "main.lua".to_owned(),
+                    content: b"-- schedules only".to_vec(),
+                    ..Default::default()
+                }],
+            }),
+            script_config: Some(ScriptConfig {
+                id: String::new(),
+                entry_point: "main.lua".to_owned(),
+                includes: vec![],
+                ignore: vec![],
+                capabilities: Some(Capabilities {
+                    workflow_schedules: vec![
+                        schedule("every", "allow"),
+                        schedule("single", "skip"),
+                        schedule("held", "buffer-one"),
+                    ],
+                    ..Default::default()
+                }),
+            }),
+            ..Default::default()
+        };
+        let prepared =
+            Arc::new(PreparedRevision::prepare(Script::default(), revision).expect("prepares"));
+        let channel = tonic::transport::Channel::from_static("http://127.0.0.1:1").connect_lazy();
+        let egress = crate::egress::EgressClient::new(crate::egress::EgressPolicy::new([], false))
+            .expect("egress builds");
+        let runtime = ActiasRuntime::new(
+            prepared,
+            KvServiceClient::new(channel),
+            egress,
+            None,
+            None,
+            None,
+        )
+        .await
+        .expect("runtime builds");
+
+        let started: Arc<std::sync::Mutex<Vec<String>>> = Arc::default();
+        let settled = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let (started_for, settled_for) = (started.clone(), settled.clone());
+        let router: ObjectRouter = Arc::new(move |target: ObjectTarget| {
+            let (started, settled) = (started_for.clone(), settled_for.clone());
+            Box::pin(async move {
+                Ok::<_, String>(match target.method.as_str() {
+                    "start" => {
+                        started.lock().expect("log").push(target.name);
+                        serde_json::Value::Null
+                    }
+                    _ if settled.load(std::sync::atomic::Ordering::SeqCst) => {
+                        serde_json::json!({ "state": "completed" })
+                    }
+                    _ => serde_json::json!({ "state": "running" }),
+                })
+            })
+        });
+        runtime.set_app_data::<ObjectRouter>(router);
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let handle = spawn_object_task(
+            runtime,
+            TaskOptions {
+                storage: Some(
+                    crate::storage::SqliteStorage::open(&dir.path().join("cron.db"))
+                        .expect("opens"),
+                ),
+                ..Default::default()
+            },
+        );
+        handle
+            .call(
+                "__dispatch",
+                serde_json::json!({
+                    "class": "__cron", "name": EVENT, "method": "ensure", "args": [EVENT],
+                }),
+            )
+            .await
+            .expect("ensure arms");
+
+        let runs = |prefix: &str| -> Vec<String> {
+            started
+                .lock()
+                .expect("log")
+                .iter()
+                .filter(|name| name.starts_with(prefix))
+                .cloned()
+                .collect()
+        };
+        // Polls until `ready` holds, within a deadline far past the two
+        // fires a step needs.
+        async fn wait_for(ready: impl Fn() -> bool) -> bool {
+            let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+            while !ready() {
+                if std::time::Instant::now() >= deadline {
+                    return false;
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            }
+            true
+        }
+
+        assert!(
+            wait_for(|| runs("every/").len() >= 2).await,
+            "allow starts each fire: {:?}",
+            runs("every/")
+        );
+        let every = runs("every/");
+        assert!(
+            every
+                .iter()
+                .all(|name| name.starts_with("every/scheduled-") && name.ends_with('Z')),
+            "{every:?}"
+        );
+        assert_eq!(runs("single/").len(), 1, "skip waits for the first run");
+        let held = runs("held/");
+        assert_eq!(held.len(), 1, "buffer-one holds the later fires back");
+
+        settled.store(true, std::sync::atomic::Ordering::SeqCst);
+        assert!(
+            wait_for(|| runs("held/").len() >= 2 && runs("single/").len() >= 2).await,
+            "the held fire starts and skip resumes once settled: {:?}",
+            started.lock().expect("log")
+        );
+        let drained = runs("held/");
+        let occurrence = drained[1].trim_start_matches("held/");
+        assert!(
+            runs("every/").iter().any(|name| name.ends_with(occurrence)),
+            "the held fire keeps its own occurrence: {drained:?}"
+        );
+    }
+
+    /// The queue substrate end to end: send enqueues, the alarm loop
+    /// delivers to the `on "queue:<name>"` listener, and the payload
+    /// survives the json round trip through sqlite.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn a_queue_delivers_sent_messages_to_the_listener() {
+        const SOURCE: &str = r#"
+            got = nil
+            on "queue:jobs" (function(message)
+                sleep_ms(5)
+                got = message
+            end)
+            function get_got() return got end
+        "#;
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let handle = spawn_object_task(
+            runtime_with(SOURCE).await,
+            TaskOptions {
+                storage: Some(
+                    crate::storage::SqliteStorage::open(&dir.path().join("q.db")).expect("opens"),
+                ),
+                ..Default::default()
+            },
+        );
+
+        handle
+            .call(
+                "__dispatch",
+                serde_json::json!({
+                    "class": "__queue", "name": "jobs", "method": "send",
+                    "args": [{"kind": "render", "frame": 17}],
+                }),
+            )
+            .await
+            .expect("send enqueues");
+
+        tokio::time::sleep(std::time::Duration::from_millis(400)).await;
+        let got = handle
+            .call("get_got", serde_json::Value::Null)
+            .await
+            .expect("read back");
+        assert_eq!(got["kind"], "render", "payload round-trips: {got}");
+        assert_eq!(got["frame"], 17);
+    }
+
+    /// A topic publishes once and every subscriber's `on "topic:<name>"`
+    /// receives it through its own subscription; the router here stands in
+    /// for the worker's, delivering each subscription send to a task of
+    /// its own.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn a_topic_fans_out_to_every_subscription() {
+        const SUBSCRIBER: &str = r#"
+            got = {}
+            on "topic:orders" (function(order)
+                table.insert(got, order)
+            end)
+            function get_got() return got end
+        "#;
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let mut subscriptions = std::collections::HashMap::new();
+        for subscriber in ["billing", "email"] {
+            let handle = spawn_object_task(
+                runtime_with(SUBSCRIBER).await,
+                TaskOptions {
+                    storage: Some(
+                        crate::storage::SqliteStorage::open(
+                            &dir.path().join(format!("{subscriber}.db")),
+                        )
+                        .expect("opens"),
+                    ),
+                    ..Default::default()
+                },
+            );
+            subscriptions.insert(format!("orders/{subscriber}"), handle);
+        }
+        let subscriptions = Arc::new(subscriptions);
+
+        let topic = runtime_with("").await;
+        let routed = subscriptions.clone();
+        topic.set_app_data::<crate::extensions::objects::ObjectRouter>(Arc::new(
+            move |target: crate::extensions::objects::ObjectTarget| {
+                let routed = routed.clone();
+                Box::pin(async move {
+                    let handle = routed.get(&target.name).ok_or("no such subscription")?;
+                    handle
+                        .call(
+                            "__dispatch",
+                            serde_json::json!({
+                                "class": target.class, "name": target.name,
+                                "method": target.method, "args": target.arguments,
+                            }),
+                        )
+                        .await
+                        .map_err(|e| e.to_string())
+                })
+            },
+        ));
+        topic.set_app_data::<crate::platform::topic::SubscriberLookup>(Arc::new(|_topic| {
+            Box::pin(async { Ok(vec!["billing".to_owned(), "email".to_owned()]) })
+        }));
+        let handle = spawn_object_task(
+            topic,
+            TaskOptions {
+                storage: Some(
+                    crate::storage::SqliteStorage::open(&dir.path().join("t.db")).expect("opens"),
+                ),
+                ..Default::default()
+            },
+        );
+
+        handle
+            .call(
+                "__dispatch",
+                serde_json::json!({
+                    "class": "__topic", "name": "orders", "method": "publish",
+                    "args": [{"order": 7}],
+                }),
+            )
+            .await
+            .expect("publish appends");
+
+        tokio::time::sleep(std::time::Duration::from_millis(600)).await;
+        for subscription in subscriptions.values() {
+            let got = subscription
+                .call("get_got", serde_json::Value::Null)
+                .await
+                .expect("read back");
+            assert_eq!(got[0]["order"], 7, "each subscriber got it once: {got}");
+            assert_eq!(got.as_array().map(Vec::len), Some(1));
+        }
+
+        let stats = handle
+            .call(
+                "__dispatch",
+                serde_json::json!({
+                    "class": "__topic", "name": "orders", "method": "stats", "args": [],
+                }),
+            )
+            .await
+            .expect("stats");
+        assert_eq!(stats["pending"], 0, "the entry left the log: {stats}");
+    }
+
+    /// A subscriber that keeps refusing is retried alone, never the ones
+    /// that took the entry, and dead-letters once the attempts run out.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn a_topic_retries_only_missing_subscribers_then_dead_letters() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let sends = Arc::new(std::sync::Mutex::new(Vec::<String>::new()));
+
+        let topic = runtime_with("").await;
+        let counted = sends.clone();
+        topic.set_app_data::<crate::extensions::objects::ObjectRouter>(Arc::new(
+            move |target: crate::extensions::objects::ObjectTarget| {
+                counted.lock().expect("sends").push(target.name.clone());
+                Box::pin(async move {
+                    if target.name == "orders/broken" {
+                        Err("subscription unavailable".to_owned())
+                    } else {
+                        Ok(serde_json::json!(true))
+                    }
+                })
+            },
+        ));
+        topic.set_app_data::<crate::platform::topic::SubscriberLookup>(Arc::new(|_topic| {
+            Box::pin(async { Ok(vec!["billing".to_owned(), "broken".to_owned()]) })
+        }));
+        let handle = spawn_object_task(
+            topic,
+            TaskOptions {
+                storage: Some(
+                    crate::storage::SqliteStorage::open(&dir.path().join("t.db")).expect("opens"),
+                ),
+                // Three attempts at a compressed backoff: dead fast.
+                queue: crate::platform::queue::QueuePolicy {
+                    max_attempts: 3,
+                    backoff_base_ms: 5,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+
+        handle
+            .call(
+                "__dispatch",
+                serde_json::json!({
+                    "class": "__topic", "name": "orders", "method": "publish",
+                    "args": [{"order": 7}],
+                }),
+            )
+            .await
+            .expect("publish appends");
+        tokio::time::sleep(std::time::Duration::from_millis(600)).await;
+
+        let sends = sends.lock().expect("sends").clone();
+        let count = |name: &str| sends.iter().filter(|sent| *sent == name).count();
+        assert_eq!(
+            count("orders/billing"),
+            1,
+            "the taker is sent once: {sends:?}"
+        );
+        assert_eq!(count("orders/broken"), 3, "the refuser, once per attempt");
+
+        let stats = handle
+            .call(
+                "__dispatch",
+                serde_json::json!({
+                    "class": "__topic", "name": "orders", "method": "stats", "args": [],
+                }),
+            )
+            .await
+            .expect("stats");
+        assert_eq!(stats["pending"], 0, "the entry left the log: {stats}");
+        assert_eq!(
+            stats["dead_letters"], 1,
+            "one letter, the refuser's: {stats}"
+        );
+    }
+
+    /// An entry nobody could be tried for, its subscribers unlisted,
+    /// dead-letters once the attempts run out, as one letter for whoever
+    /// is missing it; resent, it reaches every subscriber and leaves.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn a_topic_dead_letters_an_unroutable_entry_and_resends_it() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let sends = Arc::new(std::sync::Mutex::new(Vec::<String>::new()));
+        let listed = Arc::new(std::sync::atomic::AtomicBool::new(false));
+
+        let topic = runtime_with("").await;
+        let counted = sends.clone();
+        topic.set_app_data::<crate::extensions::objects::ObjectRouter>(Arc::new(
+            move |target: crate::extensions::objects::ObjectTarget| {
+                counted.lock().expect("sends").push(target.name.clone());
+                Box::pin(async { Ok(serde_json::json!(true)) })
+            },
+        ));
+        let listing = listed.clone();
+        topic.set_app_data::<crate::platform::topic::SubscriberLookup>(Arc::new(move |_topic| {
+            let listed = listing.load(std::sync::atomic::Ordering::SeqCst);
+            Box::pin(async move {
+                if listed {
+                    Ok(vec!["billing".to_owned(), "email".to_owned()])
+                } else {
+                    Err("contracts unavailable".to_owned())
+                }
+            })
+        }));
+        let handle = spawn_object_task(
+            topic,
+            TaskOptions {
+                storage: Some(
+                    crate::storage::SqliteStorage::open(&dir.path().join("t.db")).expect("opens"),
+                ),
+                queue: crate::platform::queue::QueuePolicy {
+                    max_attempts: 2,
+                    backoff_base_ms: 5,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+        let call = |method: &str, args: serde_json::Value| {
+            handle.call(
+                "__dispatch",
+                serde_json::json!({
+                    "class": "__topic", "name": "orders", "method": method, "args": args,
+                }),
+            )
+        };
+
+        call("publish", serde_json::json!([{"order": 7}]))
+            .await
+            .expect("publish appends");
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        let letters = loop {
+            let letters = call("messages", serde_json::json!([]))
+                .await
+                .expect("messages");
+            if letters
+                .as_array()
+                .is_some_and(|letters| !letters.is_empty())
+            {
+                break letters;
+            }
+            assert!(
+                std::time::Instant::now() < deadline,
+                "the entry dead-letters"
+            );
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        };
+        assert_eq!(
+            letters[0]["subscriber"], "",
+            "a letter for everyone: {letters}"
+        );
+        assert_eq!(letters[0]["attempts"], 2);
+        assert!(
+            letters[0]["error"]
+                .as_str()
+                .is_some_and(|error| error.contains("contracts unavailable")),
+            "{letters}"
+        );
+        let stats = call("stats", serde_json::json!([])).await.expect("stats");
+        assert_eq!(stats["pending"], 0, "the entry left the log: {stats}");
+        assert!(sends.lock().expect("sends").is_empty());
+
+        listed.store(true, std::sync::atomic::Ordering::SeqCst);
+        let landed = call("retry_message", serde_json::json!([letters[0]["id"]]))
+            .await
+            .expect("retry_message");
+        assert_eq!(landed, 1, "the letter landed");
+        let mut sent = sends.lock().expect("sends").clone();
+        sent.sort();
+        assert_eq!(sent, ["orders/billing", "orders/email"]);
+
+        let letters = call("messages", serde_json::json!([]))
+            .await
+            .expect("messages");
+        assert_eq!(letters, serde_json::json!([]), "the letter left");
+        let dropped = call("drop_message", serde_json::json!([1]))
+            .await
+            .expect("drop_message");
+        assert_eq!(dropped, false, "nothing left to drop");
+    }
+
+    /// Committed writes reach `on "database:<name>"` as row changes,
+    /// through the database's own subscription per listener; a call that
+    /// rolls back reports nothing.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn database_changes_reach_listeners_after_commit() {
+        const LISTENER: &str = r#"
+            got = {}
+            on "database:orders" (function(changes)
+                for _, change in ipairs(changes) do table.insert(got, change) end
+            end)
+            function get_got() return got end
+        "#;
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let subscription = Arc::new(spawn_object_task(
+            runtime_with(LISTENER).await,
+            TaskOptions {
+                storage: Some(
+                    crate::storage::SqliteStorage::open(&dir.path().join("s.db")).expect("opens"),
+                ),
+                ..Default::default()
+            },
+        ));
+
+        let database = runtime_with_files(&[
+            ("main.lua", r#"local orders = database "orders""#),
+            (
+                "migrations/orders/0001_init.sql",
+                "CREATE TABLE orders (sku TEXT PRIMARY KEY, qty INTEGER);",
+            ),
+        ])
+        .await;
+        let routed = subscription.clone();
+        database.set_app_data::<crate::extensions::objects::ObjectRouter>(Arc::new(
+            move |target: crate::extensions::objects::ObjectTarget| {
+                let routed = routed.clone();
+                Box::pin(async move {
+                    if target.name != "database/orders/billing" {
+                        return Err("no such subscription".to_owned());
+                    }
+                    routed
+                        .call(
+                            "__dispatch",
+                            serde_json::json!({
+                                "class": target.class, "name": target.name,
+                                "method": target.method, "args": target.arguments,
+                            }),
+                        )
+                        .await
+                        .map_err(|e| e.to_string())
+                })
+            },
+        ));
+        database.set_app_data::<crate::platform::topic::SubscriberLookup>(Arc::new(|event| {
+            Box::pin(async move {
+                assert_eq!(event, "database:orders");
+                Ok(vec!["billing".to_owned()])
+            })
+        }));
+        let handle = spawn_object_task(
+            database,
+            TaskOptions {
+                storage: Some(
+                    crate::storage::SqliteStorage::open(&dir.path().join("d.db")).expect("opens"),
+                ),
+                ..Default::default()
+            },
+        );
+        let exec = |sql: &str| {
+            serde_json::json!({
+                "class": "__database", "name": "orders", "method": "exec", "args": [sql],
+            })
+        };
+
+        handle
+            .call(
+                "__dispatch",
+                exec("INSERT INTO orders VALUES ('a', 1), ('b', 2)"),
+            )
+            .await
+            .expect("inserts");
+        handle
+            .call(
+                "__dispatch",
+                exec("INSERT INTO orders VALUES ('c', 3), ('a', 9)"),
+            )
+            .await
+            .expect_err("the duplicate rolls the whole call back");
+        handle
+            .call(
+                "__dispatch",
+                exec("UPDATE orders SET qty = 5 WHERE sku = 'b'"),
+            )
+            .await
+            .expect("updates");
+        handle
+            .call("__dispatch", exec("DELETE FROM orders WHERE sku = 'a'"))
+            .await
+            .expect("deletes");
+
+        tokio::time::sleep(std::time::Duration::from_millis(600)).await;
+        let got = subscription
+            .call("get_got", serde_json::Value::Null)
+            .await
+            .expect("read back");
+        let ops: Vec<&str> = got
+            .as_array()
+            .expect("a list of changes")
+            .iter()
+            .filter_map(|change| change["op"].as_str())
+            .collect();
+        assert_eq!(ops, vec!["insert", "insert", "update", "delete"], "{got}");
+        assert_eq!(got[0]["table"], "orders");
+        assert_eq!(got[0]["key"]["sku"], "a");
+        assert_eq!(got[2]["key"]["sku"], "b");
+        assert_eq!(got[2]["values"]["qty"], 5, "updates carry the new row");
+        assert_eq!(
+            got[3]["key"],
+            serde_json::json!({ "sku": "a" }),
+            "a delete carries the text key the row left with"
+        );
+        assert!(got[3]["values"].is_null());
+    }
+
+    /// A database nobody listens to logs no changes; one that is listened
+    /// to logs a bulk write whole, an entry at a time.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn database_changes_are_captured_only_for_listeners_in_entries() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("d.db");
+        let database = runtime_with_files(&[
+            ("main.lua", r#"local orders = database "orders""#),
+            (
+                "migrations/orders/0001_init.sql",
+                "CREATE TABLE orders (sku TEXT PRIMARY KEY, qty INTEGER);",
+            ),
+        ])
+        .await;
+        database.set_app_data::<crate::platform::topic::SubscriberLookup>(Arc::new(|_event| {
+            Box::pin(async { Ok(vec!["billing".to_owned()]) })
+        }));
+        let listening = Arc::new(AtomicBool::new(false));
+        let check = listening.clone();
+        database.set_app_data(crate::platform::database::ListenerCheck(Arc::new(
+            move |_event| -> crate::platform::topic::SubscriberFuture {
+                let listening = check.load(Ordering::SeqCst);
+                Box::pin(async move {
+                    Ok(if listening {
+                        vec!["billing".to_owned()]
+                    } else {
+                        Vec::new()
+                    })
+                })
+            },
+        )));
+        let handle = spawn_object_task(
+            database,
+            TaskOptions {
+                storage: Some(crate::storage::SqliteStorage::open(&path).expect("opens")),
+                ..Default::default()
+            },
+        );
+        let exec = |sql: &str| {
+            serde_json::json!({
+                "class": "__database", "name": "orders", "method": "exec", "args": [sql],
+            })
+        };
+        let count = |sql: &str| -> i64 {
+            rusqlite::Connection::open(&path)
+                .expect("opens")
+                .query_row(sql, [], |row| row.get(0))
+                .expect("counts")
+        };
+
+        handle
+            .call("__dispatch", exec("INSERT INTO orders VALUES ('a', 1)"))
+            .await
+            .expect("inserts");
+        assert_eq!(
+            count("SELECT COUNT(*) FROM sqlite_master WHERE name = '__actias_db_changes'"),
+            0,
+            "nobody listens, so nothing is logged"
+        );
+
+        listening.store(true, Ordering::SeqCst);
+        handle
+            .call(
+                "__dispatch",
+                exec(
+                    "WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 250) \
+                     INSERT INTO orders SELECT 'k' || i, i FROM n",
+                ),
+            )
+            .await
+            .expect("a bulk write lands under a listener");
+        assert_eq!(count("SELECT COUNT(*) FROM orders"), 251);
+        assert_eq!(
+            count("SELECT COUNT(*) FROM __actias_db_changes"),
+            3,
+            "two full entries and the rest"
+        );
+    }
+
+    /// A refused delivery retries with backoff and succeeds on the second
+    /// attempt; nothing is lost and nothing dead-letters.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn a_failed_delivery_retries_until_the_handler_accepts() {
+        const SOURCE: &str = r#"
+            tries = 0
+            on "queue:jobs" (function(message)
+                tries = tries + 1
+                if tries < 2 then error("not yet") end
+            end)
+            function get_tries() return tries end
+        "#;
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let handle = spawn_object_task(
+            runtime_with(SOURCE).await,
+            TaskOptions {
+                storage: Some(
+                    crate::storage::SqliteStorage::open(&dir.path().join("q.db")).expect("opens"),
+                ),
+                // Compressed backoff: production waits seconds, the test
+                // needs the retry inside its own window.
+                queue: crate::platform::queue::QueuePolicy {
+                    backoff_base_ms: 20,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+
+        handle
+            .call(
+                "__dispatch",
+                serde_json::json!({
+                    "class": "__queue", "name": "jobs", "method": "send", "args": ["once"],
+                }),
+            )
+            .await
+            .expect("send enqueues");
+
+        tokio::time::sleep(std::time::Duration::from_millis(600)).await;
+        let tries = handle
+            .call("get_tries", serde_json::Value::Null)
+            .await
+            .expect("tries read");
+        assert_eq!(tries.as_i64(), Some(2), "one refusal, one delivery");
+
+        let stats = handle
+            .call(
+                "__dispatch",
+                serde_json::json!({
+                    "class": "__queue", "name": "jobs", "method": "stats", "args": [],
+                }),
+            )
+            .await
+            .expect("stats");
+        assert_eq!(stats["depth"], 0, "the retried message was consumed");
+        assert_eq!(stats["dead_letters"], 0);
+    }
+
+    /// A poison message exhausts its attempts and lands in the dead-letter
+    /// table instead of blocking the queue forever.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn a_poison_message_dead_letters_after_max_attempts() {
+        const SOURCE: &str = r#"
+            on "queue:jobs" (function(message)
+                error("always refuses")
+            end)
+        "#;
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let handle = spawn_object_task(
+            runtime_with(SOURCE).await,
+            TaskOptions {
+                storage: Some(
+                    crate::storage::SqliteStorage::open(&dir.path().join("q.db")).expect("opens"),
+                ),
+                // Compressed backoff so all five attempts fit the test.
+                queue: crate::platform::queue::QueuePolicy {
+                    backoff_base_ms: 5,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+
+        handle
+            .call(
+                "__dispatch",
+                serde_json::json!({
+                    "class": "__queue", "name": "jobs", "method": "send", "args": ["poison"],
+                }),
+            )
+            .await
+            .expect("send enqueues");
+
+        tokio::time::sleep(std::time::Duration::from_millis(900)).await;
+        let stats = handle
+            .call(
+                "__dispatch",
+                serde_json::json!({
+                    "class": "__queue", "name": "jobs", "method": "stats", "args": [],
+                }),
+            )
+            .await
+            .expect("stats");
+        assert_eq!(stats["depth"], 0, "the poison message left the queue");
+        assert_eq!(
+            stats["dead_letters"], 1,
+            "and landed in the dead-letter table"
+        );
+    }
+
+    /// A scheduled send waits as its own state while later immediate sends
+    /// deliver past it, then delivers when it comes due.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn a_scheduled_send_waits_without_holding_back_the_queue() {
+        const SOURCE: &str = r#"
+            got = {}
+            on "queue:jobs" (function(message)
+                table.insert(got, message)
+            end)
+            function get_got() return got end
+        "#;
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let handle = spawn_object_task(
+            runtime_with(SOURCE).await,
+            TaskOptions {
+                storage: Some(
+                    crate::storage::SqliteStorage::open(&dir.path().join("q.db")).expect("opens"),
+                ),
+                ..Default::default()
+            },
+        );
+        let dispatch = |method: &str, args: serde_json::Value| {
+            serde_json::json!({
+                "class": "__queue", "name": "jobs", "method": method, "args": args,
+            })
+        };
+
+        handle
+            .call(
+                "__dispatch",
+                dispatch("send", serde_json::json!(["later", { "delay": "500ms" }])),
+            )
+            .await
+            .expect("scheduled send enqueues");
+        handle
+            .call("__dispatch", dispatch("send", serde_json::json!(["now"])))
+            .await
+            .expect("send enqueues");
+        let refused = handle
+            .call(
+                "__dispatch",
+                dispatch("send", serde_json::json!(["x", { "delay": "1s", "at": 1 }])),
+            )
+            .await;
+        assert!(refused.is_err(), "delay and at together are refused");
+
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        let got = handle
+            .call("get_got", serde_json::Value::Null)
+            .await
+            .expect("read back");
+        assert_eq!(got, serde_json::json!(["now"]));
+        let stats = handle
+            .call("__dispatch", dispatch("stats", serde_json::json!([])))
+            .await
+            .expect("stats");
+        assert_eq!(stats["scheduled"], 1, "{stats}");
+        let messages = handle
+            .call("__dispatch", dispatch("messages", serde_json::json!([])))
+            .await
+            .expect("messages");
+        assert_eq!(messages[0]["state"], "scheduled");
+
+        tokio::time::sleep(std::time::Duration::from_millis(700)).await;
+        let got = handle
+            .call("get_got", serde_json::Value::Null)
+            .await
+            .expect("read back");
+        assert_eq!(got, serde_json::json!(["now", "later"]));
+    }
+
+    /// A pull consumer leases messages under a visibility timeout and
+    /// settles them by receipt; a nack and a lapsed lease both cost an
+    /// attempt, a lapsed receipt settles nothing, and a queue no script
+    /// listens to leaves its messages to pullers.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn a_pull_consumer_leases_acks_and_nacks_by_receipt() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let handle = spawn_object_task(
+            runtime_with("").await,
             TaskOptions {
                 storage: Some(
-                    crate::storage::SqliteStorage::open(&dir.path().join("cron.db"))
-                        .expect("opens"),
+                    crate::storage::SqliteStorage::open(&dir.path().join("q.db")).expect("opens"),
                 ),
+                queue: crate::platform::queue::QueuePolicy {
+                    backoff_base_ms: 5,
+                    ..Default::default()
+                },
                 ..Default::default()
             },
         );
+        let dispatch = |method: &str, args: serde_json::Value| {
+            serde_json::json!({
+                "class": "__queue", "name": "jobs", "method": method, "args": args,
+            })
+        };
 
-        handle
+        for body in ["a", "b"] {
+            handle
+                .call("__dispatch", dispatch("send", serde_json::json!([body])))
+                .await
+                .expect("send enqueues");
+        }
+        // No listener: the alarm must leave both messages for the puller.
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+        let leased = handle
             .call(
                 "__dispatch",
-                serde_json::json!({
-                    "class": "__cron", "name": EVENT, "method": "ensure", "args": [EVENT],
-                }),
+                dispatch("lease", serde_json::json!([2, "10s"])),
             )
             .await
-            .expect("ensure arms");
+            .expect("lease");
+        assert_eq!(leased[0]["body"], "a", "{leased}");
+        assert_eq!(leased[1]["attempt"], 1);
+        let stats = handle
+            .call("__dispatch", dispatch("stats", serde_json::json!([])))
+            .await
+            .expect("stats");
+        assert_eq!(stats["leased"], 2, "{stats}");
+        assert_eq!(stats["scheduled"], 0, "a lease is not a scheduled send");
 
-        // An every-second schedule (clamped to 1s minimum) must have fired
-        // at least twice in 2.6s, proving the alarm re-arms itself.
-        tokio::time::sleep(std::time::Duration::from_millis(2600)).await;
-        let marks = handle
-            .call("get_marks", serde_json::Value::Null)
+        let (a, b) = (leased[0]["receipt"].clone(), leased[1]["receipt"].clone());
+        let acked = handle
+            .call("__dispatch", dispatch("ack", serde_json::json!([[a]])))
             .await
-            .expect("marks read");
-        let marks = marks.as_i64().unwrap_or(0);
-        assert!(marks >= 2, "the schedule must self-perpetuate: {marks}");
+            .expect("ack");
+        assert_eq!(acked, 1);
+        let nacked = handle
+            .call(
+                "__dispatch",
+                dispatch("nack", serde_json::json!([b, "bad row"])),
+            )
+            .await
+            .expect("nack");
+        assert_eq!(nacked, 1);
+
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        let leased = handle
+            .call(
+                "__dispatch",
+                dispatch("lease", serde_json::json!([5, "50ms"])),
+            )
+            .await
+            .expect("lease");
+        assert_eq!(leased.as_array().map(Vec::len), Some(1), "{leased}");
+        assert_eq!(leased[0]["attempt"], 2, "the nack cost an attempt");
+        let stale = leased[0]["receipt"].clone();
+
+        // Never acked: the lease lapses and the message comes back.
+        tokio::time::sleep(std::time::Duration::from_millis(150)).await;
+        let leased = handle
+            .call(
+                "__dispatch",
+                dispatch("lease", serde_json::json!([5, "10s"])),
+            )
+            .await
+            .expect("lease");
+        assert_eq!(
+            leased[0]["attempt"], 3,
+            "the lapse cost an attempt: {leased}"
+        );
+
+        // The lapsed puller's receipt cannot settle the new lease.
+        let acked = handle
+            .call("__dispatch", dispatch("ack", serde_json::json!([stale])))
+            .await
+            .expect("ack");
+        assert_eq!(acked, 0, "a stale receipt settles nothing");
+        let stats = handle
+            .call("__dispatch", dispatch("stats", serde_json::json!([])))
+            .await
+            .expect("stats");
+        assert_eq!(stats["leased"], 1, "{stats}");
     }
 
-    /// The queue substrate end to end: send enqueues, the alarm loop
-    /// delivers to the `on "queue:<name>"` listener, and the payload
-    /// survives the json round trip through sqlite.
+    /// A batched consumer gets one call with every due message once the
+    /// short batch has waited out `max_wait`, and a message it marks for
+    /// retry keeps its own attempt count while its neighbours settle.
     #[tokio::test(flavor = "multi_thread")]
-    async fn a_queue_delivers_sent_messages_to_the_listener() {
+    async fn a_batched_consumer_settles_each_message_on_its_own() {
         const SOURCE: &str = r#"
-            got = nil
-            on "queue:jobs" (function(message)
-                sleep_ms(5)
-                got = message
+            sizes = {}
+            on "queue:jobs" { batch = 3, max_wait = "300ms" } (function(batch)
+                table.insert(sizes, #batch)
+                for _, message in batch do
+                    if message.body == "bad" then
+                        message:retry("refused")
+                    else
+                        message:ack()
+                    end
+                end
             end)
-            function get_got() return got end
+            function get_sizes() return sizes end
         "#;
 
         let dir = tempfile::tempdir().expect("tempdir");
@@ -1754,38 +3696,55 @@ mod tests {
                 ..Default::default()
             },
         );
+        let dispatch = |method: &str, args: serde_json::Value| {
+            serde_json::json!({
+                "class": "__queue", "name": "jobs", "method": method, "args": args,
+            })
+        };
 
-        handle
-            .call(
-                "__dispatch",
-                serde_json::json!({
-                    "class": "__queue", "name": "jobs", "method": "send",
-                    "args": [{"kind": "render", "frame": 17}],
-                }),
-            )
+        for body in ["good", "bad"] {
+            handle
+                .call("__dispatch", dispatch("send", serde_json::json!([body])))
+                .await
+                .expect("send enqueues");
+        }
+
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        let sizes = handle
+            .call("get_sizes", serde_json::Value::Null)
             .await
-            .expect("send enqueues");
+            .expect("read back");
+        assert_eq!(sizes, serde_json::json!([]), "a short batch waits to fill");
 
-        tokio::time::sleep(std::time::Duration::from_millis(400)).await;
-        let got = handle
-            .call("get_got", serde_json::Value::Null)
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+        let sizes = handle
+            .call("get_sizes", serde_json::Value::Null)
             .await
             .expect("read back");
-        assert_eq!(got["kind"], "render", "payload round-trips: {got}");
-        assert_eq!(got["frame"], 17);
+        assert_eq!(sizes, serde_json::json!([2]), "then goes out in one call");
+
+        let messages = handle
+            .call("__dispatch", dispatch("messages", serde_json::json!([])))
+            .await
+            .expect("messages");
+        let messages = messages.as_array().expect("rows");
+        assert_eq!(messages.len(), 1, "only the refused message remains");
+        assert_eq!(messages[0]["payload"], "\"bad\"");
+        assert_eq!(messages[0]["attempts"], 1);
     }
 
-    /// A refused delivery retries with backoff and succeeds on the second
-    /// attempt; nothing is lost and nothing dead-letters.
+    /// A repeated dedup key enqueues nothing, and a group's failing head
+    /// holds back only its own group: the later grouped message waits as
+    /// blocked while an ungrouped one delivers past both.
     #[tokio::test(flavor = "multi_thread")]
-    async fn a_failed_delivery_retries_until_the_handler_accepts() {
+    async fn dedup_keys_suppress_repeats_and_groups_block_only_themselves() {
         const SOURCE: &str = r#"
-            tries = 0
+            got = {}
             on "queue:jobs" (function(message)
-                tries = tries + 1
-                if tries < 2 then error("not yet") end
+                if message == "a1" then error("not yet") end
+                table.insert(got, message)
             end)
-            function get_tries() return tries end
+            function get_got() return got end
         "#;
 
         let dir = tempfile::tempdir().expect("tempdir");
@@ -1795,102 +3754,192 @@ mod tests {
                 storage: Some(
                     crate::storage::SqliteStorage::open(&dir.path().join("q.db")).expect("opens"),
                 ),
-                // Compressed backoff: production waits seconds, the test
-                // needs the retry inside its own window.
-                queue: crate::platform::queue::QueuePolicy {
-                    backoff_base_ms: 20,
-                    ..Default::default()
-                },
                 ..Default::default()
             },
         );
+        let dispatch = |method: &str, args: serde_json::Value| {
+            serde_json::json!({
+                "class": "__queue", "name": "jobs", "method": method, "args": args,
+            })
+        };
 
-        handle
+        let first = handle
             .call(
                 "__dispatch",
-                serde_json::json!({
-                    "class": "__queue", "name": "jobs", "method": "send", "args": ["once"],
-                }),
+                dispatch(
+                    "send",
+                    serde_json::json!(["a1", { "group": "a", "dedup_key": "k" }]),
+                ),
             )
             .await
             .expect("send enqueues");
+        assert_eq!(first, serde_json::json!(true));
+        let repeat = handle
+            .call(
+                "__dispatch",
+                dispatch(
+                    "send",
+                    serde_json::json!(["a1", { "group": "a", "dedup_key": "k" }]),
+                ),
+            )
+            .await
+            .expect("a duplicate is not an error");
+        assert_eq!(
+            repeat,
+            serde_json::json!(false),
+            "the repeat was suppressed"
+        );
+        for (body, options) in [
+            ("a2", serde_json::json!({ "group": "a" })),
+            ("free", serde_json::Value::Null),
+        ] {
+            handle
+                .call(
+                    "__dispatch",
+                    dispatch("send", serde_json::json!([body, options])),
+                )
+                .await
+                .expect("send enqueues");
+        }
 
-        tokio::time::sleep(std::time::Duration::from_millis(600)).await;
-        let tries = handle
-            .call("get_tries", serde_json::Value::Null)
+        tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+        let got = handle
+            .call("get_got", serde_json::Value::Null)
             .await
-            .expect("tries read");
-        assert_eq!(tries.as_i64(), Some(2), "one refusal, one delivery");
+            .expect("read back");
+        assert_eq!(got, serde_json::json!(["free"]), "a2 waits behind a1");
 
         let stats = handle
-            .call(
-                "__dispatch",
-                serde_json::json!({
-                    "class": "__queue", "name": "jobs", "method": "stats", "args": [],
-                }),
-            )
+            .call("__dispatch", dispatch("stats", serde_json::json!([])))
             .await
             .expect("stats");
-        assert_eq!(stats["depth"], 0, "the retried message was consumed");
-        assert_eq!(stats["dead_letters"], 0);
+        assert_eq!(stats["depth"], 2, "{stats}");
+        assert_eq!(stats["blocked"], 1, "{stats}");
+        let messages = handle
+            .call("__dispatch", dispatch("messages", serde_json::json!([])))
+            .await
+            .expect("messages");
+        let a2 = messages
+            .as_array()
+            .expect("rows")
+            .iter()
+            .find(|row| row["payload"] == "\"a2\"")
+            .expect("a2 listed");
+        assert_eq!(a2["state"], "blocked");
+        assert_eq!(a2["group"], "a");
+
+        let events = handle
+            .call("__dispatch", dispatch("events", serde_json::json!([0])))
+            .await
+            .expect("events");
+        assert!(
+            events
+                .as_array()
+                .expect("events")
+                .iter()
+                .any(|event| event["kind"] == "deduplicated" && event["detail"]["dedup_key"] == "k"),
+            "the journal records the suppressed repeat: {events}"
+        );
     }
 
-    /// A poison message exhausts its attempts and lands in the dead-letter
-    /// table instead of blocking the queue forever.
+    /// A consumer's declared policy beats the node's defaults: one attempt
+    /// dead-letters at once where the default would retry for minutes,
+    /// and the file reports the policy it delivered under.
     #[tokio::test(flavor = "multi_thread")]
-    async fn a_poison_message_dead_letters_after_max_attempts() {
+    async fn a_declared_queue_policy_overrides_the_node_defaults() {
+        use crate::proto::script_service::{Capabilities, QueuePolicy, ScriptConfig};
+
         const SOURCE: &str = r#"
+            local jobs = queue "jobs" { max_attempts = 1, backoff = "1s" }
             on "queue:jobs" (function(message)
                 error("always refuses")
             end)
         "#;
+        let revision = Revision {
+            bundle: Some(Bundle {
+                entry_point: "main.lua".to_owned(),
+                files: vec![File {
+                    file_path: "main.lua".to_owned(),
+                    content: SOURCE.as_bytes().to_vec(),
+                    ..Default::default()
+                }],
+            }),
+            script_config: Some(ScriptConfig {
+                capabilities: Some(Capabilities {
+                    events: vec!["queue:jobs".to_owned()],
+                    queues: vec!["jobs".to_owned()],
+                    queue_policies: vec![QueuePolicy {
+                        queue: "jobs".to_owned(),
+                        max_attempts: Some(1),
+                        backoff_ms: Some(1_000),
+                        max_backoff_ms: None,
+                    }],
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let prepared =
+            Arc::new(PreparedRevision::prepare(Script::default(), revision).expect("prepares"));
+        let channel = tonic::transport::Channel::from_static("http://127.0.0.1:1").connect_lazy();
+        let egress = crate::egress::EgressClient::new(crate::egress::EgressPolicy::new([], false))
+            .expect("egress builds");
+        let runtime = ActiasRuntime::new(
+            prepared,
+            KvServiceClient::new(channel),
+            egress,
+            None,
+            None,
+            None,
+        )
+        .await
+        .expect("runtime builds");
 
         let dir = tempfile::tempdir().expect("tempdir");
         let handle = spawn_object_task(
-            runtime_with(SOURCE).await,
+            runtime,
             TaskOptions {
                 storage: Some(
                     crate::storage::SqliteStorage::open(&dir.path().join("q.db")).expect("opens"),
                 ),
-                // Compressed backoff so all five attempts fit the test.
-                queue: crate::platform::queue::QueuePolicy {
-                    backoff_base_ms: 5,
-                    ..Default::default()
-                },
                 ..Default::default()
             },
         );
+        let dispatch = |method: &str| {
+            serde_json::json!({
+                "class": "__queue", "name": "jobs", "method": method, "args": ["poison"],
+            })
+        };
 
         handle
-            .call(
-                "__dispatch",
-                serde_json::json!({
-                    "class": "__queue", "name": "jobs", "method": "send", "args": ["poison"],
-                }),
-            )
+            .call("__dispatch", dispatch("send"))
             .await
             .expect("send enqueues");
+        tokio::time::sleep(std::time::Duration::from_millis(300)).await;
 
-        tokio::time::sleep(std::time::Duration::from_millis(900)).await;
         let stats = handle
-            .call(
-                "__dispatch",
-                serde_json::json!({
-                    "class": "__queue", "name": "jobs", "method": "stats", "args": [],
-                }),
-            )
+            .call("__dispatch", dispatch("stats"))
             .await
             .expect("stats");
-        assert_eq!(stats["depth"], 0, "the poison message left the queue");
-        assert_eq!(
-            stats["dead_letters"], 1,
-            "and landed in the dead-letter table"
-        );
+        assert_eq!(stats["dead_letters"], 1, "one attempt, then dead: {stats}");
+        assert_eq!(stats["policy"]["max_attempts"], 1);
+        assert_eq!(stats["policy"]["backoff_base_ms"], 1_000);
+        // Undeclared fields keep the node's default.
+        assert_eq!(stats["policy"]["max_backoff_ms"], 3_600_000);
+
+        let messages = handle
+            .call("__dispatch", dispatch("messages"))
+            .await
+            .expect("messages");
+        assert_eq!(messages[0]["state"], "dead");
+        assert_eq!(messages[0]["max_attempts"], 1);
     }
 
     /// The inspector's contract: the journal carries message ids,
     /// producers and per-attempt error text; dead letters requeue through
-    /// retry_dead and deliver; drop discards for good.
+    /// retry_dead and deliver; drop discards one for good and purge the
+    /// rest.
     #[tokio::test(flavor = "multi_thread")]
     async fn the_journal_carries_producers_and_dead_letters_requeue() {
         const SOURCE: &str = r#"
@@ -1916,6 +3965,7 @@ mod tests {
                 queue: crate::platform::queue::QueuePolicy {
                     max_attempts: 2,
                     backoff_base_ms: 5,
+                    ..Default::default()
                 },
                 ..Default::default()
             },
@@ -2020,6 +4070,26 @@ mod tests {
             .await
             .expect("stats");
         assert_eq!(stats["dead_letters"], 0, "the drop removed it");
+
+        for _ in 0..3 {
+            handle
+                .call(
+                    "__dispatch",
+                    dispatch("send", serde_json::json!(["later", { "delay": "1h" }])),
+                )
+                .await
+                .expect("send enqueues");
+        }
+        let purged = handle
+            .call("__dispatch", dispatch("purge", serde_json::json!([])))
+            .await
+            .expect("purge");
+        assert_eq!(purged, 3);
+        let stats = handle
+            .call("__dispatch", dispatch("stats", serde_json::json!([])))
+            .await
+            .expect("stats");
+        assert_eq!(stats["depth"], 0, "the purge emptied the queue");
     }
 
     /// Message ids are never reused: a delivered message's id stays
@@ -2165,6 +4235,77 @@ mod tests {
         assert!(cron_delay_ms("cron:not a schedule").is_err());
     }
 
+    #[test]
+    fn cron_occurrences_follow_the_zone_through_dst() {
+        use crate::extensions::objects::{cron_latest_occurrences_ms, cron_occurrences_ms};
+
+        let at = |text: &str| {
+            chrono::DateTime::parse_from_rfc3339(text)
+                .expect("a timestamp")
+                .timestamp_millis()
+        };
+        let new_york: chrono_tz::Tz = "America/New_York".parse().expect("a zone");
+
+        // 02:30 never happens on 8 March 2026; that night fires as the
+        // clocks resume at 03:00 EDT, and the next one is 02:30 EDT.
+        let spring = cron_occurrences_ms(
+            "cron:30 2 * * *",
+            new_york,
+            at("2026-03-07T00:00:00Z"),
+            at("2026-03-10T00:00:00Z"),
+            10,
+        )
+        .expect("occurrences");
+        assert_eq!(
+            spring,
+            vec![
+                at("2026-03-07T07:30:00Z"),
+                at("2026-03-08T07:00:00Z"),
+                at("2026-03-09T06:30:00Z"),
+            ]
+        );
+
+        // 01:30 happens twice on 1 November 2026; it fires on the first
+        // pass only.
+        let autumn = cron_occurrences_ms(
+            "cron:30 1 * * *",
+            new_york,
+            at("2026-10-31T12:00:00Z"),
+            at("2026-11-02T12:00:00Z"),
+            10,
+        )
+        .expect("occurrences");
+        assert_eq!(
+            autumn,
+            vec![at("2026-11-01T05:30:00Z"), at("2026-11-02T06:30:00Z")]
+        );
+
+        // A catch-up window holds each missed occurrence once, in order.
+        let hourly = cron_occurrences_ms(
+            "cron:0 * * * *",
+            chrono_tz::UTC,
+            at("2026-05-01T00:00:00Z"),
+            at("2026-05-01T03:00:00Z"),
+            10,
+        )
+        .expect("occurrences");
+        assert_eq!(hourly.len(), 3);
+
+        // A capped catch-up keeps the newest of them, still in order.
+        let newest = cron_latest_occurrences_ms(
+            "cron:0 * * * *",
+            chrono_tz::UTC,
+            at("2026-05-01T00:00:00Z"),
+            at("2026-05-01T03:00:00Z"),
+            2,
+        )
+        .expect("occurrences");
+        assert_eq!(
+            newest,
+            vec![at("2026-05-01T01:00:00Z"), at("2026-05-01T02:00:00Z")]
+        );
+    }
+
     /// The output gate: only calls that wrote pay it, and it has run by
     /// the time the caller has its answer.
     #[tokio::test(flavor = "multi_thread")]
@@ -1,14 +1,199 @@
 //! The `__cron` platform class: one instance per cron event, whose alarm
-//! re-arms the next occurrence and fires the listener.
+//! re-arms the next occurrence, fires the listener and starts the
+//! workflows scheduled on it.
 //!
-//! The instance name is the event itself (`cron:<expr>`), so the class
-//! needs no storage of its own beyond the alarm row. The re-arm happens
-//! before the fire and listener failures are contained in
+//! The instance name is the event itself (`cron:<expr>`). The re-arm
+//! happens before the fire and listener failures are contained in
 //! [`super::fire_listener`], so a failing handler can never kill the
 //! schedule.
+//!
+//! A listener may declare the zone its expression reads and what happens
+//! to occurrences that passed while no node ran the alarm: `on
+//! "cron:0 9 * * 1-5" { tz = "America/New_York", catch_up = "all" }`.
+//! Occurrences follow the zone's clocks through DST; the state row keeps
+//! the last fire, the next one and every occurrence the policy skipped,
+//! for inspection. A workflow scheduled on the same expression shares
+//! its listener's policy, since both ride the one instance.
+//!
+//! Each fire, the ones a caller asks for through `fire` included, leaves
+//! a row in a short history: the occurrence, when it ran, how long the
+//! listener and starts took and what failed. [`PlatformRead::CronState`]
+//! reads it with the state row off the file alone, which is why `arm`
+//! also records the policy the instance fires by.
+//!
+//! [`PlatformRead::CronState`]: super::PlatformRead::CronState
+//!
+//! A scheduled start names its run after the occurrence it stands for
+//! (`scheduled-<rfc3339>`), never the moment the alarm ran, so a second
+//! node firing the same occurrence after a takeover joins the run the
+//! first one started. The overlap policy decides what a fire does while
+//! the previous scheduled run is still going: `skip` drops it, `allow`
+//! starts anyway, `buffer-one` keeps the newest fire and starts it once
+//! the previous run settles, polling for that between occurrences.
+
+use std::sync::Arc;
+
+use serde::Serialize;
+
+use crate::extensions::objects::{
+    CRON_CLASS, ObjectRouter, ObjectTarget, cron_next_ms, cron_occurrence_ms, cron_occurrences_ms,
+    unix_now_ms,
+};
+use crate::runtime::{ActiasRuntime, PreparedRevision};
+
+/// The cron schema's version, stamped in the file's version cell.
+const SCHEMA_VERSION: i64 = 3;
+
+/// The occurrence the alarm is armed for (an alarm arriving before it is
+/// a buffer poll, not a fire), the last occurrence fired, and the ones
+/// the catch-up policy skipped.
+const CREATE_STATE: &str = "CREATE TABLE IF NOT EXISTS __actias_cron_state (
+        id INTEGER PRIMARY KEY CHECK (id = 1),
+        next_ms INTEGER NOT NULL
+    )";
+
+/// Version 2's state columns, added in place.
+const ADD_STATE_HISTORY: [&str; 3] = [
+    "ALTER TABLE __actias_cron_state ADD COLUMN last_ms INTEGER",
+    "ALTER TABLE __actias_cron_state ADD COLUMN missed INTEGER NOT NULL DEFAULT 0",
+    "ALTER TABLE __actias_cron_state ADD COLUMN last_missed_ms INTEGER",
+];
+
+/// Version 3's state columns: the policy the last arm fired by, so a
+/// read off the file alone knows it.
+const ADD_STATE_POLICY: [&str; 2] = [
+    "ALTER TABLE __actias_cron_state ADD COLUMN tz TEXT",
+    "ALTER TABLE __actias_cron_state ADD COLUMN catch_up TEXT",
+];
+
+/// One row per fire, newest kept: the occurrence it stood for, when it
+/// ran, how long the listener and starts took, and what failed.
+const CREATE_FIRES: &str = "CREATE TABLE IF NOT EXISTS __actias_cron_fires (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        scheduled_ms INTEGER NOT NULL,
+        fired_ms INTEGER NOT NULL,
+        duration_ms INTEGER NOT NULL,
+        error TEXT,
+        manual INTEGER NOT NULL DEFAULT 0
+    )";
+
+/// How many fires the history keeps.
+const FIRE_HISTORY: i64 = 50;
+
+/// Per scheduled workflow: the run its last fire started and the one fire
+/// `buffer-one` holds back.
+const CREATE_SCHEDULES: &str = "CREATE TABLE IF NOT EXISTS __actias_cron_schedules (
+        workflow TEXT PRIMARY KEY,
+        last_run TEXT,
+        buffered TEXT
+    )";
+
+/// How often a held-back fire checks whether the previous run settled,
+/// when the next occurrence is further off than this.
+const BUFFER_POLL_MS: i64 = 30_000;
 
-use crate::extensions::objects::{CRON_CLASS, cron_delay_ms, unix_now_ms};
-use crate::runtime::ActiasRuntime;
+/// Most missed occurrences `catch_up = "all"` fires: the newest ones, in
+/// order; any older ones are recorded as missed.
+const MAX_CATCH_UP: usize = 100;
+
+/// How many missed occurrences one fire counts before it stops looking.
+const MAX_COUNTED: usize = 10_000;
+
+/// How late an alarm may run and still be on time for `catch_up =
+/// "none"`: alarms are never exact, outages are longer than this.
+const ON_TIME_MS: i64 = 60_000;
+
+/// What a fire does about occurrences that passed while no node ran the
+/// alarm.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CatchUp {
+    /// Fires each missed occurrence in order, at most the newest
+    /// [`MAX_CATCH_UP`] of them, then the latest.
+    All,
+    /// Fires the latest occurrence once, however late.
+    #[default]
+    Latest,
+    /// Fires only an occurrence the alarm is on time for.
+    None,
+}
+
+impl CatchUp {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CatchUp::All => "all",
+            CatchUp::Latest => "latest",
+            CatchUp::None => "none",
+        }
+    }
+
+    pub fn parse(text: &str) -> Option<Self> {
+        match text {
+            "all" => Some(CatchUp::All),
+            "latest" => Some(CatchUp::Latest),
+            "none" => Some(CatchUp::None),
+            _ => None,
+        }
+    }
+}
+
+/// How a cron listener's expression is read and fired: `on "cron:0 9 *
+/// * 1-5" { tz = "America/New_York", catch_up = "latest" } (fn)`. UTC and
+/// `latest` unless declared.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CronPolicy {
+    pub tz: chrono_tz::Tz,
+    pub catch_up: CatchUp,
+}
+
+impl Default for CronPolicy {
+    fn default() -> Self {
+        CronPolicy {
+            tz: chrono_tz::UTC,
+            catch_up: CatchUp::default(),
+        }
+    }
+}
+
+impl CronPolicy {
+    /// Reads `{ tz = "Europe/Paris", catch_up = "all" }`. Unknown keys,
+    /// zones and policies fail the declaration, the way a policy typo
+    /// does.
+    pub fn from_table(event: &str, table: &mlua::Table) -> Result<Self, String> {
+        let mut policy = CronPolicy::default();
+
+        for pair in table.pairs::<String, mlua::Value>() {
+            let (key, value) = pair.map_err(|e| format!("Cron '{event}' options: {e}"))?;
+            let text = match &value {
+                mlua::Value::String(text) => text.to_str().ok().map(|text| text.to_string()),
+                _ => None,
+            };
+            match key.as_str() {
+                "tz" => {
+                    policy.tz = text
+                        .and_then(|zone| zone.parse::<chrono_tz::Tz>().ok())
+                        .ok_or_else(|| {
+                            format!(
+                                "Cron '{event}': tz must be an IANA zone like \"Europe/Paris\"."
+                            )
+                        })?;
+                }
+                "catch_up" => {
+                    policy.catch_up =
+                        text.as_deref().and_then(CatchUp::parse).ok_or_else(|| {
+                            format!("Cron '{event}': catch_up must be all, latest or none.")
+                        })?;
+                }
+                other => {
+                    return Err(format!(
+                        "Cron '{event}' has no option '{other}'; expected tz or catch_up."
+                    ));
+                }
+            }
+        }
+
+        Ok(policy)
+    }
+}
 
 /// Routes one `__cron` method call.
 ///
@@ -20,9 +205,42 @@ pub(crate) async fn dispatch(
     context: &super::PlatformContext<'_>,
     call: &super::Call,
 ) -> Result<serde_json::Value, String> {
+    context.home.with_storage(|storage| {
+        let version = storage.schema_version()?;
+        if version >= SCHEMA_VERSION {
+            return Ok(());
+        }
+        let connection = storage.platform();
+        if version == 0 {
+            for statement in [CREATE_STATE, CREATE_SCHEDULES] {
+                connection
+                    .execute(statement, [])
+                    .map_err(|e| e.to_string())?;
+            }
+        }
+        if version <= 1 {
+            for statement in ADD_STATE_HISTORY {
+                connection
+                    .execute(statement, [])
+                    .map_err(|e| e.to_string())?;
+            }
+        }
+        if version <= 2 {
+            for statement in ADD_STATE_POLICY.into_iter().chain([CREATE_FIRES]) {
+                connection
+                    .execute(statement, [])
+                    .map_err(|e| e.to_string())?;
+            }
+        }
+        storage.set_schema_version(SCHEMA_VERSION)
+    })?;
+
+    let policy = runtime.cron_policy(context.name);
     match call.method.as_str() {
-        "ensure" => ensure(context),
-        "alarm" => fire(runtime, context).await,
+        "ensure" => ensure(context, &policy),
+        "alarm" => fire(runtime, context, &policy).await,
+        "fire" => fire_now(runtime, context).await,
+        "state" => state(runtime, context, &policy),
         other => Err(format!(
             "Object class '{CRON_CLASS}' has no method '{other}'."
         )),
@@ -31,27 +249,561 @@ pub(crate) async fn dispatch(
 
 /// Arms the first occurrence; called once per revision per process, and
 /// idempotent because setting an alarm replaces the previous one.
-fn ensure(context: &super::PlatformContext<'_>) -> Result<serde_json::Value, String> {
-    let delay_ms = cron_delay_ms(context.name)?;
-    super::set_alarm(context, CRON_CLASS, delay_ms)?;
+fn ensure(
+    context: &super::PlatformContext<'_>,
+    policy: &CronPolicy,
+) -> Result<serde_json::Value, String> {
+    arm(context, policy)?;
     Ok(serde_json::Value::Null)
 }
 
-/// Re-arms the next occurrence, then fires the listener; in that order,
-/// so the schedule survives anything the handler does.
-async fn fire(
+/// What the instance knows about its schedule: the policy it fires by,
+/// the last and next fire, what it skipped, its recent fires, and each
+/// scheduled workflow's last run and held-back fire.
+fn state(
     runtime: &ActiasRuntime,
     context: &super::PlatformContext<'_>,
+    policy: &CronPolicy,
 ) -> Result<serde_json::Value, String> {
-    let delay_ms = cron_delay_ms(context.name)?;
-    super::set_alarm(context, CRON_CLASS, delay_ms)?;
+    let recorded = context.home.with_storage(read_state)?;
+
+    let mut schedules = Vec::new();
+    for (workflow, overlap) in scheduled_workflows(runtime, context) {
+        let (last_run, buffered) = schedule_row(context, &workflow)?;
+        schedules.push(serde_json::json!({
+            "workflow": workflow,
+            "overlap": overlap,
+            "last_run": last_run,
+            "buffered": buffered,
+        }));
+    }
 
-    let payload = serde_json::json!({
+    Ok(serde_json::json!({
         "cron": context.name,
-        "scheduled_at": unix_now_ms(),
-    });
-    // The verdict is already logged; a cron fire has no retry story.
-    let _ = super::fire_listener(runtime, context.name, &payload).await;
+        "tz": policy.tz.name(),
+        "catch_up": policy.catch_up.as_str(),
+        "next_ms": recorded.next_ms,
+        "last_ms": recorded.last_ms,
+        "missed": recorded.missed,
+        "last_missed_ms": recorded.last_missed_ms,
+        "fires": recorded.fires,
+        "schedules": schedules,
+    }))
+}
+
+/// What [`PlatformRead::CronState`] reads: the state row and the fire
+/// history, newest first; plain data by design.
+///
+/// [`PlatformRead::CronState`]: super::PlatformRead::CronState
+#[derive(Debug, Default, Serialize)]
+pub struct CronState {
+    /// The zone and catch-up policy the last arm fired by; absent in a
+    /// file no dispatch has touched since policies were recorded.
+    pub tz: Option<String>,
+    pub catch_up: Option<String>,
+    /// The occurrence the alarm is armed for.
+    pub next_ms: Option<i64>,
+    /// The last occurrence the schedule fired.
+    pub last_ms: Option<i64>,
+    /// Occurrences the catch-up policy skipped, and the latest of them.
+    pub missed: i64,
+    pub last_missed_ms: Option<i64>,
+    pub fires: Vec<Fire>,
+}
+
+/// One fire as the history records it.
+#[derive(Debug, Serialize)]
+pub struct Fire {
+    /// The occurrence the fire stood for; a manual fire's is its own
+    /// moment.
+    pub scheduled_ms: i64,
+    pub fired_ms: i64,
+    /// How long the listener and the scheduled starts took together.
+    pub duration_ms: i64,
+    /// What failed, listener and starts joined; absent when nothing did.
+    pub error: Option<String>,
+    /// Whether a caller asked for it through `fire` rather than the alarm.
+    pub manual: bool,
+}
+
+/// Reads the state row and the fire history. A file that predates either
+/// reads as what it holds: a fresh object has no state, one armed before
+/// version 3 has no policy and no history yet.
+///
+/// # Errors
+/// Returns SQLite's message.
+pub fn read_state(storage: &mut crate::storage::SqliteStorage) -> Result<CronState, String> {
+    if !storage.table_exists("__actias_cron_state")? {
+        return Ok(CronState::default());
+    }
+    let version = storage.schema_version()?;
+    let columns = match version {
+        ..=1 => "NULL, NULL, next_ms, NULL, 0, NULL",
+        2 => "NULL, NULL, next_ms, last_ms, missed, last_missed_ms",
+        _ => "tz, catch_up, next_ms, last_ms, missed, last_missed_ms",
+    };
+    let mut state = storage
+        .platform()
+        .query_row(
+            &format!("SELECT {columns} FROM __actias_cron_state WHERE id = 1"),
+            [],
+            |row| {
+                Ok(CronState {
+                    tz: row.get(0)?,
+                    catch_up: row.get(1)?,
+                    next_ms: row.get(2)?,
+                    last_ms: row.get(3)?,
+                    missed: row.get(4)?,
+                    last_missed_ms: row.get(5)?,
+                    fires: Vec::new(),
+                })
+            },
+        )
+        .or_else(|error| match error {
+            rusqlite::Error::QueryReturnedNoRows => Ok(CronState::default()),
+            error => Err(error.to_string()),
+        })?;
+
+    if version >= 3 {
+        let connection = storage.platform();
+        let mut statement = connection
+            .prepare(
+                "SELECT scheduled_ms, fired_ms, duration_ms, error, manual \
+                 FROM __actias_cron_fires ORDER BY id DESC",
+            )
+            .map_err(|e| e.to_string())?;
+        state.fires = statement
+            .query_map([], |row| {
+                Ok(Fire {
+                    scheduled_ms: row.get(0)?,
+                    fired_ms: row.get(1)?,
+                    duration_ms: row.get(2)?,
+                    error: row.get(3)?,
+                    manual: row.get(4)?,
+                })
+            })
+            .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(state)
+}
+
+/// The workflows the revision schedules on this instance's expression,
+/// with their overlap policies.
+fn scheduled_workflows(
+    runtime: &ActiasRuntime,
+    context: &super::PlatformContext<'_>,
+) -> Vec<(String, String)> {
+    runtime
+        .app_data_ref::<Arc<PreparedRevision>>()
+        .map(|prepared| {
+            prepared
+                .workflow_schedules(context.name)
+                .into_iter()
+                .map(|schedule| (schedule.workflow.clone(), schedule.overlap.clone()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
 
+/// Re-arms the next occurrence, then fires the listener and the
+/// schedules for each occurrence the catch-up policy keeps; in that
+/// order, so the schedule survives anything the handler does. An alarm
+/// before the armed occurrence only drains buffered starts.
+async fn fire(
+    runtime: &ActiasRuntime,
+    context: &super::PlatformContext<'_>,
+    policy: &CronPolicy,
+) -> Result<serde_json::Value, String> {
+    let armed = context.home.with_storage(|storage| {
+        storage
+            .platform()
+            .query_row(
+                "SELECT next_ms FROM __actias_cron_state WHERE id = 1",
+                [],
+                |row| row.get::<_, i64>(0),
+            )
+            .map(Some)
+            .or_else(|error| match error {
+                rusqlite::Error::QueryReturnedNoRows => Ok(None),
+                error => Err(error.to_string()),
+            })
+    })?;
+    let now = unix_now_ms();
+    let fires = match armed {
+        Some(next_ms) if next_ms > now => Vec::new(),
+        armed => {
+            let (fires, skipped) = catch_up(context.name, policy, armed, now)?;
+            if !skipped.is_empty() {
+                actias_common::tracing::warn!(
+                    cron = context.name,
+                    missed = skipped.len(),
+                    catch_up = policy.catch_up.as_str(),
+                    "cron occurrences passed unfired"
+                );
+            }
+            arm(context, policy)?;
+            record_fire(context, &fires, &skipped)?;
+            fires
+        }
+    };
+
+    run_occurrences(runtime, context, &fires, false).await?;
+
+    // A held-back fire polls between occurrences rather than waiting for
+    // the next one.
+    arm(context, policy)?;
     Ok(serde_json::Value::Null)
 }
+
+/// Fires the listener and the schedules once, now, for a caller
+/// debugging them: no catch-up, no re-arm, and the scheduled runs are
+/// named `manual-<rfc3339>` so they never join a scheduled one. Returns
+/// the fire's history row.
+async fn fire_now(
+    runtime: &ActiasRuntime,
+    context: &super::PlatformContext<'_>,
+) -> Result<serde_json::Value, String> {
+    let fired = run_occurrences(runtime, context, &[unix_now_ms()], true).await?;
+    serde_json::to_value(fired.into_iter().next()).map_err(|e| e.to_string())
+}
+
+/// Fires the listener and starts the scheduled workflows for each
+/// occurrence in turn, recording each in the history. Buffered starts
+/// whose predecessor settled go first, occurrences or not.
+async fn run_occurrences(
+    runtime: &ActiasRuntime,
+    context: &super::PlatformContext<'_>,
+    occurrences: &[i64],
+    manual: bool,
+) -> Result<Vec<Fire>, String> {
+    let workflows = scheduled_workflows(runtime, context);
+    let router = runtime
+        .app_data_ref::<ObjectRouter>()
+        .map(|router| router.clone())
+        .filter(|_| !workflows.is_empty());
+    if router.is_none() && !workflows.is_empty() {
+        actias_common::tracing::warn!(cron = context.name, "no router for scheduled workflows");
+    }
+
+    let mut schedules = Vec::new();
+    if let Some(router) = &router {
+        for (workflow, overlap) in workflows.iter() {
+            let (mut last_run, mut buffered) = schedule_row(context, workflow)?;
+            let mut running = match &last_run {
+                Some(run) => !settled(router, context, workflow, run).await,
+                None => false,
+            };
+
+            // The held-back fire goes first once its predecessor settles,
+            // so a fire landing now is its successor.
+            if let Some(held) = buffered.take_if(|_| !running) {
+                // Its failure belongs to the fire that buffered it, which
+                // is already recorded; the log is all it gets.
+                let _ = start(router, context, workflow, &held).await;
+                last_run = Some(held);
+                running = true;
+            }
+            schedules.push((workflow, overlap, last_run, buffered, running));
+        }
+    }
+
+    // A schedule-only expression has no listener, and that is fine.
+    let listens = workflows.is_empty() || runtime.listener(context.name).is_ok();
+    let mut fired = Vec::new();
+    for occurrence in occurrences {
+        let fired_ms = unix_now_ms();
+        let mut errors = Vec::new();
+        if listens {
+            let payload = serde_json::json!({
+                "cron": context.name,
+                "scheduled_at": occurrence,
+                "manual": manual,
+            });
+            // The verdict is already logged; a cron fire has no retry
+            // story, only a history row.
+            if let Err(error) = super::fire_listener(runtime, context.name, &payload).await {
+                errors.push(error);
+            }
+        }
+
+        if let Some(router) = &router {
+            let id = run_id(*occurrence, manual)?;
+            for (workflow, overlap, last_run, buffered, running) in schedules.iter_mut() {
+                match overlap.as_str() {
+                    // Only the newest fire waits; an older held one is
+                    // dropped.
+                    "buffer-one" if *running => *buffered = Some(id.clone()),
+                    "skip" if *running => {}
+                    _ => {
+                        if let Err(error) = start(router, context, workflow, &id).await {
+                            errors.push(format!("{workflow}: {error}"));
+                        }
+                        *last_run = Some(id.clone());
+                        *running = true;
+                    }
+                }
+            }
+        }
+
+        let fire = Fire {
+            scheduled_ms: *occurrence,
+            fired_ms,
+            duration_ms: unix_now_ms() - fired_ms,
+            error: (!errors.is_empty()).then(|| errors.join("; ")),
+            manual,
+        };
+        record_history(context, &fire)?;
+        fired.push(fire);
+    }
+
+    for (workflow, _, last_run, buffered, _) in &schedules {
+        store_schedule_row(context, workflow, last_run.as_deref(), buffered.as_deref())?;
+    }
+    Ok(fired)
+}
+
+/// Appends a fire to the history and drops what falls off its end.
+fn record_history(context: &super::PlatformContext<'_>, fire: &Fire) -> Result<(), String> {
+    context.home.with_storage(|storage| {
+        let connection = storage.platform();
+        connection
+            .execute(
+                "INSERT INTO __actias_cron_fires \
+                 (scheduled_ms, fired_ms, duration_ms, error, manual) VALUES (?, ?, ?, ?, ?)",
+                rusqlite::params![
+                    fire.scheduled_ms,
+                    fire.fired_ms,
+                    fire.duration_ms,
+                    fire.error,
+                    fire.manual
+                ],
+            )
+            .map_err(|e| e.to_string())?;
+        connection
+            .execute(
+                "DELETE FROM __actias_cron_fires WHERE id <= \
+                 (SELECT MAX(id) FROM __actias_cron_fires) - ?",
+                rusqlite::params![FIRE_HISTORY],
+            )
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    })
+}
+
+/// The occurrences a due alarm fires, oldest first, and the ones it
+/// skips, per the catch-up policy. `armed` is
+/// the occurrence the alarm was set for: everything from it up to now
+/// passed unfired. An object armed before the state table existed has no
+/// record, and fires the occurrence just passed.
+fn catch_up(
+    event: &str,
+    policy: &CronPolicy,
+    armed: Option<i64>,
+    now: i64,
+) -> Result<(Vec<i64>, Vec<i64>), String> {
+    let latest = cron_occurrence_ms(event, policy.tz)?;
+    let passed = match armed {
+        Some(next_ms) => {
+            cron_occurrences_ms(event, policy.tz, next_ms - 1, latest - 1, MAX_COUNTED)?
+        }
+        None => Vec::new(),
+    };
+
+    let (mut fires, mut skipped) = match policy.catch_up {
+        CatchUp::All => {
+            // Counting stops at MAX_COUNTED, so past it the newest are
+            // looked up from the other end of the window.
+            let fires = match armed {
+                Some(next_ms) if passed.len() >= MAX_COUNTED => {
+                    crate::extensions::objects::cron_latest_occurrences_ms(
+                        event,
+                        policy.tz,
+                        next_ms - 1,
+                        latest,
+                        MAX_CATCH_UP,
+                    )?
+                }
+                _ => passed[passed.len().saturating_sub(MAX_CATCH_UP)..].to_vec(),
+            };
+            let oldest_fired = fires.first().copied().unwrap_or(i64::MAX);
+            let skipped = passed.into_iter().filter(|at| *at < oldest_fired).collect();
+            (fires, skipped)
+        }
+        CatchUp::Latest | CatchUp::None => (Vec::new(), passed),
+    };
+    if policy.catch_up != CatchUp::None || now - latest <= ON_TIME_MS {
+        fires.push(latest);
+    } else {
+        skipped.push(latest);
+    }
+    Ok((fires, skipped))
+}
+
+/// Records a fire in the state row: the last occurrence fired, and the
+/// count and latest of those the policy skipped.
+fn record_fire(
+    context: &super::PlatformContext<'_>,
+    fires: &[i64],
+    skipped: &[i64],
+) -> Result<(), String> {
+    context.home.with_storage(|storage| {
+        storage
+            .platform()
+            .execute(
+                "UPDATE __actias_cron_state SET \
+                 last_ms = COALESCE(?, last_ms), \
+                 missed = missed + ?, \
+                 last_missed_ms = COALESCE(?, last_missed_ms) \
+                 WHERE id = 1",
+                rusqlite::params![fires.last(), skipped.len() as i64, skipped.last()],
+            )
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    })
+}
+
+/// Arms the next occurrence, or the buffer poll when a fire is held back
+/// and the occurrence is further off, and records which occurrence the
+/// alarm stands for and the policy it fires by.
+fn arm(context: &super::PlatformContext<'_>, policy: &CronPolicy) -> Result<(), String> {
+    let next_ms = cron_next_ms(context.name, policy.tz)?;
+    let held = context.home.with_storage(|storage| {
+        let connection = storage.platform();
+        connection
+            .execute(
+                "INSERT INTO __actias_cron_state (id, next_ms, tz, catch_up) \
+                 VALUES (1, ?, ?, ?) ON CONFLICT (id) DO UPDATE SET \
+                 next_ms = excluded.next_ms, tz = excluded.tz, catch_up = excluded.catch_up",
+                rusqlite::params![next_ms, policy.tz.name(), policy.catch_up.as_str()],
+            )
+            .map_err(|e| e.to_string())?;
+        connection
+            .query_row(
+                "SELECT COUNT(*) FROM __actias_cron_schedules WHERE buffered IS NOT NULL",
+                [],
+                |row| row.get::<_, i64>(0),
+            )
+            .map_err(|e| e.to_string())
+    })?;
+
+    let mut delay_ms = (next_ms - unix_now_ms()).max(1000);
+    if held > 0 {
+        delay_ms = delay_ms.min(BUFFER_POLL_MS);
+    }
+    super::set_alarm(context, CRON_CLASS, delay_ms)
+}
+
+/// The deterministic run id of the fire for `occurrence`: the scheduled
+/// time at second precision, in UTC; a manual fire's says so.
+fn run_id(occurrence: i64, manual: bool) -> Result<String, String> {
+    let at = chrono::DateTime::from_timestamp_millis(occurrence)
+        .ok_or_else(|| format!("occurrence {occurrence} is out of range"))?;
+    Ok(format!(
+        "{}-{}",
+        if manual { "manual" } else { "scheduled" },
+        at.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+    ))
+}
+
+fn schedule_row(
+    context: &super::PlatformContext<'_>,
+    workflow: &str,
+) -> Result<(Option<String>, Option<String>), String> {
+    context.home.with_storage(|storage| {
+        storage
+            .platform()
+            .query_row(
+                "SELECT last_run, buffered FROM __actias_cron_schedules WHERE workflow = ?",
+                rusqlite::params![workflow],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .or_else(|error| match error {
+                rusqlite::Error::QueryReturnedNoRows => Ok((None, None)),
+                error => Err(error.to_string()),
+            })
+    })
+}
+
+fn store_schedule_row(
+    context: &super::PlatformContext<'_>,
+    workflow: &str,
+    last_run: Option<&str>,
+    buffered: Option<&str>,
+) -> Result<(), String> {
+    context.home.with_storage(|storage| {
+        storage
+            .platform()
+            .execute(
+                "INSERT INTO __actias_cron_schedules (workflow, last_run, buffered) \
+                 VALUES (?, ?, ?) ON CONFLICT (workflow) DO UPDATE \
+                 SET last_run = excluded.last_run, buffered = excluded.buffered",
+                rusqlite::params![workflow, last_run, buffered],
+            )
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    })
+}
+
+/// Starts (or, for an id a previous fire already used, joins) the
+/// scheduled run. A failed start is logged and not retried: the run is
+/// the workflow's to retry, the fire is the schedule's to repeat.
+async fn start(
+    router: &ObjectRouter,
+    context: &super::PlatformContext<'_>,
+    workflow: &str,
+    id: &str,
+) -> Result<(), String> {
+    let input = serde_json::json!({ "schedule": context.name });
+    let started = router(target(context, workflow, id, "start", vec![input])).await;
+    started.map(|_| ()).map_err(|error| {
+        actias_common::tracing::warn!(
+            %error, cron = context.name, workflow, id, "scheduled workflow did not start"
+        );
+        error
+    })
+}
+
+/// Whether the scheduled run `id` is finished one way or another. A run
+/// the router cannot reach counts as running, so `skip` errs towards one
+/// run at a time.
+async fn settled(
+    router: &ObjectRouter,
+    context: &super::PlatformContext<'_>,
+    workflow: &str,
+    id: &str,
+) -> bool {
+    match router(target(context, workflow, id, "status", Vec::new())).await {
+        // A run whose start never landed has no journal to wait on.
+        Ok(serde_json::Value::Null) => true,
+        Ok(status) => matches!(
+            status["state"].as_str(),
+            Some("completed" | "cancelled" | "failed")
+        ),
+        Err(error) => {
+            actias_common::tracing::warn!(
+                %error, cron = context.name, workflow, id, "scheduled run status unknown"
+            );
+            false
+        }
+    }
+}
+
+fn target(
+    context: &super::PlatformContext<'_>,
+    workflow: &str,
+    id: &str,
+    method: &str,
+    arguments: Vec<serde_json::Value>,
+) -> ObjectTarget {
+    ObjectTarget {
+        class: actias_common::classes::WORKFLOW_CLASS.to_owned(),
+        name: format!("{workflow}/{id}"),
+        method: method.to_owned(),
+        arguments,
+        chain: vec![context.own_key.to_owned()],
+        caller: None,
+        consistency: None,
+        deadline: None,
+    }
+}
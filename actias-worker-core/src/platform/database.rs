@@ -6,17 +6,64 @@
 //! methods. The statements are user SQL, so unlike the other platform
 //! classes every one of them runs through the script-guarded
 //! [`SqliteStorage`] surface, never the bare connection.
+//!
+//! Scripts react to writes with `on "database:<name>"`. While the
+//! project declares such a listener, every statement runs under SQLite's
+//! update hook; the rows it touched are read back after the statement and
+//! appended, still inside the call's transaction, to a change log the
+//! alarm fans out exactly like a topic's (see [`super::topic`]). A
+//! listener gets one call's changes as a list, each `{ table, op, key,
+//! values }`, with the retries, backoff and dead letters of its own
+//! subscription, named `database/<name>/<script id>`. A rolled-back call
+//! logs nothing, so listeners only ever see committed rows. A database
+//! nobody listens to pays nothing for any of it, and a call changing many
+//! rows writes its log entries as they fill rather than holding them all.
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
 
 use crate::extensions::objects::DATABASE_CLASS;
 use crate::objects::ObjectHome;
-use crate::storage::SqliteStorage;
+use crate::runtime::ActiasRuntime;
+use crate::storage::{ChangeOp, RowChange, SqliteStorage};
 
 /// A typed handle to one database instance's operations.
 pub struct Database<'a> {
     home: &'a ObjectHome,
     name: &'a str,
+    /// Whether statements are watched for row changes.
+    watch: bool,
+    /// Changes watched but not yet logged, described and in statement
+    /// order; never more than one entry's worth.
+    changes: RefCell<Vec<serde_json::Value>>,
+    /// Whether this call has logged a full entry already.
+    logged: Cell<bool>,
 }
 
+/// The change log: committed calls' row changes not yet at every
+/// listener, shaped like the topic log so the same fan-out drains it.
+const CHANGE_LOG: &str = "__actias_db_changes";
+
+const CREATE_CHANGE_LOG: &str = "CREATE TABLE IF NOT EXISTS __actias_db_changes (
+        seq INTEGER PRIMARY KEY AUTOINCREMENT,
+        payload TEXT NOT NULL,
+        published_at INTEGER NOT NULL,
+        attempts INTEGER NOT NULL DEFAULT 0,
+        next_at INTEGER NOT NULL
+    )";
+
+/// Most changes one log entry carries; a call touching more splits into
+/// several deliveries, in order, rather than one unbounded payload.
+const CHANGES_PER_ENTRY: usize = 100;
+
+/// Whether a database has listeners, asked before each call with its
+/// `database:<name>` event. Unlike the fan-out's own
+/// [`super::topic::SubscriberLookup`], the worker answers this from a
+/// cache on the pointer ttl: it runs on every call, and a new listener
+/// only has to start seeing writes once its publish has propagated. A vm
+/// without one watches whenever it can fan out.
+pub struct ListenerCheck(pub super::topic::SubscriberLookup);
+
 impl<'a> Database<'a> {
     /// Opens the instance, applying pending migrations first when this is
     /// the vm's first touch: the tracking rows ride the call's
@@ -26,7 +73,13 @@ impl<'a> Database<'a> {
     /// # Errors
     /// Returns the failed migration's user-safe message.
     pub fn open(home: &'a ObjectHome, name: &'a str) -> Result<Self, String> {
-        let database = Self { home, name };
+        let database = Self {
+            home,
+            name,
+            watch: false,
+            changes: RefCell::default(),
+            logged: Cell::new(false),
+        };
         if home.migrations_unchecked() {
             database.apply_migrations()?;
             home.mark_migrations_checked();
@@ -34,12 +87,35 @@ impl<'a> Database<'a> {
         Ok(database)
     }
 
+    /// Watches every statement from here on, logging the rows each one
+    /// touched; [`Self::finish_changes`] logs the rest. Migrations
+    /// already ran, so what they seed is never reported.
+    pub fn watching_changes(mut self) -> Self {
+        self.watch = true;
+        self
+    }
+
+    /// Logs what the call's watched statements left unlogged, answering
+    /// whether the call logged anything at all.
+    ///
+    /// # Errors
+    /// Returns SQLite's message.
+    pub fn finish_changes(&self) -> Result<bool, String> {
+        let rest = self.changes.take();
+        if !rest.is_empty() {
+            self.home
+                .with_storage(|storage| append_entry(storage, &rest))?;
+            self.logged.set(true);
+        }
+        Ok(self.logged.get())
+    }
+
     /// Runs one statement; the affected row count is the result.
     ///
     /// # Errors
     /// Returns a refused statement's or SQLite's user-safe message.
     pub fn exec(&self, sql: &str, params: &[serde_json::Value]) -> Result<u64, String> {
-        self.home.with_storage(|storage| storage.exec(sql, params))
+        self.statement(|storage| storage.exec(sql, params))
     }
 
     /// Runs one query; rows come back as string-keyed json objects.
@@ -51,7 +127,8 @@ impl<'a> Database<'a> {
         sql: &str,
         params: &[serde_json::Value],
     ) -> Result<Vec<serde_json::Value>, String> {
-        self.home.with_storage(|storage| storage.query(sql, params))
+        // A query may write too (`INSERT ... RETURNING`).
+        self.statement(|storage| storage.query(sql, params))
     }
 
     /// [`Self::query`] returning only the first row, if any.
@@ -82,7 +159,39 @@ impl<'a> Database<'a> {
             .collect()
     }
 
-    /// Applies this database's pending migrations in order.
+    /// Runs one statement against storage, watched when this handle
+    /// watches. The touched rows are read back right after the statement,
+    /// before a later one can move them, and every full entry's worth is
+    /// logged there and then.
+    fn statement<T>(
+        &self,
+        run: impl FnOnce(&mut SqliteStorage) -> Result<T, String>,
+    ) -> Result<T, String> {
+        if !self.watch {
+            return self.home.with_storage(run);
+        }
+
+        self.home.with_storage(|storage| {
+            let (result, touched) = storage.watch_changes(run);
+            let result = result?;
+            let mut keys = HashMap::new();
+            let mut changes = self.changes.borrow_mut();
+            for change in &touched {
+                changes.push(describe(storage, change, &mut keys)?);
+                if changes.len() == CHANGES_PER_ENTRY {
+                    append_entry(storage, &changes)?;
+                    changes.clear();
+                    self.logged.set(true);
+                }
+            }
+            Ok(result)
+        })
+    }
+
+    /// Applies this database's pending migrations in order, after
+    /// checking that none already applied was edited since: a database
+    /// whose history no longer matches its files refuses every call
+    /// rather than silently diverge from a fresh one.
     fn apply_migrations(&self) -> Result<(), String> {
         let Some(revision) = self.home.revision() else {
             return Err("Runtime has no revision loaded.".to_owned());
@@ -93,30 +202,213 @@ impl<'a> Database<'a> {
         }
 
         self.home.with_storage(|storage: &mut SqliteStorage| {
-            let applied = storage.applied_migrations()?;
+            let applied: std::collections::HashMap<String, Option<String>> =
+                storage.applied_migrations()?.into_iter().collect();
             for (name, sql) in migrations {
-                if applied.contains(&name) {
-                    continue;
+                let checksum = migration_checksum(&sql);
+                match applied.get(&name) {
+                    Some(Some(recorded)) if *recorded != checksum => {
+                        return Err(format!(
+                            "Migration {name} was edited after it was applied to database \
+                             '{}'; restore it and add a new migration instead.",
+                            self.name
+                        ));
+                    }
+                    Some(Some(_)) => {}
+                    Some(None) => storage.adopt_checksum(&name, &checksum)?,
+                    None => {
+                        storage
+                            .exec_script(&sql)
+                            .map_err(|error| format!("Migration {name} failed: {error}"))?;
+                        storage.record_migration(&name, &checksum)?;
+                    }
                 }
-                storage
-                    .exec_script(&sql)
-                    .map_err(|error| format!("Migration {name} failed: {error}"))?;
-                storage.record_migration(&name)?;
             }
             Ok(())
         })
     }
 }
 
+/// The checksum an applied migration is recorded with: the blake3 of its
+/// content, the same hash the bundle store keys the file by, so `actias
+/// publish` can compare against a deployed revision without the worker.
+pub fn migration_checksum(sql: &str) -> String {
+    blake3::hash(sql.as_bytes()).to_hex().to_string()
+}
+
+/// A table's primary key columns in key order, each with whether it is
+/// the rowid itself (a lone `INTEGER PRIMARY KEY`).
+fn primary_key(storage: &mut SqliteStorage, table: &str) -> Result<Vec<(String, bool)>, String> {
+    let columns: Vec<(String, String)> = {
+        let mut statement = storage
+            .platform()
+            .prepare("SELECT name, type FROM pragma_table_info(?) WHERE pk > 0 ORDER BY pk")
+            .map_err(|e| e.to_string())?;
+        statement
+            .query_map([table], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| e.to_string())?
+            .collect::<Result<_, _>>()
+            .map_err(|e| e.to_string())?
+    };
+    let is_rowid = columns.len() == 1 && columns[0].1.eq_ignore_ascii_case("INTEGER");
+    Ok(columns
+        .into_iter()
+        .map(|(name, _)| (name, is_rowid))
+        .collect())
+}
+
+/// One touched row as a listener sees it: the table, the operation, the
+/// primary key's columns and the row's values after the statement (nil
+/// for a delete, or for a row a later write in the same statement
+/// removed). The hook only knows the rowid; a deleted row's key is the
+/// one storage kept on its way out, and the rowid stands in as
+/// `{ rowid = N }` only for a table without a primary key.
+fn describe(
+    storage: &mut SqliteStorage,
+    change: &RowChange,
+    keys: &mut HashMap<String, Vec<(String, bool)>>,
+) -> Result<serde_json::Value, String> {
+    if !keys.contains_key(&change.table) {
+        let key = primary_key(storage, &change.table)?;
+        keys.insert(change.table.clone(), key);
+    }
+    let key_columns = &keys[&change.table];
+
+    let values = match change.op {
+        ChangeOp::Delete => None,
+        ChangeOp::Insert | ChangeOp::Update => {
+            // Identifier interpolation is safe here: the name came from
+            // SQLite's own hook, quoted against exotic table names.
+            let quoted = change.table.replace('"', "\"\"");
+            storage
+                .query(
+                    &format!("SELECT * FROM \"{quoted}\" WHERE rowid = ?"),
+                    &[serde_json::json!(change.rowid)],
+                )?
+                .into_iter()
+                .next()
+        }
+    };
+
+    let rowid_key = serde_json::json!({ "rowid": change.rowid });
+    let key = match (key_columns.as_slice(), &values, &change.key) {
+        ([], _, _) => rowid_key,
+        ([(column, true)], _, _) => serde_json::Value::Object(
+            [(column.clone(), serde_json::json!(change.rowid))]
+                .into_iter()
+                .collect(),
+        ),
+        (columns, Some(values), _) => serde_json::Value::Object(
+            columns
+                .iter()
+                .map(|(column, _)| (column.clone(), values[column.as_str()].clone()))
+                .collect(),
+        ),
+        (columns, None, Some(kept)) => serde_json::Value::Object(
+            columns
+                .iter()
+                .map(|(column, _)| column.clone())
+                .zip(kept.iter().cloned())
+                .collect(),
+        ),
+        (_, None, None) => rowid_key,
+    };
+
+    Ok(serde_json::json!({
+        "table": change.table,
+        "op": change.op.as_str(),
+        "key": key,
+        "values": values,
+    }))
+}
+
+/// Appends one entry of changes to the change log, inside the call's
+/// transaction.
+fn append_entry(storage: &mut SqliteStorage, changes: &[serde_json::Value]) -> Result<(), String> {
+    let now = crate::extensions::objects::unix_now_ms();
+    let payload = serde_json::to_string(changes).map_err(|e| e.to_string())?;
+    let connection = storage.platform();
+    connection
+        .execute(CREATE_CHANGE_LOG, [])
+        .map_err(|e| e.to_string())?;
+    connection
+        .execute(
+            "INSERT INTO __actias_db_changes (payload, published_at, next_at) VALUES (?, ?, ?)",
+            rusqlite::params![payload, now, now],
+        )
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
 /// The wire codec: maps one dispatched method name and its json arguments
 /// onto [`Database`], and the typed result back to json. Method names
 /// arrive as strings because that is what a Lua handle sends.
-pub(crate) fn dispatch(
+pub(crate) async fn dispatch(
+    runtime: &ActiasRuntime,
     context: &super::PlatformContext<'_>,
     call: &super::Call,
 ) -> Result<serde_json::Value, String> {
+    let feed = super::topic::Feed {
+        log: CHANGE_LOG,
+        event: format!("database:{}", context.name),
+        subscriptions: format!("database/{}", context.name),
+    };
+    if call.method == "alarm" {
+        context.home.with_storage(|storage| {
+            storage
+                .platform()
+                .execute(CREATE_CHANGE_LOG, [])
+                .map_err(|e| e.to_string())?;
+            Ok(())
+        })?;
+        return super::topic::fan_out(runtime, context, &feed).await;
+    }
+    // The feed's dead letters answer to the same controls as a topic's.
+    if let Some(result) = super::topic::dead_letter_call(runtime, context, &feed, call).await {
+        return result;
+    }
+
+    let watch = has_listeners(runtime, context).await;
     let database = Database::open(context.home, context.name)?;
+    let database = if watch {
+        database.watching_changes()
+    } else {
+        database
+    };
+    let result = methods(&database, call)?;
+    // Armed once the call is done; the alarm runs after it has committed.
+    if database.finish_changes()? {
+        super::set_alarm(context, DATABASE_CLASS, 0)?;
+    }
+    Ok(result)
+}
+
+/// Whether this call's writes are watched. Only a vm that can fan out
+/// watches: one that cannot (local testing) would grow a log nothing ever
+/// drains. Then only while someone listens, though a check that fails
+/// watches anyway: an entry nobody takes costs a fan-out, a change a
+/// listener misses is gone.
+async fn has_listeners(runtime: &ActiasRuntime, context: &super::PlatformContext<'_>) -> bool {
+    if runtime
+        .app_data_ref::<super::topic::SubscriberLookup>()
+        .is_none()
+    {
+        return false;
+    }
+    let check = runtime
+        .app_data_ref::<ListenerCheck>()
+        .map(|check| check.0.clone());
+    match check {
+        Some(lookup) => match lookup(format!("database:{}", context.name)).await {
+            Ok(listeners) => !listeners.is_empty(),
+            Err(_) => true,
+        },
+        None => true,
+    }
+}
 
+/// The statement methods, against an opened handle.
+fn methods(database: &Database<'_>, call: &super::Call) -> Result<serde_json::Value, String> {
     match call.method.as_str() {
         "exec" => {
             let (sql, params) = statement(&call.args)?;
@@ -269,3 +561,206 @@ pub fn read_overview(storage: &mut crate::storage::SqliteStorage) -> Result<Over
         tables,
     })
 }
+
+/// What a restore rebuilds a database from: a [`dump_sql`] script, or a
+/// whole SQLite file as [`dump_file`] copies one.
+pub enum Dump {
+    Sql(String),
+    File(Vec<u8>),
+}
+
+/// The whole database as SQL: the `user_version` marker, every table's
+/// DDL and rows, then indexes, triggers and views. Reserved tables ride
+/// along, because the applied-migration rows are what keeps a restored
+/// copy from migrating twice; only SQLite's own tables stay out, save the
+/// AUTOINCREMENT counters, and so do virtual tables' shadow tables, which
+/// `CREATE VIRTUAL TABLE` makes again and the virtual table's own rows
+/// refill.
+pub fn dump_sql(storage: &mut SqliteStorage) -> Result<String, String> {
+    let connection = storage.platform();
+
+    let shadows: Vec<String> = {
+        let mut statement = connection
+            .prepare("SELECT name FROM pragma_table_list WHERE schema = 'main' AND type = 'shadow'")
+            .map_err(|e| e.to_string())?;
+        statement
+            .query_map([], |row| row.get(0))
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?
+    };
+
+    let version: i64 = connection
+        .pragma_query_value(None, "user_version", |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    let schema: Vec<(String, String, String)> = {
+        let mut statement = connection
+            .prepare(
+                "SELECT type, name, sql FROM sqlite_master \
+                 WHERE sql IS NOT NULL AND name NOT LIKE 'sqlite_%' \
+                 ORDER BY type != 'table', rowid",
+            )
+            .map_err(|e| e.to_string())?;
+        statement
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?
+    };
+
+    let mut dump = format!("PRAGMA user_version = {version};\n");
+    for (kind, name, sql) in &schema {
+        if shadows.contains(name) {
+            continue;
+        }
+        dump.push_str(sql);
+        dump.push_str(";\n");
+        if kind == "table" {
+            dump_rows(connection, name, &mut dump)?;
+        }
+    }
+
+    let sequenced: bool = connection
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE name = 'sqlite_sequence')",
+            [],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if sequenced {
+        dump.push_str("DELETE FROM sqlite_sequence;\n");
+        dump_rows(connection, "sqlite_sequence", &mut dump)?;
+    }
+
+    Ok(dump)
+}
+
+/// One table's rows as INSERT statements, appended to `dump`. Only the
+/// stored columns are written, named, so generated columns (which refuse
+/// a value) and virtual tables' hidden ones compute again on restore.
+fn dump_rows(
+    connection: &rusqlite::Connection,
+    table: &str,
+    dump: &mut String,
+) -> Result<(), String> {
+    // Identifier interpolation is safe here: the names came from
+    // sqlite_master and the table's own info, quoted against exotic ones.
+    let quote = |name: &str| format!("\"{}\"", name.replace('"', "\"\""));
+    let columns: Vec<String> = {
+        let mut statement = connection
+            .prepare("SELECT name FROM pragma_table_xinfo(?) WHERE hidden = 0 ORDER BY cid")
+            .map_err(|e| e.to_string())?;
+        statement
+            .query_map([table], |row| row.get::<_, String>(0))
+            .map_err(|e| e.to_string())?
+            .map(|name| name.map(|name| quote(&name)))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?
+    };
+    if columns.is_empty() {
+        return Ok(());
+    }
+    let quoted = quote(table);
+    let listed = columns.join(", ");
+
+    let mut statement = connection
+        .prepare(&format!("SELECT {listed} FROM {quoted}"))
+        .map_err(|e| e.to_string())?;
+    let mut rows = statement.query([]).map_err(|e| e.to_string())?;
+    while let Some(row) = rows.next().map_err(|e| e.to_string())? {
+        let values = (0..columns.len())
+            .map(|index| row.get_ref(index).map(literal))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+        dump.push_str(&format!(
+            "INSERT INTO {quoted} ({listed}) VALUES({});\n",
+            values.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// One stored value as the SQL literal that stores it again, type and
+/// all: a real keeps its decimal point, a blob its bytes.
+fn literal(value: rusqlite::types::ValueRef<'_>) -> String {
+    use rusqlite::types::ValueRef;
+
+    match value {
+        ValueRef::Null => "NULL".to_owned(),
+        ValueRef::Integer(i) => i.to_string(),
+        // SQLite reads an out-of-range literal as the infinity it was.
+        ValueRef::Real(f) if f.is_infinite() => if f > 0.0 { "9e999" } else { "-9e999" }.to_owned(),
+        ValueRef::Real(f) => format!("{f:?}"),
+        ValueRef::Text(t) => format!("'{}'", String::from_utf8_lossy(t).replace('\'', "''")),
+        ValueRef::Blob(b) => format!(
+            "X'{}'",
+            b.iter()
+                .map(|byte| format!("{byte:02X}"))
+                .collect::<String>()
+        ),
+    }
+}
+
+/// The whole file, consistent as of one read: `VACUUM INTO` a scratch
+/// copy, which works over the read-only connections reads use and folds
+/// in whatever the WAL still holds.
+pub fn dump_file(storage: &mut SqliteStorage) -> Result<Vec<u8>, String> {
+    static SCRATCH: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+    let scratch = std::env::temp_dir().join(format!(
+        "actias-dump-{}-{}.db",
+        std::process::id(),
+        SCRATCH.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+    ));
+
+    storage
+        .platform()
+        .execute("VACUUM INTO ?1", [scratch.to_string_lossy()])
+        .map_err(|e| e.to_string())?;
+    let bytes = std::fs::read(&scratch).map_err(|e| e.to_string());
+    let _ = std::fs::remove_file(&scratch);
+    bytes
+}
+
+/// Builds a restore's candidate at `file` from a dump, checked before
+/// anything is replaced: the SQL runs in one transaction under the dump
+/// guard, a raw file must be SQLite, both must fit `max_bytes` and pass
+/// SQLite's own integrity check. Leaves a WAL-mode file with its log
+/// checkpointed away, ready to ship.
+///
+/// # Errors
+/// Returns the user-safe reason the dump was refused; the candidate may
+/// be left behind for the caller to remove.
+pub fn load_dump(file: &std::path::Path, dump: &Dump, max_bytes: u64) -> Result<(), String> {
+    match dump {
+        Dump::Sql(sql) => {
+            let mut storage = SqliteStorage::open(file)?;
+            storage.set_size_limit(max_bytes)?;
+            storage.begin()?;
+            if let Err(error) = storage.exec_dump(sql) {
+                storage.rollback()?;
+                return Err(format!("The dump did not load: {error}"));
+            }
+            storage.commit()?;
+        }
+        Dump::File(bytes) => {
+            if bytes.len() as u64 > max_bytes {
+                return Err(format!(
+                    "The file is {} bytes; a database holds at most {max_bytes}.",
+                    bytes.len()
+                ));
+            }
+            std::fs::write(file, bytes).map_err(|e| e.to_string())?;
+        }
+    }
+
+    let mut storage = SqliteStorage::open(file)
+        .map_err(|error| format!("The file is not a SQLite database: {error}"))?;
+    let verdict: String = storage
+        .platform()
+        .query_row("PRAGMA quick_check", [], |row| row.get(0))
+        .map_err(|error| format!("The file is not a SQLite database: {error}"))?;
+    if verdict != "ok" {
+        return Err(format!("The restored database is damaged: {verdict}"));
+    }
+    storage.checkpoint()
+}
@@ -4,7 +4,8 @@
 //! never enters the vm: [`dispatch`] decodes the same payload the Lua
 //! `__dispatch` speaks and routes it to the class's module. The vm is
 //! entered in exactly one place, [`fire_listener`], when a platform class
-//! must run user code (a queue delivery, a cron fire).
+//! must run user code (a queue delivery, a cron fire, a topic or
+//! database subscription's delivery).
 //!
 //! Everything here rides the object substrate unchanged: the mailbox
 //! serializes calls, the dispatch guard owns the transaction, alarms and
@@ -14,6 +15,7 @@
 pub mod cron;
 pub mod database;
 pub mod queue;
+pub mod topic;
 pub mod workflow;
 
 use mlua::LuaSerdeExt;
@@ -57,6 +59,9 @@ pub(crate) struct Caller {
 /// guest runtime appears only where a listener must actually fire.
 pub(crate) struct PlatformContext<'a> {
     pub home: &'a ObjectHome,
+    /// The class the call targeted; classes sharing one implementation
+    /// (a queue and a topic subscription) tell themselves apart by it.
+    pub class: &'a str,
     /// The instance name.
     pub name: &'a str,
     /// The object's own key, seeding any alarm it arms.
@@ -76,6 +81,8 @@ pub enum PlatformRead {
     QueueEvents { since: i64 },
     /// The queue's live and dead message rows with display states.
     QueueMessages,
+    /// A topic's or database change feed's fan-out dead letters.
+    FanOutDead,
     /// A database's file size and user tables with their shapes; any
     /// object's storage answers it, user classes included.
     DatabaseOverview,
@@ -83,11 +90,17 @@ pub enum PlatformRead {
     /// authorizer script SQL runs with; how the console browses an
     /// object's storage without dispatching into its vm.
     Query { sql: String },
+    /// The whole file for a backup: schema and rows as SQL, or with `raw`
+    /// the SQLite file itself, base64-encoded.
+    Dump { raw: bool },
     /// A workflow run's derived status plus journal head facts.
     WorkflowStatus,
     /// The workflow journal after `since`, oldest first: what the CI
     /// view folds.
     WorkflowJournal { since: i64 },
+    /// A cron instance's policy, last and next fire, skipped occurrences
+    /// and recent fires, newest first.
+    CronState,
 }
 
 impl PlatformRead {
@@ -96,14 +109,26 @@ impl PlatformRead {
     /// file like any database, so it answers the overview too.
     pub fn stats_for_class(class: &str) -> Option<Self> {
         match class {
-            crate::extensions::objects::QUEUE_CLASS => Some(Self::QueueStats),
+            crate::extensions::objects::QUEUE_CLASS
+            | crate::extensions::objects::SUBSCRIPTION_CLASS => Some(Self::QueueStats),
             actias_common::classes::WORKFLOW_CLASS => Some(Self::WorkflowStatus),
             crate::extensions::objects::DATABASE_CLASS => Some(Self::DatabaseOverview),
+            crate::extensions::objects::CRON_CLASS => Some(Self::CronState),
             class if class.starts_with("__") => None,
             _ => Some(Self::DatabaseOverview),
         }
     }
 
+    /// The message listing a dashboard asks for by class name: the dead
+    /// letters for a class that fans out, the queue's rows otherwise.
+    pub fn messages_for_class(class: &str) -> Self {
+        match class {
+            crate::extensions::objects::TOPIC_CLASS
+            | crate::extensions::objects::DATABASE_CLASS => Self::FanOutDead,
+            _ => Self::QueueMessages,
+        }
+    }
+
     /// Runs the read against a file, opened read-only. Blocking SQLite io;
     /// async callers wrap it in `spawn_blocking`.
     ///
@@ -118,8 +143,23 @@ impl PlatformRead {
                 serde_json::to_value(queue::read_events(&mut storage, *since)?)
             }
             Self::QueueMessages => serde_json::to_value(queue::read_messages(&mut storage)?),
+            Self::FanOutDead => serde_json::to_value(topic::read_dead_letters(&mut storage)?),
             Self::DatabaseOverview => serde_json::to_value(database::read_overview(&mut storage)?),
             Self::Query { sql } => serde_json::to_value(storage.query(sql, &[])?),
+            Self::Dump { raw: false } => {
+                return Ok(serde_json::json!({
+                    "format": "sql",
+                    "content": database::dump_sql(&mut storage)?,
+                }));
+            }
+            Self::Dump { raw: true } => {
+                use base64::Engine;
+                return Ok(serde_json::json!({
+                    "format": "sqlite",
+                    "content": base64::engine::general_purpose::STANDARD
+                        .encode(database::dump_file(&mut storage)?),
+                }));
+            }
             Self::WorkflowStatus => {
                 let entries = workflow::read_journal_readonly(&mut storage)?;
                 let status = workflow::run_status(&entries);
@@ -140,6 +180,7 @@ impl PlatformRead {
             Self::WorkflowJournal { since } => {
                 serde_json::to_value(workflow::read_journal_readonly_from(&mut storage, *since)?)
             }
+            Self::CronState => serde_json::to_value(cron::read_state(&mut storage)?),
         };
         value.map_err(|e| e.to_string())
     }
@@ -176,14 +217,23 @@ pub(crate) async fn dispatch(
 
     let context = PlatformContext {
         home,
+        class: &call.class,
         name: &call.name,
         own_key: call.chain.last().map(String::as_str).unwrap_or_default(),
     };
 
     let result = match call.class.as_str() {
-        crate::extensions::objects::QUEUE_CLASS => queue::dispatch(runtime, &context, &call).await,
+        // A subscription is a queue fed by its topic; only the listener
+        // it fires differs.
+        crate::extensions::objects::QUEUE_CLASS
+        | crate::extensions::objects::SUBSCRIPTION_CLASS => {
+            queue::dispatch(runtime, &context, &call).await
+        }
+        crate::extensions::objects::TOPIC_CLASS => topic::dispatch(runtime, &context, &call).await,
         crate::extensions::objects::CRON_CLASS => cron::dispatch(runtime, &context, &call).await,
-        crate::extensions::objects::DATABASE_CLASS => database::dispatch(&context, &call),
+        crate::extensions::objects::DATABASE_CLASS => {
+            database::dispatch(runtime, &context, &call).await
+        }
         actias_common::classes::WORKFLOW_CLASS => {
             workflow::dispatch(runtime, &context, &call).await
         }
@@ -204,10 +254,6 @@ pub(crate) async fn fire_listener(
     event: &str,
     payload: &serde_json::Value,
 ) -> Result<(), String> {
-    let Ok(listener) = runtime.listener(event) else {
-        actias_common::tracing::warn!(event, "no listener registered for event");
-        return Err(format!("no listener registered for '{event}'"));
-    };
     let argument = match runtime.to_value(payload) {
         Ok(argument) => argument,
         Err(error) => {
@@ -215,6 +261,20 @@ pub(crate) async fn fire_listener(
             return Err(format!("event payload did not convert: {error}"));
         }
     };
+    fire_listener_with(runtime, event, argument).await
+}
+
+/// [`fire_listener`] for an argument already built in the vm, which is
+/// how batched queue delivery passes tables carrying functions.
+pub(crate) async fn fire_listener_with(
+    runtime: &ActiasRuntime,
+    event: &str,
+    argument: mlua::Value,
+) -> Result<(), String> {
+    let Ok(listener) = runtime.listener(event) else {
+        actias_common::tracing::warn!(event, "no listener registered for event");
+        return Err(format!("no listener registered for '{event}'"));
+    };
     if let Err(error) = listener.call_async::<mlua::Value>(argument).await {
         actias_common::tracing::warn!(%error, event, "event handler failed");
         return Err(error.to_string());
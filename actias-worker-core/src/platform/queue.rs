@@ -1,33 +1,66 @@
 //! The `__queue` platform class: a durable message queue whose sqlite is
 //! the message store and whose alarm loop is the delivery loop.
 //!
-//! `send` appends and arms an immediate alarm; the alarm delivers due
-//! messages to the script's `on "queue:<name>"` listener one at a time. A
-//! refused delivery retries with exponential backoff; a message that
-//! exhausts its attempts moves to the dead-letter table instead of
-//! blocking the queue, where `retry_dead`/`retry_message` can requeue it
-//! and `drop_message` discards it. Messages ride the call's transaction,
-//! ship with the file, and survive takeover like any other object row.
+//! `send` appends and arms the alarm for the earliest due message; the
+//! alarm delivers due messages to the script's `on "queue:<name>"`
+//! listener one at a time, or, for a consumer that declared
+//! `{ batch = 50, max_wait = "5s" }`, as one array whose messages each
+//! carry `ack`/`retry` and settle individually. A send may be scheduled
+//! (`{ delay = "2h" }` or `{ at = unix_ms }`), which only moves its first
+//! delivery time: a scheduled message is an ordinary row that is not due
+//! yet. A send with a `dedup_key` seen within the window enqueues
+//! nothing; a send with a `group` delivers strictly after the group's
+//! earlier messages, so a failing message holds back its own group and
+//! nothing else. A refused delivery retries with exponential backoff; a
+//! message that exhausts its attempts moves to the dead-letter table
+//! instead of blocking the queue, where `retry_dead`/`retry_message` can
+//! requeue it and `drop_message` discards it; `purge` empties both.
+//! Messages ride the call's transaction, ship with the file, and survive
+//! takeover like any other object row.
+//!
+//! Consumers outside Actias pull instead: `lease` hands out up to N due
+//! messages for a visibility timeout, during which delivery skips them,
+//! and `ack`/`nack` settle them by the receipt each lease returned, which
+//! stops working once the lease lapses. A lease that lapses unsettled
+//! counts as a failed attempt, so a puller that dies mid-batch retries
+//! and dead-letters like a refusing listener. A queue no script listens
+//! to is left to its pullers: delivery never fires a listener that is
+//! not there.
+//!
+//! The same implementation serves `__subscription`, one subscriber's
+//! copy of a topic or of a database's changes: its rows arrive from the
+//! fan-out instead of from scripts, and delivery fires `on
+//! "topic:<name>"` or `on "database:<name>"` instead.
+//!
+//! A queue's delivery policy is the node's default overlaid with what the
+//! consumer declared (`queue "jobs" { max_attempts = 10 }`). The effective
+//! policy is written into the file on every dispatch, so any read path
+//! that opens the file, a replica included, reports what delivery does.
 //!
 //! Every state change is journaled into a ring table committed with the
 //! state it describes; the detail column is json (message id, payload
 //! preview, producer, per-attempt error), which is what the dashboard's
 //! inspector renders.
 
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
 use serde::Serialize;
 
-use crate::extensions::objects::{QUEUE_CLASS, unix_now_ms};
+use crate::extensions::objects::{SUBSCRIPTION_CLASS, parse_duration_ms, unix_now_ms};
 use crate::runtime::ActiasRuntime;
 
-/// Delivery limits, worker configuration rather than constants: operators
-/// tune them the way they tune sweep timings, and tests compress them.
-#[derive(Clone)]
+/// Delivery limits. The node's configuration supplies the defaults,
+/// which operators tune the way they tune sweep timings and tests
+/// compress; a consumer's declaration overrides them per queue.
+#[derive(Clone, Debug, PartialEq, Serialize)]
 pub struct QueuePolicy {
     /// Deliveries attempted before a message dead-letters.
     pub max_attempts: i64,
-    /// First retry delay; each further attempt doubles it, capped at an
-    /// hour.
+    /// First retry delay; each further attempt doubles it.
     pub backoff_base_ms: i64,
+    /// Ceiling the doubling stops at.
+    pub max_backoff_ms: i64,
 }
 
 impl Default for QueuePolicy {
@@ -35,10 +68,34 @@ impl Default for QueuePolicy {
         Self {
             max_attempts: 5,
             backoff_base_ms: 2000,
+            max_backoff_ms: 3_600_000,
         }
     }
 }
 
+impl QueuePolicy {
+    /// These defaults with every field `declared` sets taken from it.
+    pub fn overlaid(&self, declared: &crate::proto::script_service::QueuePolicy) -> Self {
+        Self {
+            max_attempts: declared.max_attempts.unwrap_or(self.max_attempts),
+            backoff_base_ms: declared.backoff_ms.unwrap_or(self.backoff_base_ms),
+            max_backoff_ms: declared.max_backoff_ms.unwrap_or(self.max_backoff_ms),
+        }
+    }
+
+    /// The retry delay after `attempts` failed deliveries: the base,
+    /// doubled per earlier failure, never past the ceiling.
+    pub(crate) fn backoff_after(&self, attempts: i64) -> i64 {
+        let doublings = attempts.clamp(0, 32) as u32;
+        self.backoff_base_ms
+            .saturating_mul(1_i64 << doublings)
+            .min(self.max_backoff_ms)
+    }
+}
+
+/// A consumer's batching is read by the same code publish checks it with.
+pub use actias_declarations::{Batching, MAX_BATCH};
+
 /// What the dashboard and `stats` calls read; plain data by design.
 #[derive(Serialize)]
 pub struct Stats {
@@ -46,17 +103,34 @@ pub struct Stats {
     pub depth: i64,
     /// Messages due now, in delivery's hands.
     pub in_flight: i64,
+    /// Messages sent for later that have not come due yet; retries
+    /// waiting out a backoff are not counted.
+    pub scheduled: i64,
+    /// Grouped messages waiting behind an older message of their group.
+    pub blocked: i64,
+    /// Messages a pull consumer holds under an unexpired lease.
+    pub leased: i64,
+    /// Enqueue time of the oldest message that is due or retrying; a
+    /// scheduled send is not a backlog until it comes due.
     pub oldest_pending: Option<i64>,
     pub dead_letters: i64,
+    /// The delivery policy last applied; absent in a file no dispatch
+    /// has touched since policies were recorded.
+    pub policy: Option<QueuePolicy>,
 }
 
 /// One message row as the inspector's table shows it.
 #[derive(Serialize)]
 pub struct Message {
     pub id: i64,
-    /// pending, in-flight or dead; delivered rows live in the journal.
+    /// scheduled, pending, in-flight, leased, blocked or dead; delivered
+    /// rows live in the journal.
     pub state: String,
     pub attempts: i64,
+    /// Attempts the policy allows before dead-lettering, so a row reads
+    /// as "2 of 10"; absent when the file records no policy.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_attempts: Option<i64>,
     pub preview: String,
     /// The whole payload text, for the inspector's drawer; queues are
     /// quota-small by design, so rows carry it whole.
@@ -69,40 +143,96 @@ pub struct Message {
     /// When it dead-lettered; absent for live rows.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub died_ms: Option<i64>,
+    /// The send's dedup key, while it is a live row.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dedup_key: Option<String>,
+    /// The ordering group the message belongs to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// When a pull consumer's lease on it lapses, while one holds it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub leased_until: Option<i64>,
 }
 
 /// Table names, for existence probes; each must match its DDL below.
 const MESSAGES_TABLE: &str = "__actias_queue_messages";
 const DEAD_TABLE: &str = "__actias_queue_dead";
 const EVENTS_TABLE: &str = "__actias_queue_events";
+const POLICY_TABLE: &str = "__actias_queue_policy";
 
 /// The queue schema's version, stamped in the file's version cell.
 /// Version 1 predates AUTOINCREMENT ids: rowids could reuse after a
 /// delete, so one journal id named several generations of messages.
 /// Version 2 rebuilds the table so an id names exactly one message for
-/// the file's whole life.
-const SCHEMA_VERSION: i64 = 2;
+/// the file's whole life. Version 3 adds the dedup and group keys, and
+/// the dedup window table. Version 4 adds the pull consumers' lease.
+/// Version 5 indexes groups, which every delivery query consults.
+const SCHEMA_VERSION: i64 = 5;
 
 /// Messages awaiting delivery. Id ordering is FIFO among live rows,
 /// which is the only ordering delivery observes; AUTOINCREMENT keeps
 /// every id unique forever, which is what makes journal entries and the
-/// retry/drop controls unambiguous.
+/// retry/drop controls unambiguous. Rows sharing a `group_key` deliver
+/// strictly in id order: only a group's oldest row is ever deliverable.
+/// A leased row has `next_at` pushed to its `leased_until`, so delivery
+/// and other pullers pass it by until the lease lapses.
 const CREATE_MESSAGES: &str = "CREATE TABLE IF NOT EXISTS __actias_queue_messages (
         id INTEGER PRIMARY KEY AUTOINCREMENT,
         payload TEXT NOT NULL,
         attempts INTEGER NOT NULL DEFAULT 0,
         next_at INTEGER NOT NULL,
-        enqueued_at INTEGER NOT NULL
+        enqueued_at INTEGER NOT NULL,
+        dedup_key TEXT,
+        group_key TEXT,
+        leased_until INTEGER
     )";
 
+/// What finds a group's head without scanning the table: [`DELIVERABLE`]
+/// asks for it once per candidate row.
+const CREATE_GROUP_INDEX: &str = "CREATE INDEX IF NOT EXISTS __actias_queue_messages_group \
+     ON __actias_queue_messages (group_key, id)";
+
 /// Messages that exhausted their attempts; kept for inspection and manual
-/// requeueing, never redelivered by the platform on its own.
+/// requeueing, never redelivered by the platform on its own. The id and
+/// group ride along so a requeued message takes its old place in its
+/// group's order.
 const CREATE_DEAD: &str = "CREATE TABLE IF NOT EXISTS __actias_queue_dead (
         id INTEGER,
         payload TEXT,
         attempts INTEGER,
         enqueued_at INTEGER,
-        died_at INTEGER
+        died_at INTEGER,
+        group_key TEXT
+    )";
+
+/// Dedup keys seen within the window, with the message each one let
+/// through. Rows outlive their messages on purpose: a duplicate sent
+/// just after delivery is still a duplicate.
+const CREATE_DEDUP: &str = "CREATE TABLE IF NOT EXISTS __actias_queue_dedup (
+        dedup_key TEXT PRIMARY KEY,
+        message_id INTEGER NOT NULL,
+        expires_at INTEGER NOT NULL
+    )";
+
+/// How long a dedup key suppresses repeats of itself. Long enough to
+/// cover a client's retries of one request, short enough that the table
+/// stays small under steady traffic.
+const DEDUP_WINDOW_MS: i64 = 10 * 60 * 1000;
+
+/// The rows delivery may take: ungrouped ones, and the oldest row of each
+/// group. A group's head that is failing or scheduled holds back its
+/// group and nothing else.
+const DELIVERABLE: &str = "(group_key IS NULL OR id = \
+     (SELECT MIN(head.id) FROM __actias_queue_messages AS head \
+      WHERE head.group_key = __actias_queue_messages.group_key))";
+
+/// The effective delivery policy, one row; rewritten whenever the
+/// consumer's declaration or the node's defaults change it.
+const CREATE_POLICY: &str = "CREATE TABLE IF NOT EXISTS __actias_queue_policy (
+        id INTEGER PRIMARY KEY CHECK (id = 1),
+        max_attempts INTEGER NOT NULL,
+        backoff_ms INTEGER NOT NULL,
+        max_backoff_ms INTEGER NOT NULL
     )";
 
 /// How many due messages one alarm firing works through before re-arming.
@@ -178,22 +308,69 @@ pub(crate) async fn dispatch(
                 .execute(CREATE_DEAD, [])
                 .map_err(|e| e.to_string())?;
         } else {
-            // v1 -> v2: rebuild messages so ids never reuse. Rows carry
-            // over with their ids, and AUTOINCREMENT resumes past the
-            // highest one; runs inside the call's transaction like any
-            // other platform write.
-            connection
-                .execute_batch(&format!(
-                    "ALTER TABLE __actias_queue_messages RENAME TO __actias_queue_messages_v1;
-                     {CREATE_MESSAGES};
-                     INSERT INTO __actias_queue_messages SELECT * FROM __actias_queue_messages_v1;
-                     DROP TABLE __actias_queue_messages_v1;"
-                ))
-                .map_err(|e| e.to_string())?;
+            if version < 2 {
+                // v1 -> v2: rebuild messages so ids never reuse. Rows carry
+                // over with their ids, and AUTOINCREMENT resumes past the
+                // highest one; runs inside the call's transaction like any
+                // other platform write. The rebuilt table already has the
+                // v3 columns.
+                connection
+                    .execute_batch(&format!(
+                        "ALTER TABLE __actias_queue_messages RENAME TO __actias_queue_messages_v1;
+                         {CREATE_MESSAGES};
+                         INSERT INTO __actias_queue_messages \
+                             (id, payload, attempts, next_at, enqueued_at) \
+                             SELECT id, payload, attempts, next_at, enqueued_at \
+                             FROM __actias_queue_messages_v1;
+                         DROP TABLE __actias_queue_messages_v1;"
+                    ))
+                    .map_err(|e| e.to_string())?;
+            } else {
+                if version < 3 {
+                    // v2 -> v3: existing rows have no keys, which is what
+                    // ungrouped, undeduplicated sends look like.
+                    connection
+                        .execute_batch(
+                            "ALTER TABLE __actias_queue_messages ADD COLUMN dedup_key TEXT;
+                             ALTER TABLE __actias_queue_messages ADD COLUMN group_key TEXT;",
+                        )
+                        .map_err(|e| e.to_string())?;
+                }
+                if version < 4 {
+                    // v3 -> v4: nothing was leased before pulling existed.
+                    connection
+                        .execute(
+                            "ALTER TABLE __actias_queue_messages ADD COLUMN leased_until INTEGER",
+                            [],
+                        )
+                        .map_err(|e| e.to_string())?;
+                }
+            }
+            if version < 3 {
+                connection
+                    .execute(
+                        "ALTER TABLE __actias_queue_dead ADD COLUMN group_key TEXT",
+                        [],
+                    )
+                    .map_err(|e| e.to_string())?;
+            }
         }
+        connection
+            .execute(CREATE_DEDUP, [])
+            .map_err(|e| e.to_string())?;
+        connection
+            .execute(CREATE_GROUP_INDEX, [])
+            .map_err(|e| e.to_string())?;
         storage.set_schema_version(SCHEMA_VERSION)
     })?;
 
+    // The consumer's declaration rides the revision this vm runs; the
+    // file records the result, so reads and replicas see it too.
+    let policy = effective_policy(runtime, context);
+    context
+        .home
+        .with_storage(|storage| store_policy(storage, &policy))?;
+
     match call.method.as_str() {
         "send" => send(
             context,
@@ -201,9 +378,10 @@ pub(crate) async fn dispatch(
                 .first()
                 .cloned()
                 .unwrap_or(serde_json::Value::Null),
+            call.args.get(1),
             call.caller.as_ref(),
         ),
-        "alarm" => deliver(runtime, context).await,
+        "alarm" => deliver(runtime, context, &policy).await,
         "stats" => stats(context),
         "events" => events(
             context,
@@ -213,15 +391,89 @@ pub(crate) async fn dispatch(
                 .unwrap_or(0),
         ),
         "messages" => messages(context),
+        "lease" => lease(context, &policy, call.args.first(), call.args.get(1)),
+        "ack" => ack(context, &policy, &receipts_argument(call)?),
+        "nack" => nack(
+            context,
+            &policy,
+            &receipts_argument(call)?,
+            call.args.get(1).and_then(|value| value.as_str()),
+        ),
         "retry_dead" => retry_dead(context, None),
         "retry_message" => retry_dead(context, require_id(call)?.into()),
         "drop_message" => drop_message(context, require_id(call)?),
+        "purge" => purge(context),
         other => Err(format!(
-            "Object class '{QUEUE_CLASS}' has no method '{other}'."
+            "Object class '{}' has no method '{other}'.",
+            context.class
         )),
     }
 }
 
+/// The node's defaults overlaid with this queue's declared policy, when
+/// the revision consuming it declared one.
+fn effective_policy(runtime: &ActiasRuntime, context: &super::PlatformContext<'_>) -> QueuePolicy {
+    let defaults = context.home.queue_policy();
+    runtime
+        .app_data_ref::<std::sync::Arc<crate::runtime::PreparedRevision>>()
+        .and_then(|prepared| prepared.queue_policy(context.name).cloned())
+        .map(|declared| defaults.overlaid(&declared))
+        .unwrap_or_else(|| defaults.clone())
+}
+
+/// Records `policy` in the file unless it already says exactly that, so
+/// the common call writes nothing.
+fn store_policy(
+    storage: &mut crate::storage::SqliteStorage,
+    policy: &QueuePolicy,
+) -> Result<(), String> {
+    if storage.table_exists(POLICY_TABLE)? && read_policy(storage)?.as_ref() == Some(policy) {
+        return Ok(());
+    }
+    let connection = storage.platform();
+    connection
+        .execute(CREATE_POLICY, [])
+        .map_err(|e| e.to_string())?;
+    connection
+        .execute(
+            "INSERT OR REPLACE INTO __actias_queue_policy \
+             (id, max_attempts, backoff_ms, max_backoff_ms) VALUES (1, ?, ?, ?)",
+            rusqlite::params![
+                policy.max_attempts,
+                policy.backoff_base_ms,
+                policy.max_backoff_ms
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// The policy the file records; [`None`] before any dispatch stored one,
+/// probed rather than created so read-only opens work.
+pub fn read_policy(
+    storage: &mut crate::storage::SqliteStorage,
+) -> Result<Option<QueuePolicy>, String> {
+    if !storage.table_exists(POLICY_TABLE)? {
+        return Ok(None);
+    }
+    let row = storage.platform().query_row(
+        "SELECT max_attempts, backoff_ms, max_backoff_ms FROM __actias_queue_policy WHERE id = 1",
+        [],
+        |row| {
+            Ok(QueuePolicy {
+                max_attempts: row.get(0)?,
+                backoff_base_ms: row.get(1)?,
+                max_backoff_ms: row.get(2)?,
+            })
+        },
+    );
+    match row {
+        Ok(policy) => Ok(Some(policy)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(error) => Err(error.to_string()),
+    }
+}
+
 /// The message id argument the retry/drop controls take.
 fn require_id(call: &super::Call) -> Result<i64, String> {
     call.args
@@ -230,25 +482,155 @@ fn require_id(call: &super::Call) -> Result<i64, String> {
         .ok_or_else(|| "The message id must be a number.".to_owned())
 }
 
-/// Appends one message and arms an immediate delivery alarm. The journal
-/// row carries the producer when the router knew the caller.
+/// What a send's options table asks for; every key is optional.
+struct SendOptions {
+    /// When the message first comes due.
+    due: i64,
+    /// Suppresses repeats of the same key within [`DEDUP_WINDOW_MS`].
+    dedup_key: Option<String>,
+    /// Orders the message behind earlier ones with the same group.
+    group: Option<String>,
+}
+
+/// Furthest ahead a send may be scheduled; anything past it is almost
+/// certainly a unit mistake (seconds for milliseconds), and refusing it
+/// keeps `now + delay` far from overflowing.
+const MAX_SEND_DELAY_MS: i64 = 365 * 24 * 60 * 60 * 1000;
+
+/// Reads a send's options: `delay` (a duration or seconds, at most a
+/// year) or `at` (unix milliseconds; a past time means now) schedule it, `dedup_key` makes
+/// it idempotent within the window, and `group` orders it. Unknown keys
+/// are refused so a typo never silently sends unkeyed.
+fn send_options(options: Option<&serde_json::Value>, now: i64) -> Result<SendOptions, String> {
+    let mut parsed = SendOptions {
+        due: now,
+        dedup_key: None,
+        group: None,
+    };
+    let Some(options) = options.filter(|options| !options.is_null()) else {
+        return Ok(parsed);
+    };
+    let options = options
+        .as_object()
+        .ok_or_else(|| "Send options must be a table.".to_owned())?;
+    if let Some(other) = options
+        .keys()
+        .find(|key| !matches!(key.as_str(), "delay" | "at" | "dedup_key" | "group"))
+    {
+        return Err(format!(
+            "Unknown send option '{other}'; expected delay, at, dedup_key or group."
+        ));
+    }
+
+    parsed.due = match (options.get("delay"), options.get("at")) {
+        (Some(_), Some(_)) => return Err("A send takes delay or at, not both.".to_owned()),
+        (Some(delay), None) => {
+            let delay_ms = match delay {
+                serde_json::Value::String(text) => parse_duration_ms(text)?,
+                serde_json::Value::Number(seconds) => seconds
+                    .as_f64()
+                    .map(|seconds| (seconds * 1000.0) as i64)
+                    .ok_or_else(|| "The send delay is not a duration.".to_owned())?,
+                _ => return Err("The send delay is not a duration.".to_owned()),
+            };
+            now.saturating_add(delay_ms.max(0))
+        }
+        (None, Some(at)) => at
+            .as_f64()
+            .map(|at| (at as i64).max(now))
+            .ok_or_else(|| "The send time 'at' must be unix milliseconds.".to_owned())?,
+        (None, None) => now,
+    };
+    if parsed.due.saturating_sub(now) > MAX_SEND_DELAY_MS {
+        return Err("A send can be scheduled at most a year ahead.".to_owned());
+    }
+
+    // Keys are strings; numbers are accepted as their text, since ids are
+    // often numeric and `tostring` at every call site is noise.
+    let key = |field: &str| -> Result<Option<String>, String> {
+        match options.get(field) {
+            None | Some(serde_json::Value::Null) => Ok(None),
+            Some(serde_json::Value::String(text)) if !text.is_empty() => Ok(Some(text.clone())),
+            Some(serde_json::Value::Number(number)) => Ok(Some(number.to_string())),
+            Some(_) => Err(format!(
+                "The send option '{field}' must be a non-empty string."
+            )),
+        }
+    };
+    parsed.dedup_key = key("dedup_key")?;
+    parsed.group = key("group")?;
+    Ok(parsed)
+}
+
+/// Appends one message, due now or when its options schedule it, and
+/// arms the alarm for the earliest due message. The journal row carries
+/// the producer when the router knew the caller.
+///
+/// A send whose dedup key was seen within the window enqueues nothing
+/// and returns `false`; the journal records the suppressed duplicate
+/// against the message that got through.
 fn send(
     context: &super::PlatformContext<'_>,
     payload: serde_json::Value,
+    options: Option<&serde_json::Value>,
     caller: Option<&super::Caller>,
 ) -> Result<serde_json::Value, String> {
     let text = serde_json::to_string(&payload).map_err(|e| e.to_string())?;
     let now = unix_now_ms();
+    let options = send_options(options, now)?;
 
-    context.home.with_storage(|storage| {
+    let sent = context.home.with_storage(|storage| {
         let connection = storage.platform();
+
+        if let Some(dedup_key) = &options.dedup_key {
+            connection
+                .execute(
+                    "DELETE FROM __actias_queue_dedup WHERE expires_at <= ?",
+                    rusqlite::params![now],
+                )
+                .map_err(|e| e.to_string())?;
+            let original = connection.query_row(
+                "SELECT message_id FROM __actias_queue_dedup WHERE dedup_key = ?",
+                rusqlite::params![dedup_key],
+                |row| row.get::<_, i64>(0),
+            );
+            let original = match original {
+                Ok(id) => Some(id),
+                Err(rusqlite::Error::QueryReturnedNoRows) => None,
+                Err(error) => return Err(error.to_string()),
+            };
+            if let Some(original) = original {
+                record_event(
+                    connection,
+                    "deduplicated",
+                    &serde_json::json!({
+                        "id": original,
+                        "dedup_key": dedup_key,
+                        "producer_script": caller.map(|c| c.script.as_str()),
+                        "producer_revision": caller.map(|c| c.revision.as_str()),
+                    }),
+                )?;
+                return Ok(false);
+            }
+        }
+
         connection
             .execute(
-                "INSERT INTO __actias_queue_messages (payload, next_at, enqueued_at) VALUES (?, ?, ?)",
-                rusqlite::params![text, now, now],
+                "INSERT INTO __actias_queue_messages \
+                 (payload, next_at, enqueued_at, dedup_key, group_key) VALUES (?, ?, ?, ?, ?)",
+                rusqlite::params![text, options.due, now, options.dedup_key, options.group],
             )
             .map_err(|e| e.to_string())?;
         let id = connection.last_insert_rowid();
+        if let Some(dedup_key) = &options.dedup_key {
+            connection
+                .execute(
+                    "INSERT INTO __actias_queue_dedup (dedup_key, message_id, expires_at) \
+                     VALUES (?, ?, ?)",
+                    rusqlite::params![dedup_key, id, now + DEDUP_WINDOW_MS],
+                )
+                .map_err(|e| e.to_string())?;
+        }
         record_event(
             connection,
             "enqueued",
@@ -258,13 +640,78 @@ fn send(
                 "size": text.len(),
                 "producer_script": caller.map(|c| c.script.as_str()),
                 "producer_revision": caller.map(|c| c.revision.as_str()),
+                // Only scheduled sends say when; the rest are due at once.
+                "due_ms": (options.due > now).then_some(options.due),
+                "dedup_key": options.dedup_key,
+                "group": options.group,
             }),
         )?;
-        Ok(())
+        Ok(true)
+    })?;
+
+    if sent {
+        arm_for_earliest(context)?;
+    }
+    Ok(serde_json::Value::Bool(sent))
+}
+
+/// Arms the alarm for the earliest deliverable message, or leaves it be
+/// when there is none. Setting replaces, so a far-off send never pushes
+/// an earlier delivery back. Rows queued behind their group's head do
+/// not count: they cannot deliver until the head settles, and arming for
+/// them would spin the alarm on a blocked group.
+fn arm_for_earliest(context: &super::PlatformContext<'_>) -> Result<(), String> {
+    let earliest: Option<i64> = context.home.with_storage(|storage| {
+        storage
+            .platform()
+            .query_row(
+                &format!("SELECT MIN(next_at) FROM __actias_queue_messages WHERE {DELIVERABLE}"),
+                [],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())
+    })?;
+
+    if let Some(at) = earliest {
+        super::set_alarm(context, context.class, at - unix_now_ms())?;
+    }
+    Ok(())
+}
+
+/// Arms the alarm for the earliest lease to lapse, so an unacknowledged
+/// message comes back on time even when no listener ever wakes the queue.
+fn arm_for_lapse(context: &super::PlatformContext<'_>) -> Result<(), String> {
+    let earliest: Option<i64> = context.home.with_storage(|storage| {
+        storage
+            .platform()
+            .query_row(
+                "SELECT MIN(leased_until) FROM __actias_queue_messages",
+                [],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())
     })?;
 
-    super::set_alarm(context, QUEUE_CLASS, 0)?;
-    Ok(serde_json::Value::Bool(true))
+    if let Some(at) = earliest {
+        super::set_alarm(context, context.class, at - unix_now_ms())?;
+    }
+    Ok(())
+}
+
+/// The listener delivery fires: `queue:<name>` for a queue; for a
+/// subscription, whose storage is a queue's, `topic:<topic>` when it is
+/// named `<topic>/<subscriber>` and `database:<name>` when it is named
+/// `database/<name>/<subscriber>`. Topic names never hold a '/', so the
+/// two cannot collide.
+fn listener_event(context: &super::PlatformContext<'_>) -> String {
+    if context.class != SUBSCRIPTION_CLASS {
+        return format!("queue:{}", context.name);
+    }
+    match context.name.split('/').collect::<Vec<_>>().as_slice() {
+        ["database", database, _] => format!("database:{database}"),
+        [topic, ..] => format!("topic:{topic}"),
+        [] => "topic:".to_owned(),
+    }
 }
 
 /// One due message as delivery reads it.
@@ -272,135 +719,517 @@ struct Due {
     id: i64,
     payload: String,
     attempts: i64,
+    /// When it came due, which is when a batch's wait starts counting.
+    due_at: i64,
+    /// The ordering group, for the journal.
+    group: Option<String>,
 }
 
 /// Delivers due messages to the `on "queue:<name>"` listener, applying
 /// the per-message verdict, then re-arms for the earliest remaining
 /// message. The storage borrow is never held across the listener await.
+///
+/// A consumer that declared batching gets one call with an array of due
+/// messages instead; see [`deliver_batch`].
 async fn deliver(
     runtime: &ActiasRuntime,
     context: &super::PlatformContext<'_>,
+    policy: &QueuePolicy,
 ) -> Result<serde_json::Value, String> {
-    let policy = context.home.queue_policy();
-    let event = format!("queue:{}", context.name);
+    let event = listener_event(context);
 
-    let due = context.home.with_storage(|storage| {
+    context
+        .home
+        .with_storage(|storage| expire_leases(storage.platform(), policy))?;
+
+    // Nobody here listens: the queue is its pullers'. Arming for due
+    // messages would only spin the alarm on rows no listener will take,
+    // so the only wake left to schedule is the next lease lapse.
+    if runtime.listener(&event).is_err() {
+        arm_for_lapse(context)?;
+        return Ok(serde_json::Value::Null);
+    }
+
+    if let Some(batching) = runtime.batching(&event) {
+        return deliver_batch(runtime, context, policy, &event, batching).await;
+    }
+
+    for message in due_messages(context, DELIVERY_BATCH)? {
+        // A payload that no longer parses is corrupt storage, an expected
+        // input: it delivers as null rather than wedging the queue.
+        let payload = serde_json::from_str(&message.payload).unwrap_or(serde_json::Value::Null);
+        let verdict = super::fire_listener(runtime, &event, &payload).await;
+
+        context
+            .home
+            .with_storage(|storage| settle(storage.platform(), policy, &message, &verdict))?;
+    }
+
+    arm_for_earliest(context)?;
+    Ok(serde_json::Value::Null)
+}
+
+/// Up to `limit` due messages, oldest first, at most one per group.
+fn due_messages(context: &super::PlatformContext<'_>, limit: i64) -> Result<Vec<Due>, String> {
+    context.home.with_storage(|storage| {
         let mut statement = storage
             .platform()
-            .prepare(
-                "SELECT id, payload, attempts FROM __actias_queue_messages \
-                 WHERE next_at <= ? ORDER BY id LIMIT ?",
-            )
+            .prepare(&format!(
+                "SELECT id, payload, attempts, next_at, group_key FROM __actias_queue_messages \
+                 WHERE next_at <= ? AND {DELIVERABLE} ORDER BY id LIMIT ?"
+            ))
             .map_err(|e| e.to_string())?;
         let due = statement
-            .query_map(rusqlite::params![unix_now_ms(), DELIVERY_BATCH], |row| {
+            .query_map(rusqlite::params![unix_now_ms(), limit], |row| {
                 Ok(Due {
                     id: row.get(0)?,
                     payload: row.get(1)?,
                     attempts: row.get(2)?,
+                    due_at: row.get(3)?,
+                    group: row.get(4)?,
                 })
             })
             .map_err(|e| e.to_string())?
             .collect::<Result<Vec<_>, _>>()
             .map_err(|e| e.to_string())?;
         Ok(due)
-    })?;
+    })
+}
 
-    for message in due {
-        // A payload that no longer parses is corrupt storage, an expected
-        // input: it delivers as null rather than wedging the queue.
-        let payload = serde_json::from_str(&message.payload).unwrap_or(serde_json::Value::Null);
-        let verdict = super::fire_listener(runtime, &event, &payload).await;
-        let attempt = message.attempts + 1;
+/// Applies one message's verdict: delete it on success, otherwise
+/// dead-letter it once its attempts are spent or schedule its retry on
+/// the policy's backoff. Each message keeps its own attempt count, so a
+/// batch that fails one message leaves its neighbours' counts alone.
+fn settle(
+    connection: &rusqlite::Connection,
+    policy: &QueuePolicy,
+    message: &Due,
+    verdict: &Result<(), String>,
+) -> Result<(), String> {
+    let attempt = message.attempts + 1;
+    match verdict {
+        Ok(()) => {
+            connection
+                .execute(
+                    "DELETE FROM __actias_queue_messages WHERE id = ?",
+                    rusqlite::params![message.id],
+                )
+                .map_err(|e| e.to_string())?;
+            record_event(
+                connection,
+                "delivered",
+                &serde_json::json!({
+                    "id": message.id,
+                    "attempt": attempt,
+                    "group": message.group,
+                }),
+            )?;
+        }
+        Err(error) if attempt >= policy.max_attempts => {
+            connection
+                .execute(
+                    "INSERT INTO __actias_queue_dead \
+                     (id, payload, attempts, enqueued_at, died_at, group_key) \
+                     SELECT id, payload, attempts + 1, enqueued_at, ?, group_key \
+                     FROM __actias_queue_messages WHERE id = ?",
+                    rusqlite::params![unix_now_ms(), message.id],
+                )
+                .map_err(|e| e.to_string())?;
+            connection
+                .execute(
+                    "DELETE FROM __actias_queue_messages WHERE id = ?",
+                    rusqlite::params![message.id],
+                )
+                .map_err(|e| e.to_string())?;
+            record_event(
+                connection,
+                "dead-lettered",
+                &serde_json::json!({
+                    "id": message.id,
+                    "attempt": attempt,
+                    "error": error,
+                    "group": message.group,
+                }),
+            )?;
+        }
+        Err(error) => {
+            let next_ms = unix_now_ms() + policy.backoff_after(message.attempts);
+            connection
+                .execute(
+                    "UPDATE __actias_queue_messages \
+                     SET attempts = attempts + 1, next_at = ?, leased_until = NULL WHERE id = ?",
+                    rusqlite::params![next_ms, message.id],
+                )
+                .map_err(|e| e.to_string())?;
+            record_event(
+                connection,
+                "retried",
+                &serde_json::json!({
+                    "id": message.id,
+                    "attempt": attempt,
+                    "error": error,
+                    "next_ms": next_ms,
+                    "group": message.group,
+                }),
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Batched delivery: one listener call receives an array of up to
+/// `batching.size` due messages, each a table of `id`, `body` and
+/// `attempt` with `ack()` and `retry(reason)` controls.
+///
+/// A short batch waits until its oldest message has been due for
+/// `max_wait`, re-arming for that moment, so a trickle still flows. After
+/// the call each message settles on its own: an explicit mark wins,
+/// otherwise the call's outcome decides, so a handler that raises
+/// without marking anything retries the whole batch, while one that
+/// marks a single bad message retries only that one.
+async fn deliver_batch(
+    runtime: &ActiasRuntime,
+    context: &super::PlatformContext<'_>,
+    policy: &QueuePolicy,
+    event: &str,
+    batching: Batching,
+) -> Result<serde_json::Value, String> {
+    let due = due_messages(context, batching.size)?;
+
+    if let Some(oldest) = due.iter().map(|message| message.due_at).min()
+        && (due.len() as i64) < batching.size
+    {
+        let send_at = oldest + batching.max_wait_ms;
+        let now = unix_now_ms();
+        if send_at > now {
+            super::set_alarm(context, context.class, send_at - now)?;
+            return Ok(serde_json::Value::Null);
+        }
+    }
+
+    if !due.is_empty() {
+        let marks = Arc::new(Mutex::new(HashMap::<i64, Result<(), String>>::new()));
+        let verdict = match batch_argument(runtime, &due, &marks) {
+            Ok(argument) => super::fire_listener_with(runtime, event, argument).await,
+            Err(error) => {
+                actias_common::tracing::warn!(%error, event, "batch did not convert");
+                Err(format!("batch did not convert: {error}"))
+            }
+        };
 
+        let marks = std::mem::take(&mut *marks.lock().expect("marks lock poisoned"));
         context.home.with_storage(|storage| {
-            let connection = storage.platform();
-            match &verdict {
-                Ok(()) => {
-                    connection
-                        .execute(
-                            "DELETE FROM __actias_queue_messages WHERE id = ?",
-                            rusqlite::params![message.id],
-                        )
-                        .map_err(|e| e.to_string())?;
-                    record_event(
-                        connection,
-                        "delivered",
-                        &serde_json::json!({ "id": message.id, "attempt": attempt }),
-                    )?;
-                }
-                Err(error) if attempt >= policy.max_attempts => {
-                    connection
-                        .execute(
-                            "INSERT INTO __actias_queue_dead \
-                             SELECT id, payload, attempts + 1, enqueued_at, ? \
-                             FROM __actias_queue_messages WHERE id = ?",
-                            rusqlite::params![unix_now_ms(), message.id],
-                        )
-                        .map_err(|e| e.to_string())?;
-                    connection
-                        .execute(
-                            "DELETE FROM __actias_queue_messages WHERE id = ?",
-                            rusqlite::params![message.id],
-                        )
-                        .map_err(|e| e.to_string())?;
-                    record_event(
-                        connection,
-                        "dead-lettered",
-                        &serde_json::json!({
-                            "id": message.id,
-                            "attempt": attempt,
-                            "error": error,
-                        }),
-                    )?;
-                }
-                Err(error) => {
-                    let backoff = (policy.backoff_base_ms << message.attempts).min(3_600_000);
-                    let next_ms = unix_now_ms() + backoff;
-                    connection
-                        .execute(
-                            "UPDATE __actias_queue_messages \
-                             SET attempts = attempts + 1, next_at = ? WHERE id = ?",
-                            rusqlite::params![next_ms, message.id],
-                        )
-                        .map_err(|e| e.to_string())?;
-                    record_event(
-                        connection,
-                        "retried",
-                        &serde_json::json!({
-                            "id": message.id,
-                            "attempt": attempt,
-                            "error": error,
-                            "next_ms": next_ms,
-                        }),
-                    )?;
-                }
+            for message in &due {
+                let outcome = marks.get(&message.id).unwrap_or(&verdict);
+                settle(storage.platform(), policy, message, outcome)?;
             }
             Ok(())
         })?;
     }
 
-    let earliest: Option<i64> = context.home.with_storage(|storage| {
-        storage
-            .platform()
-            .query_row(
-                "SELECT MIN(next_at) FROM __actias_queue_messages",
-                [],
-                |row| row.get(0),
-            )
-            .map_err(|e| e.to_string())
+    arm_for_earliest(context)?;
+    Ok(serde_json::Value::Null)
+}
+
+/// The array a batched listener receives. The controls write into
+/// `marks`, keyed by message id; the last mark for a message wins.
+fn batch_argument(
+    runtime: &ActiasRuntime,
+    due: &[Due],
+    marks: &Arc<Mutex<HashMap<i64, Result<(), String>>>>,
+) -> mlua::Result<mlua::Value> {
+    let batch = runtime.create_table()?;
+    for message in due {
+        let payload: serde_json::Value =
+            serde_json::from_str(&message.payload).unwrap_or(serde_json::Value::Null);
+        let entry = runtime.create_table()?;
+        entry.set("id", message.id)?;
+        entry.set("body", runtime.to_value(&payload)?)?;
+        entry.set("attempt", message.attempts + 1)?;
+
+        let id = message.id;
+        let acked = marks.clone();
+        entry.set(
+            "ack",
+            runtime.create_function(move |_, _: mlua::MultiValue| {
+                acked
+                    .lock()
+                    .expect("marks lock poisoned")
+                    .insert(id, Ok(()));
+                Ok(())
+            })?,
+        )?;
+
+        // `message:retry("why")` and `message.retry("why")` both work:
+        // the reason is the first string argument, whichever slot it is.
+        let retried = marks.clone();
+        entry.set(
+            "retry",
+            runtime.create_function(move |_, args: mlua::MultiValue| {
+                let reason = args
+                    .iter()
+                    .find_map(|arg| arg.as_string().and_then(|s| s.to_str().ok()))
+                    .map(|reason| reason.to_string())
+                    .unwrap_or_else(|| "retry requested".to_owned());
+                retried
+                    .lock()
+                    .expect("marks lock poisoned")
+                    .insert(id, Err(reason));
+                Ok(())
+            })?,
+        )?;
+
+        batch.push(entry)?;
+    }
+    Ok(mlua::Value::Table(batch))
+}
+
+/// How long a lease hides its messages when the puller names no
+/// visibility timeout.
+const DEFAULT_VISIBILITY_MS: i64 = 30_000;
+
+/// Longest visibility timeout a lease may ask for; a puller that needs
+/// longer should ack in parts rather than sit on a message for a day.
+const MAX_VISIBILITY_MS: i64 = 12 * 60 * 60 * 1000;
+
+/// What settles one lease: the message id and the `leased_until` that
+/// lease set, written `<id>:<leased_until>`. A message leased again gets
+/// a later `leased_until`, so a puller whose lease lapsed holds a stale
+/// receipt and can never settle the next consumer's lease.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Receipt {
+    id: i64,
+    leased_until: i64,
+}
+
+impl Receipt {
+    fn parse(text: &str) -> Option<Self> {
+        let (id, leased_until) = text.split_once(':')?;
+        Some(Self {
+            id: id.parse().ok()?,
+            leased_until: leased_until.parse().ok()?,
+        })
+    }
+
+    fn to_text(self) -> String {
+        format!("{}:{}", self.id, self.leased_until)
+    }
+}
+
+/// The receipts `ack`/`nack` take: one receipt or an array of them.
+fn receipts_argument(call: &super::Call) -> Result<Vec<Receipt>, String> {
+    let invalid = || "Leases settle by receipt: a receipt string or an array of them.".to_owned();
+    let receipt = |value: &serde_json::Value| value.as_str().and_then(Receipt::parse);
+    match call.args.first() {
+        Some(serde_json::Value::Array(receipts)) => receipts
+            .iter()
+            .map(|value| receipt(value).ok_or_else(invalid))
+            .collect(),
+        Some(value) => receipt(value)
+            .map(|receipt| vec![receipt])
+            .ok_or_else(invalid),
+        None => Err(invalid()),
+    }
+}
+
+/// Leased rows as settling reads them: those whose lease lapsed, or
+/// those a puller's receipts still name, which excludes any that lapsed.
+fn leased_rows(
+    connection: &rusqlite::Connection,
+    receipts: Option<&[Receipt]>,
+) -> Result<Vec<Due>, String> {
+    let now = unix_now_ms();
+    let (filter, params) = match receipts {
+        Some(receipts) => (
+            format!(
+                "leased_until > ? AND ({})",
+                std::iter::repeat_n("(id = ? AND leased_until = ?)", receipts.len())
+                    .collect::<Vec<_>>()
+                    .join(" OR ")
+            ),
+            std::iter::once(now)
+                .chain(
+                    receipts
+                        .iter()
+                        .flat_map(|receipt| [receipt.id, receipt.leased_until]),
+                )
+                .collect::<Vec<_>>(),
+        ),
+        None => ("leased_until <= ?".to_owned(), vec![now]),
+    };
+    let mut statement = connection
+        .prepare(&format!(
+            "SELECT id, payload, attempts, next_at, group_key FROM __actias_queue_messages \
+             WHERE leased_until IS NOT NULL AND {filter} ORDER BY id"
+        ))
+        .map_err(|e| e.to_string())?;
+    let rows = statement
+        .query_map(rusqlite::params_from_iter(params.iter()), |row| {
+            Ok(Due {
+                id: row.get(0)?,
+                payload: row.get(1)?,
+                attempts: row.get(2)?,
+                due_at: row.get(3)?,
+                group: row.get(4)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(rows)
+}
+
+/// Settles every lease that lapsed unacknowledged as a failed attempt:
+/// it retries on the backoff, or dead-letters once its attempts are
+/// spent, exactly as a refused delivery would.
+fn expire_leases(connection: &rusqlite::Connection, policy: &QueuePolicy) -> Result<(), String> {
+    for message in leased_rows(connection, None)? {
+        settle(
+            connection,
+            policy,
+            &message,
+            &Err("lease expired before an ack".to_owned()),
+        )?;
+    }
+    Ok(())
+}
+
+/// Hands a pull consumer up to `count` due messages, oldest first and at
+/// most one per group, hidden from delivery and other pullers until
+/// `visibility` (a duration or seconds) runs out. Each comes back as
+/// `{ id, body, attempt, leased_until, receipt }`; the receipt is what an
+/// `ack` or `nack` settles this attempt with, until the lease lapses.
+fn lease(
+    context: &super::PlatformContext<'_>,
+    policy: &QueuePolicy,
+    count: Option<&serde_json::Value>,
+    visibility: Option<&serde_json::Value>,
+) -> Result<serde_json::Value, String> {
+    let count = match count.filter(|count| !count.is_null()) {
+        None => 1,
+        Some(count) => count
+            .as_i64()
+            .filter(|count| (1..=MAX_BATCH).contains(count))
+            .ok_or_else(|| format!("A lease takes 1 to {MAX_BATCH} messages."))?,
+    };
+    let visibility_ms = match visibility.filter(|visibility| !visibility.is_null()) {
+        None => DEFAULT_VISIBILITY_MS,
+        Some(serde_json::Value::String(text)) => parse_duration_ms(text)?,
+        Some(serde_json::Value::Number(seconds)) => seconds
+            .as_f64()
+            .map(|seconds| (seconds * 1000.0) as i64)
+            .ok_or_else(|| "The visibility timeout is not a duration.".to_owned())?,
+        Some(_) => return Err("The visibility timeout is not a duration.".to_owned()),
+    };
+    if !(1..=MAX_VISIBILITY_MS).contains(&visibility_ms) {
+        return Err("The visibility timeout must be between 1ms and 12h.".to_owned());
+    }
+
+    context
+        .home
+        .with_storage(|storage| expire_leases(storage.platform(), policy))?;
+    let due = due_messages(context, count)?;
+    let leased_until = unix_now_ms() + visibility_ms;
+
+    let leased = context.home.with_storage(|storage| {
+        let connection = storage.platform();
+        let mut leased = Vec::with_capacity(due.len());
+        for message in &due {
+            connection
+                .execute(
+                    "UPDATE __actias_queue_messages SET leased_until = ?, next_at = ? WHERE id = ?",
+                    rusqlite::params![leased_until, leased_until, message.id],
+                )
+                .map_err(|e| e.to_string())?;
+            record_event(
+                connection,
+                "leased",
+                &serde_json::json!({
+                    "id": message.id,
+                    "attempt": message.attempts + 1,
+                    "leased_until": leased_until,
+                    "group": message.group,
+                }),
+            )?;
+            leased.push(serde_json::json!({
+                "id": message.id,
+                "body": serde_json::from_str::<serde_json::Value>(&message.payload)
+                    .unwrap_or(serde_json::Value::Null),
+                "attempt": message.attempts + 1,
+                "leased_until": leased_until,
+                "receipt": Receipt {
+                    id: message.id,
+                    leased_until,
+                }
+                .to_text(),
+            }));
+        }
+        Ok(serde_json::Value::Array(leased))
     })?;
 
-    if let Some(at) = earliest {
-        super::set_alarm(context, QUEUE_CLASS, at - unix_now_ms())?;
+    // The lapse needs a wake of its own; delivery moves it to the lapse
+    // when nobody listens.
+    arm_for_earliest(context)?;
+    Ok(leased)
+}
+
+/// Settles leased messages as delivered. Receipts that no longer name a
+/// live lease (lapsed, already settled, leased again, or gone) are
+/// skipped; the count says how many were acknowledged. A lapsed lease is
+/// not acknowledged even before it is reclaimed: its attempt is already
+/// over, and the message may be another consumer's by now.
+fn ack(
+    context: &super::PlatformContext<'_>,
+    policy: &QueuePolicy,
+    receipts: &[Receipt],
+) -> Result<serde_json::Value, String> {
+    let acked = context.home.with_storage(|storage| {
+        let connection = storage.platform();
+        let leased = leased_rows(connection, Some(receipts))?;
+        for message in &leased {
+            settle(connection, policy, message, &Ok(()))?;
+        }
+        Ok(leased.len())
+    })?;
+
+    // The alarm may be armed for one of these lapses; it moves to
+    // whatever is earliest now.
+    if acked > 0 {
+        arm_for_earliest(context)?;
     }
+    Ok(serde_json::json!(acked))
+}
 
-    Ok(serde_json::Value::Null)
+/// Settles leased messages as refused: each retries on the backoff or
+/// dead-letters once its attempts are spent, with `reason` journaled as
+/// the attempt's error. Stale receipts are skipped, as for [`ack`].
+fn nack(
+    context: &super::PlatformContext<'_>,
+    policy: &QueuePolicy,
+    receipts: &[Receipt],
+    reason: Option<&str>,
+) -> Result<serde_json::Value, String> {
+    let verdict = Err(reason.unwrap_or("refused by a pull consumer").to_owned());
+    let nacked = context.home.with_storage(|storage| {
+        let connection = storage.platform();
+        let leased = leased_rows(connection, Some(receipts))?;
+        for message in &leased {
+            settle(connection, policy, message, &verdict)?;
+        }
+        Ok(leased.len())
+    })?;
+
+    if nacked > 0 {
+        arm_for_earliest(context)?;
+    }
+    Ok(serde_json::json!(nacked))
 }
 
 /// Requeues dead letters: all of them, or one by id. Requeued rows start
-/// their attempts over and become new messages (new ids), which the
-/// journal records.
+/// their attempts over under their old ids, so a grouped one goes back
+/// to the head of its group rather than behind everything sent since;
+/// the journal records the requeue. Only a row whose id is taken (a file
+/// from before ids were unique) comes back under a new one.
 fn retry_dead(
     context: &super::PlatformContext<'_>,
     id: Option<i64>,
@@ -416,8 +1245,16 @@ fn retry_dead(
         let moved = connection
             .execute(
                 &format!(
-                    "INSERT INTO __actias_queue_messages (payload, attempts, next_at, enqueued_at) \
-                     SELECT payload, 0, ?, enqueued_at FROM __actias_queue_dead{filter}"
+                    "INSERT INTO __actias_queue_messages \
+                     (id, payload, attempts, next_at, enqueued_at, group_key) \
+                     SELECT CASE WHEN EXISTS (SELECT 1 FROM __actias_queue_messages AS live \
+                                              WHERE live.id = dead.id) \
+                                   OR dead.rowid <> (SELECT MIN(twin.rowid) \
+                                                     FROM __actias_queue_dead AS twin \
+                                                     WHERE twin.id = dead.id) \
+                                 THEN NULL ELSE dead.id END, \
+                            payload, 0, ?, enqueued_at, group_key \
+                     FROM __actias_queue_dead AS dead{filter}"
                 ),
                 rusqlite::params_from_iter(params.iter()),
             )
@@ -440,7 +1277,7 @@ fn retry_dead(
     })?;
 
     if count > 0 {
-        super::set_alarm(context, QUEUE_CLASS, 0)?;
+        super::set_alarm(context, context.class, 0)?;
     }
     Ok(serde_json::json!(count))
 }
@@ -471,6 +1308,29 @@ fn drop_message(
     })
 }
 
+/// Discards every message, live, leased and dead, in one go; for the
+/// on-call engineer facing a queue full of poison. Returns how many rows
+/// went, and the journal records the purge.
+fn purge(context: &super::PlatformContext<'_>) -> Result<serde_json::Value, String> {
+    context.home.with_storage(|storage| {
+        let connection = storage.platform();
+        let live = connection
+            .execute("DELETE FROM __actias_queue_messages", [])
+            .map_err(|e| e.to_string())?;
+        let dead = connection
+            .execute("DELETE FROM __actias_queue_dead", [])
+            .map_err(|e| e.to_string())?;
+        if live + dead > 0 {
+            record_event(
+                connection,
+                "purged",
+                &serde_json::json!({ "live": live, "dead": dead }),
+            )?;
+        }
+        Ok(serde_json::json!(live + dead))
+    })
+}
+
 /// The queue's numbers, dispatched as a method today and reusable by any
 /// read path that can open the file (a snapshot, a replica, an api
 /// endpoint) without dispatching at all. A file that predates the schema
@@ -478,19 +1338,41 @@ fn drop_message(
 /// decided by probing for the tables rather than classifying errors, so
 /// the accessor is safe on read-only connections too.
 pub fn read_stats(storage: &mut crate::storage::SqliteStorage) -> Result<Stats, String> {
-    let (depth, in_flight, oldest_pending) = if storage.table_exists(MESSAGES_TABLE)? {
-        storage
-            .platform()
-            .query_row(
-                "SELECT COUNT(*), COUNT(*) FILTER (WHERE next_at <= ?), MIN(enqueued_at) \
-                 FROM __actias_queue_messages",
-                rusqlite::params![unix_now_ms()],
-                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
-            )
-            .map_err(|e| e.to_string())?
-    } else {
-        (0, 0, None)
-    };
+    let (depth, in_flight, scheduled, blocked, leased, oldest_pending) =
+        if storage.table_exists(MESSAGES_TABLE)? {
+            let now = unix_now_ms();
+            let deliverable = deliverable_filter(storage)?;
+            let leased_until = leased_column(storage)?;
+            storage
+                .platform()
+                .query_row(
+                    &format!(
+                        "SELECT COUNT(*), \
+                         COUNT(*) FILTER (WHERE next_at <= ? AND {deliverable}), \
+                         COUNT(*) FILTER (WHERE next_at > ? AND attempts = 0 \
+                             AND {leased_until} IS NULL AND {deliverable}), \
+                         COUNT(*) FILTER (WHERE NOT {deliverable}), \
+                         COUNT(*) FILTER (WHERE {leased_until} > ?), \
+                         MIN(enqueued_at) FILTER (WHERE next_at <= ? OR attempts > 0 \
+                             OR {leased_until} IS NOT NULL) \
+                         FROM __actias_queue_messages"
+                    ),
+                    rusqlite::params![now, now, now, now],
+                    |row| {
+                        Ok((
+                            row.get(0)?,
+                            row.get(1)?,
+                            row.get(2)?,
+                            row.get(3)?,
+                            row.get(4)?,
+                            row.get(5)?,
+                        ))
+                    },
+                )
+                .map_err(|e| e.to_string())?
+        } else {
+            (0, 0, 0, 0, 0, None)
+        };
 
     let dead_letters = if storage.table_exists(DEAD_TABLE)? {
         storage
@@ -506,8 +1388,33 @@ pub fn read_stats(storage: &mut crate::storage::SqliteStorage) -> Result<Stats,
     Ok(Stats {
         depth,
         in_flight,
+        scheduled,
+        blocked,
+        leased,
         oldest_pending,
         dead_letters,
+        policy: read_policy(storage)?,
+    })
+}
+
+/// [`DELIVERABLE`] for a file that has the group column, and a filter
+/// that passes everything for one that predates it; read paths never
+/// migrate, so a replica of an old file reads its rows as ungrouped.
+fn deliverable_filter(storage: &mut crate::storage::SqliteStorage) -> Result<&'static str, String> {
+    Ok(if storage.schema_version()? >= 3 {
+        DELIVERABLE
+    } else {
+        "1"
+    })
+}
+
+/// The lease column for a file that has it, and a NULL literal for one
+/// that predates pulling, whose rows were never leased.
+fn leased_column(storage: &mut crate::storage::SqliteStorage) -> Result<&'static str, String> {
+    Ok(if storage.schema_version()? >= 4 {
+        "leased_until"
+    } else {
+        "NULL"
     })
 }
 
@@ -523,34 +1430,60 @@ fn stats(context: &super::PlatformContext<'_>) -> Result<serde_json::Value, Stri
 pub fn read_messages(storage: &mut crate::storage::SqliteStorage) -> Result<Vec<Message>, String> {
     let mut rows = Vec::new();
     let now = unix_now_ms();
+    let max_attempts = read_policy(storage)?.map(|policy| policy.max_attempts);
 
     if storage.table_exists(MESSAGES_TABLE)? {
+        // Files that predate the keys or the lease read them as absent.
+        let keys = if storage.schema_version()? >= 3 {
+            format!("dedup_key, group_key, {DELIVERABLE}")
+        } else {
+            "NULL, NULL, 1".to_owned()
+        };
+        let leased_until = leased_column(storage)?;
         let connection = storage.platform();
         let mut statement = connection
-            .prepare(
-                "SELECT id, payload, attempts, next_at, enqueued_at \
-                 FROM __actias_queue_messages ORDER BY id DESC LIMIT 200",
-            )
+            .prepare(&format!(
+                "SELECT id, payload, attempts, next_at, enqueued_at, {keys}, {leased_until} \
+                 FROM __actias_queue_messages ORDER BY id DESC LIMIT 200"
+            ))
             .map_err(|e| e.to_string())?;
         let live = statement
             .query_map([], |row| {
                 let payload: String = row.get(1)?;
+                let attempts: i64 = row.get(2)?;
                 let next_at: i64 = row.get(3)?;
+                let deliverable: bool = row.get(7)?;
+                let leased_until: Option<i64> = row.get(8)?;
                 Ok(Message {
                     id: row.get(0)?,
-                    state: if next_at <= now {
+                    // Behind an older message of its group is blocked,
+                    // whatever its own timing; a pull consumer's live
+                    // lease is leased. Otherwise, not yet due and never
+                    // tried is a scheduled send; not yet due after a
+                    // failure is a retry waiting out its backoff.
+                    state: if !deliverable {
+                        "blocked"
+                    } else if leased_until.is_some_and(|until| until > now) {
+                        "leased"
+                    } else if next_at <= now {
                         "in-flight"
+                    } else if attempts == 0 {
+                        "scheduled"
                     } else {
                         "pending"
                     }
                     .to_owned(),
-                    attempts: row.get(2)?,
+                    attempts,
+                    max_attempts,
                     preview: preview(&payload),
                     size: payload.len() as i64,
                     payload,
                     enqueued_ms: row.get(4)?,
                     next_ms: Some(next_at),
                     died_ms: None,
+                    dedup_key: row.get(5)?,
+                    group: row.get(6)?,
+                    leased_until,
                 })
             })
             .map_err(|e| e.to_string())?
@@ -560,12 +1493,17 @@ pub fn read_messages(storage: &mut crate::storage::SqliteStorage) -> Result<Vec<
     }
 
     if storage.table_exists(DEAD_TABLE)? {
+        let group = if storage.schema_version()? >= 3 {
+            "group_key"
+        } else {
+            "NULL"
+        };
         let connection = storage.platform();
         let mut statement = connection
-            .prepare(
-                "SELECT id, payload, attempts, enqueued_at, died_at \
-                 FROM __actias_queue_dead ORDER BY id DESC LIMIT 200",
-            )
+            .prepare(&format!(
+                "SELECT id, payload, attempts, enqueued_at, died_at, {group} \
+                 FROM __actias_queue_dead ORDER BY id DESC LIMIT 200"
+            ))
             .map_err(|e| e.to_string())?;
         let dead = statement
             .query_map([], |row| {
@@ -574,12 +1512,16 @@ pub fn read_messages(storage: &mut crate::storage::SqliteStorage) -> Result<Vec<
                     id: row.get(0)?,
                     state: "dead".to_owned(),
                     attempts: row.get(2)?,
+                    max_attempts,
                     preview: preview(&payload),
                     size: payload.len() as i64,
                     payload,
                     enqueued_ms: row.get(3)?,
                     next_ms: None,
                     died_ms: row.get(4)?,
+                    dedup_key: None,
+                    group: row.get(5)?,
+                    leased_until: None,
                 })
             })
             .map_err(|e| e.to_string())?
@@ -0,0 +1,718 @@
+//! The `__topic` platform class: a fan-out log whose sqlite holds what
+//! was published and whose alarm loop forwards it to every subscriber.
+//!
+//! `publish` appends once and arms the alarm. The alarm asks who
+//! subscribes right now (every script whose current contract declares
+//! `on "topic:<name>"`) and sends each entry to one `__subscription`
+//! object per subscriber, named `<topic>/<script id>`. A subscription is
+//! a queue in everything but the listener it fires, so each subscriber
+//! retries, backs off and dead-letters on its own, and one slow consumer
+//! never holds back the rest.
+//!
+//! An entry leaves the log once every subscriber has it. Each landed
+//! send is recorded against the entry, so a fan-out that fails part way
+//! retries, on the node's backoff, only the subscribers still missing
+//! it; the resend also carries the entry's sequence number as its dedup
+//! key, which covers a send that landed but was not recorded. After the
+//! node's `max_attempts` the subscribers still missing the entry get a
+//! dead letter here instead, and the entry leaves the log; so does an
+//! entry nobody could be tried for (no router, or the subscriber lookup
+//! failing), as one letter for whoever is still missing it. Dead letters
+//! can be listed, resent, dropped or purged the way a queue's can.
+//!
+//! A database's change log fans out through the same loop; [`Feed`] is
+//! what differs between the two.
+
+use crate::extensions::objects::{ObjectRouter, ObjectTarget, SUBSCRIPTION_CLASS, TOPIC_CLASS};
+use crate::runtime::ActiasRuntime;
+
+/// How a fan-out learns its subscribers, asked with the event they
+/// declare (`topic:<name>`, `database:<name>`): the worker answers from
+/// the project's current contracts, so a republish that adds or drops an
+/// `on` changes the next fan-out. Carried in app data the way
+/// [`ObjectRouter`] is; a vm without one (local testing) cannot fan out
+/// and keeps its entries.
+pub type SubscriberFuture =
+    std::pin::Pin<Box<dyn Future<Output = Result<Vec<String>, String>> + Send>>;
+pub type SubscriberLookup = std::sync::Arc<dyn Fn(String) -> SubscriberFuture + Send + Sync>;
+
+/// The topic schema's version, stamped in the file's version cell.
+/// Version 2 adds the delivery records and the dead letters.
+const SCHEMA_VERSION: i64 = 2;
+
+/// Published entries not yet at every subscriber. The sequence number is
+/// the entry's identity across fan-out attempts, so AUTOINCREMENT keeps
+/// it from ever naming two entries.
+const CREATE_LOG: &str = "CREATE TABLE IF NOT EXISTS __actias_topic_log (
+        seq INTEGER PRIMARY KEY AUTOINCREMENT,
+        payload TEXT NOT NULL,
+        published_at INTEGER NOT NULL,
+        attempts INTEGER NOT NULL DEFAULT 0,
+        next_at INTEGER NOT NULL
+    )";
+
+/// Which subscribers already took an entry, so a retry skips them;
+/// rows leave with their entry.
+const CREATE_DELIVERED: &str = "CREATE TABLE IF NOT EXISTS __actias_fan_out_delivered (
+        seq INTEGER NOT NULL,
+        subscriber TEXT NOT NULL,
+        PRIMARY KEY (seq, subscriber)
+    )";
+
+/// Entries a subscriber never took within the attempts, one row per
+/// subscriber, kept for inspection; the entry itself has left the log.
+/// An empty subscriber stands for every one still missing the entry, and
+/// keeps the entry's delivery records until the letter goes.
+const CREATE_DEAD: &str = "CREATE TABLE IF NOT EXISTS __actias_fan_out_dead (
+        seq INTEGER NOT NULL,
+        subscriber TEXT NOT NULL,
+        payload TEXT NOT NULL,
+        attempts INTEGER NOT NULL,
+        died_at INTEGER NOT NULL,
+        error TEXT NOT NULL
+    )";
+
+/// How many entries one alarm firing forwards before re-arming.
+const FAN_OUT_BATCH: i64 = 16;
+
+/// Why nothing could be sent on a node without an [`ObjectRouter`].
+const NO_ROUTER: &str = "This node cannot route object calls.";
+
+/// One fan-out source: a log table shaped like the topic's, the event
+/// its subscribers declare, and the prefix their subscriptions are named
+/// under, `<prefix>/<script id>`.
+pub(crate) struct Feed {
+    pub log: &'static str,
+    pub event: String,
+    pub subscriptions: String,
+}
+
+impl Feed {
+    /// A topic's own log.
+    fn topic(name: &str) -> Self {
+        Self {
+            log: "__actias_topic_log",
+            event: format!("topic:{name}"),
+            subscriptions: name.to_owned(),
+        }
+    }
+}
+
+/// Routes one `__topic` method call.
+///
+/// # Errors
+/// Returns the user-safe text of whatever failed; unknown methods read
+/// like a missing method on any class.
+pub(crate) async fn dispatch(
+    runtime: &ActiasRuntime,
+    context: &super::PlatformContext<'_>,
+    call: &super::Call,
+) -> Result<serde_json::Value, String> {
+    context.home.with_storage(|storage| {
+        if storage.schema_version()? >= SCHEMA_VERSION {
+            return Ok(());
+        }
+        storage
+            .platform()
+            .execute_batch(&format!("{CREATE_LOG}; {CREATE_DELIVERED}; {CREATE_DEAD};"))
+            .map_err(|e| e.to_string())?;
+        storage.set_schema_version(SCHEMA_VERSION)
+    })?;
+
+    match call.method.as_str() {
+        "publish" => publish(
+            context,
+            call.args
+                .first()
+                .cloned()
+                .unwrap_or(serde_json::Value::Null),
+        ),
+        "alarm" => fan_out(runtime, context, &Feed::topic(context.name)).await,
+        "stats" => stats(context),
+        _ => match dead_letter_call(runtime, context, &Feed::topic(context.name), call).await {
+            Some(result) => result,
+            None => Err(format!(
+                "Object class '{TOPIC_CLASS}' has no method '{}'.",
+                call.method
+            )),
+        },
+    }
+}
+
+/// Routes the dead-letter controls any feed's object answers, named as a
+/// queue's are: `messages` lists the letters, `retry_dead` and
+/// `retry_message` resend all or one, `drop_message` discards one and
+/// `purge` empties the feed. [`None`] for any other method.
+pub(crate) async fn dead_letter_call(
+    runtime: &ActiasRuntime,
+    context: &super::PlatformContext<'_>,
+    feed: &Feed,
+    call: &super::Call,
+) -> Option<Result<serde_json::Value, String>> {
+    let id = || {
+        call.args
+            .first()
+            .and_then(|value| value.as_i64())
+            .ok_or_else(|| "The message id must be a number.".to_owned())
+    };
+    if !matches!(
+        call.method.as_str(),
+        "messages" | "retry_dead" | "retry_message" | "drop_message" | "purge"
+    ) {
+        return None;
+    }
+    if let Err(error) = create_fan_out_tables(context) {
+        return Some(Err(error));
+    }
+    Some(match call.method.as_str() {
+        "messages" => context
+            .home
+            .with_storage(read_dead_letters)
+            .and_then(|letters| serde_json::to_value(letters).map_err(|e| e.to_string())),
+        "retry_dead" => retry_dead(runtime, context, feed, None).await,
+        "retry_message" => match id() {
+            Ok(id) => retry_dead(runtime, context, feed, Some(id)).await,
+            Err(error) => Err(error),
+        },
+        "drop_message" => id().and_then(|id| drop_message(context, id)),
+        _ => purge(context, feed),
+    })
+}
+
+/// Appends one entry and arms the alarm to forward it now. Returns the
+/// entry's sequence number.
+fn publish(
+    context: &super::PlatformContext<'_>,
+    payload: serde_json::Value,
+) -> Result<serde_json::Value, String> {
+    let text = serde_json::to_string(&payload).map_err(|e| e.to_string())?;
+    let now = crate::extensions::objects::unix_now_ms();
+
+    let seq = context.home.with_storage(|storage| {
+        let connection = storage.platform();
+        connection
+            .execute(
+                "INSERT INTO __actias_topic_log (payload, published_at, next_at) VALUES (?, ?, ?)",
+                rusqlite::params![text, now, now],
+            )
+            .map_err(|e| e.to_string())?;
+        Ok(connection.last_insert_rowid())
+    })?;
+
+    super::set_alarm(context, TOPIC_CLASS, 0)?;
+    Ok(serde_json::json!(seq))
+}
+
+/// One due log entry as fan-out reads it.
+struct Entry {
+    seq: i64,
+    payload: serde_json::Value,
+    attempts: i64,
+}
+
+/// Forwards due entries to every current subscriber that lacks them,
+/// dropping each entry that reached them all, dead-lettering what is
+/// left of one out of attempts and backing off the rest, then re-arms
+/// for the earliest remaining one. The storage borrow is never held
+/// across a send.
+pub(crate) async fn fan_out(
+    runtime: &ActiasRuntime,
+    context: &super::PlatformContext<'_>,
+    feed: &Feed,
+) -> Result<serde_json::Value, String> {
+    create_fan_out_tables(context)?;
+
+    let due = due_entries(context, feed)?;
+    if due.is_empty() {
+        arm_for_earliest(context, feed)?;
+        return Ok(serde_json::Value::Null);
+    }
+
+    let router = runtime
+        .app_data_ref::<ObjectRouter>()
+        .map(|router| router.clone());
+    let subscribers = subscribers(runtime, feed).await;
+
+    let policy = context.home.queue_policy().clone();
+    for entry in due {
+        // The subscribers still missing the entry, each with its error;
+        // an error for the whole entry means nobody could be tried.
+        let missing = match (&router, &subscribers) {
+            (Some(router), Ok(subscribers)) => {
+                forward(router, context, feed, &entry, subscribers).await
+            }
+            (None, _) => Err(NO_ROUTER.to_owned()),
+            (_, Err(error)) => Err(error.clone()),
+        };
+
+        let attempt = entry.attempts + 1;
+        let now = crate::extensions::objects::unix_now_ms();
+        context.home.with_storage(|storage| {
+            let connection = storage.platform();
+            match &missing {
+                Ok(missing) if missing.is_empty() => {
+                    return settle(connection, feed, entry.seq);
+                }
+                Ok(missing) if attempt >= policy.max_attempts => {
+                    let payload =
+                        serde_json::to_string(&entry.payload).map_err(|e| e.to_string())?;
+                    for (subscriber, error) in missing {
+                        actias_common::tracing::warn!(
+                            %error, event = feed.event.as_str(), seq = entry.seq, subscriber,
+                            "fan-out dead-lettered"
+                        );
+                        connection
+                            .execute(
+                                "INSERT INTO __actias_fan_out_dead \
+                                 (seq, subscriber, payload, attempts, died_at, error) \
+                                 VALUES (?, ?, ?, ?, ?, ?)",
+                                rusqlite::params![
+                                    entry.seq, subscriber, payload, attempt, now, error
+                                ],
+                            )
+                            .map_err(|e| e.to_string())?;
+                    }
+                    return settle(connection, feed, entry.seq);
+                }
+                Ok(missing) => {
+                    for (subscriber, error) in missing {
+                        actias_common::tracing::warn!(
+                            %error, event = feed.event.as_str(), seq = entry.seq, subscriber,
+                            "fan-out failed"
+                        );
+                    }
+                }
+                Err(error) if attempt >= policy.max_attempts => {
+                    actias_common::tracing::warn!(
+                        %error, event = feed.event.as_str(), seq = entry.seq,
+                        "fan-out dead-lettered"
+                    );
+                    let payload =
+                        serde_json::to_string(&entry.payload).map_err(|e| e.to_string())?;
+                    connection
+                        .execute(
+                            "INSERT INTO __actias_fan_out_dead \
+                             (seq, subscriber, payload, attempts, died_at, error) \
+                             VALUES (?, '', ?, ?, ?, ?)",
+                            rusqlite::params![entry.seq, payload, attempt, now, error],
+                        )
+                        .map_err(|e| e.to_string())?;
+                    return settle(connection, feed, entry.seq);
+                }
+                Err(error) => {
+                    actias_common::tracing::warn!(
+                        %error, event = feed.event.as_str(), seq = entry.seq, "fan-out failed"
+                    );
+                }
+            }
+            connection
+                .execute(
+                    &format!(
+                        "UPDATE {} SET attempts = ?, next_at = ? WHERE seq = ?",
+                        feed.log
+                    ),
+                    rusqlite::params![
+                        attempt,
+                        now + policy.backoff_after(entry.attempts),
+                        entry.seq
+                    ],
+                )
+                .map_err(|e| e.to_string())?;
+            Ok(())
+        })?;
+    }
+
+    arm_for_earliest(context, feed)?;
+    Ok(serde_json::Value::Null)
+}
+
+/// Creates the delivery records and dead letters; a database's feed has
+/// no schema step of its own for these.
+fn create_fan_out_tables(context: &super::PlatformContext<'_>) -> Result<(), String> {
+    context.home.with_storage(|storage| {
+        storage
+            .platform()
+            .execute_batch(&format!("{CREATE_DELIVERED}; {CREATE_DEAD};"))
+            .map_err(|e| e.to_string())
+    })
+}
+
+/// The feed's current subscribers, from the node's [`SubscriberLookup`].
+async fn subscribers(runtime: &ActiasRuntime, feed: &Feed) -> Result<Vec<String>, String> {
+    let lookup = runtime
+        .app_data_ref::<SubscriberLookup>()
+        .map(|lookup| lookup.clone());
+    match lookup {
+        Some(lookup) => lookup(feed.event.clone()).await,
+        None => Err(format!(
+            "This node cannot list '{}' subscribers.",
+            feed.event
+        )),
+    }
+}
+
+/// Drops an entry that is done with, and its delivery records.
+fn settle(connection: &rusqlite::Connection, feed: &Feed, seq: i64) -> Result<(), String> {
+    connection
+        .execute(
+            &format!("DELETE FROM {} WHERE seq = ?", feed.log),
+            rusqlite::params![seq],
+        )
+        .map_err(|e| e.to_string())?;
+    forget_delivered(connection, seq)
+}
+
+/// Drops an entry's delivery records unless a letter for every missing
+/// subscriber still needs them to tell who those are.
+fn forget_delivered(connection: &rusqlite::Connection, seq: i64) -> Result<(), String> {
+    connection
+        .execute(
+            "DELETE FROM __actias_fan_out_delivered WHERE seq = ?1 AND NOT EXISTS \
+             (SELECT 1 FROM __actias_fan_out_dead WHERE seq = ?1 AND subscriber = '')",
+            rusqlite::params![seq],
+        )
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Sends one entry to each subscriber's subscription that has not taken
+/// it yet, recording every send that lands. Every send is attempted even
+/// after one fails, so a single unreachable subscriber delays only its
+/// own copy. Returns the subscribers still missing the entry.
+async fn forward(
+    router: &ObjectRouter,
+    context: &super::PlatformContext<'_>,
+    feed: &Feed,
+    entry: &Entry,
+    subscribers: &[String],
+) -> Result<Vec<(String, String)>, String> {
+    let delivered: Vec<String> = context.home.with_storage(|storage| {
+        let mut statement = storage
+            .platform()
+            .prepare("SELECT subscriber FROM __actias_fan_out_delivered WHERE seq = ?")
+            .map_err(|e| e.to_string())?;
+        statement
+            .query_map(rusqlite::params![entry.seq], |row| row.get(0))
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())
+    })?;
+
+    let mut missing = Vec::new();
+    for subscriber in subscribers
+        .iter()
+        .filter(|subscriber| !delivered.contains(subscriber))
+    {
+        let sent = router(ObjectTarget {
+            class: SUBSCRIPTION_CLASS.to_owned(),
+            name: format!("{}/{subscriber}", feed.subscriptions),
+            method: "send".to_owned(),
+            arguments: vec![
+                entry.payload.clone(),
+                serde_json::json!({ "dedup_key": entry.seq.to_string() }),
+            ],
+            chain: vec![context.own_key.to_owned()],
+            caller: None,
+            consistency: None,
+            deadline: None,
+        })
+        .await;
+        match sent {
+            Ok(_) => context.home.with_storage(|storage| {
+                storage
+                    .platform()
+                    .execute(
+                        "INSERT OR IGNORE INTO __actias_fan_out_delivered (seq, subscriber) \
+                         VALUES (?, ?)",
+                        rusqlite::params![entry.seq, subscriber],
+                    )
+                    .map_err(|e| e.to_string())?;
+                Ok(())
+            })?,
+            Err(error) => missing.push((subscriber.clone(), error)),
+        }
+    }
+    Ok(missing)
+}
+
+/// Up to [`FAN_OUT_BATCH`] due entries, oldest first.
+fn due_entries(context: &super::PlatformContext<'_>, feed: &Feed) -> Result<Vec<Entry>, String> {
+    context.home.with_storage(|storage| {
+        let mut statement = storage
+            .platform()
+            .prepare(&format!(
+                "SELECT seq, payload, attempts FROM {} \
+                 WHERE next_at <= ? ORDER BY seq LIMIT ?",
+                feed.log
+            ))
+            .map_err(|e| e.to_string())?;
+        let due = statement
+            .query_map(
+                rusqlite::params![crate::extensions::objects::unix_now_ms(), FAN_OUT_BATCH],
+                |row| {
+                    let payload: String = row.get(1)?;
+                    Ok(Entry {
+                        seq: row.get(0)?,
+                        // A payload that no longer parses forwards as null,
+                        // the way a queue delivers it.
+                        payload: serde_json::from_str(&payload).unwrap_or(serde_json::Value::Null),
+                        attempts: row.get(2)?,
+                    })
+                },
+            )
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+        Ok(due)
+    })
+}
+
+/// Arms the alarm for the earliest remaining entry, or leaves it be when
+/// the log is empty.
+fn arm_for_earliest(context: &super::PlatformContext<'_>, feed: &Feed) -> Result<(), String> {
+    let earliest: Option<i64> = context.home.with_storage(|storage| {
+        storage
+            .platform()
+            .query_row(
+                &format!("SELECT MIN(next_at) FROM {}", feed.log),
+                [],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())
+    })?;
+
+    if let Some(at) = earliest {
+        super::set_alarm(
+            context,
+            context.class,
+            at - crate::extensions::objects::unix_now_ms(),
+        )?;
+    }
+    Ok(())
+}
+
+/// Entries still on their way to subscribers, the oldest one's publish
+/// time, and the dead letters left by subscribers that never took one;
+/// delivered entries are the subscriptions' to count.
+fn stats(context: &super::PlatformContext<'_>) -> Result<serde_json::Value, String> {
+    let (pending, oldest_pending): (i64, Option<i64>) = context.home.with_storage(|storage| {
+        storage
+            .platform()
+            .query_row(
+                "SELECT COUNT(*), MIN(published_at) FROM __actias_topic_log",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .map_err(|e| e.to_string())
+    })?;
+    let dead_letters: i64 = context.home.with_storage(|storage| {
+        storage
+            .platform()
+            .query_row("SELECT COUNT(*) FROM __actias_fan_out_dead", [], |row| {
+                row.get(0)
+            })
+            .map_err(|e| e.to_string())
+    })?;
+    Ok(serde_json::json!({
+        "pending": pending,
+        "oldest_pending": oldest_pending,
+        "dead_letters": dead_letters,
+    }))
+}
+
+/// One dead letter for the inspector's table, newest first; `id` is what
+/// `retry_message` and `drop_message` take. An empty subscriber stands
+/// for every subscriber that was still missing the entry.
+#[derive(serde::Serialize)]
+pub struct DeadLetter {
+    pub id: i64,
+    pub seq: i64,
+    pub subscriber: String,
+    pub payload: String,
+    pub attempts: i64,
+    pub died_ms: i64,
+    pub error: String,
+}
+
+/// A feed's dead letters, newest first. Reusable by any read path that
+/// can open the file; one without the table reads as none.
+pub fn read_dead_letters(
+    storage: &mut crate::storage::SqliteStorage,
+) -> Result<Vec<DeadLetter>, String> {
+    if !storage.table_exists("__actias_fan_out_dead")? {
+        return Ok(Vec::new());
+    }
+    let mut statement = storage
+        .platform()
+        .prepare(
+            "SELECT rowid, seq, subscriber, payload, attempts, died_at, error \
+             FROM __actias_fan_out_dead ORDER BY rowid DESC LIMIT 200",
+        )
+        .map_err(|e| e.to_string())?;
+    statement
+        .query_map([], |row| {
+            Ok(DeadLetter {
+                id: row.get(0)?,
+                seq: row.get(1)?,
+                subscriber: row.get(2)?,
+                payload: row.get(3)?,
+                attempts: row.get(4)?,
+                died_ms: row.get(5)?,
+                error: row.get(6)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())
+}
+
+/// Resends dead letters: all of them, or one by id. Where a queue
+/// requeues, a letter is resent on the spot: retrying and backing off is
+/// the subscription's business once it holds the entry, and the send
+/// carries the entry's sequence number as its dedup key like any other.
+/// A letter for one subscriber goes to that subscriber; one for everyone
+/// missing the entry goes to each current subscriber without a delivery
+/// record. A letter that lands leaves; one that does not stays, with its
+/// attempts and error updated. Returns how many landed.
+async fn retry_dead(
+    runtime: &ActiasRuntime,
+    context: &super::PlatformContext<'_>,
+    feed: &Feed,
+    id: Option<i64>,
+) -> Result<serde_json::Value, String> {
+    let letters: Vec<(i64, String, Entry)> = context.home.with_storage(|storage| {
+        let (filter, params) = match id {
+            Some(id) => (" WHERE rowid = ?", vec![id]),
+            None => ("", Vec::new()),
+        };
+        let mut statement = storage
+            .platform()
+            .prepare(&format!(
+                "SELECT rowid, subscriber, seq, payload, attempts \
+                 FROM __actias_fan_out_dead{filter} ORDER BY rowid"
+            ))
+            .map_err(|e| e.to_string())?;
+        statement
+            .query_map(rusqlite::params_from_iter(params.iter()), |row| {
+                let payload: String = row.get(3)?;
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    Entry {
+                        seq: row.get(2)?,
+                        payload: serde_json::from_str(&payload).unwrap_or(serde_json::Value::Null),
+                        attempts: row.get(4)?,
+                    },
+                ))
+            })
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())
+    })?;
+    if letters.is_empty() {
+        return Ok(serde_json::json!(0));
+    }
+
+    let router = runtime
+        .app_data_ref::<ObjectRouter>()
+        .map(|router| router.clone())
+        .ok_or_else(|| NO_ROUTER.to_owned())?;
+    let mut everyone = None;
+    let mut landed = 0;
+    for (rowid, subscriber, entry) in letters {
+        let targets = if subscriber.is_empty() {
+            if everyone.is_none() {
+                everyone = Some(subscribers(runtime, feed).await);
+            }
+            everyone.clone().expect("looked up above")
+        } else {
+            Ok(vec![subscriber])
+        };
+        let missing = match targets {
+            Ok(targets) => forward(&router, context, feed, &entry, &targets).await?,
+            Err(error) => vec![(String::new(), error)],
+        };
+
+        let now = crate::extensions::objects::unix_now_ms();
+        context.home.with_storage(|storage| {
+            let connection = storage.platform();
+            if missing.is_empty() {
+                connection
+                    .execute(
+                        "DELETE FROM __actias_fan_out_dead WHERE rowid = ?",
+                        rusqlite::params![rowid],
+                    )
+                    .map_err(|e| e.to_string())?;
+                return forget_delivered(connection, entry.seq);
+            }
+            let error = missing
+                .iter()
+                .map(|(_, error)| error.as_str())
+                .collect::<Vec<_>>()
+                .join("; ");
+            connection
+                .execute(
+                    "UPDATE __actias_fan_out_dead \
+                     SET attempts = attempts + 1, died_at = ?, error = ? WHERE rowid = ?",
+                    rusqlite::params![now, error, rowid],
+                )
+                .map_err(|e| e.to_string())?;
+            Ok(())
+        })?;
+        if missing.is_empty() {
+            landed += 1;
+        }
+    }
+    Ok(serde_json::json!(landed))
+}
+
+/// Discards one dead letter by id. Returns whether there was one.
+fn drop_message(
+    context: &super::PlatformContext<'_>,
+    id: i64,
+) -> Result<serde_json::Value, String> {
+    context.home.with_storage(|storage| {
+        let connection = storage.platform();
+        let seq: i64 = match connection.query_row(
+            "SELECT seq FROM __actias_fan_out_dead WHERE rowid = ?",
+            rusqlite::params![id],
+            |row| row.get(0),
+        ) {
+            Ok(seq) => seq,
+            Err(rusqlite::Error::QueryReturnedNoRows) => return Ok(serde_json::json!(false)),
+            Err(error) => return Err(error.to_string()),
+        };
+        connection
+            .execute(
+                "DELETE FROM __actias_fan_out_dead WHERE rowid = ?",
+                rusqlite::params![id],
+            )
+            .map_err(|e| e.to_string())?;
+        forget_delivered(connection, seq)?;
+        Ok(serde_json::json!(true))
+    })
+}
+
+/// Discards every entry still on its way and every dead letter, with
+/// their delivery records. Returns how many entries and letters went.
+fn purge(context: &super::PlatformContext<'_>, feed: &Feed) -> Result<serde_json::Value, String> {
+    context.home.with_storage(|storage| {
+        // A database nobody has listened to yet has no change log.
+        let logged = storage.table_exists(feed.log)?;
+        let connection = storage.platform();
+        let live = if logged {
+            connection
+                .execute(&format!("DELETE FROM {}", feed.log), [])
+                .map_err(|e| e.to_string())?
+        } else {
+            0
+        };
+        let dead = connection
+            .execute("DELETE FROM __actias_fan_out_dead", [])
+            .map_err(|e| e.to_string())?;
+        connection
+            .execute("DELETE FROM __actias_fan_out_delivered", [])
+            .map_err(|e| e.to_string())?;
+        Ok(serde_json::json!(live + dead))
+    })
+}
@@ -101,6 +101,8 @@ pub struct Declarations {
     pub queues: Vec<String>,
     /// Names handed to `workflow "name"`.
     pub workflows: Vec<String>,
+    /// Names handed to `topic "name"`.
+    pub topics: Vec<String>,
 }
 
 /// The capability contract a revision was published with.
@@ -115,9 +117,18 @@ pub struct Contract {
     objects: HashSet<String>,
     databases: HashSet<String>,
     queues: HashSet<String>,
+    topics: HashSet<String>,
     /// Kept as declared (ordered, duplicates meaningless but harmless);
     /// cron arming reads these.
     events: Vec<String>,
+    /// Declared delivery policies by queue name; the queue object reads
+    /// its own when this revision is the one consuming it.
+    queue_policies: HashMap<String, crate::proto::script_service::QueuePolicy>,
+    /// Declared workflow start schedules; each rides the `__cron` object
+    /// of its expression.
+    workflow_schedules: Vec<crate::proto::script_service::WorkflowSchedule>,
+    /// Declared settled-run retentions by workflow definition.
+    workflow_retentions: HashMap<String, crate::proto::script_service::WorkflowRetention>,
 }
 
 /// Which contract list a declaration checks against.
@@ -127,6 +138,7 @@ pub enum ContractKind {
     Object,
     Database,
     Queue,
+    Topic,
 }
 
 /// A revision compiled once and shared by every request that runs it.
@@ -191,7 +203,19 @@ impl PreparedRevision {
                 objects: capabilities.objects.into_iter().collect(),
                 databases: capabilities.databases.into_iter().collect(),
                 queues: capabilities.queues.into_iter().collect(),
+                topics: capabilities.topics.into_iter().collect(),
                 events: capabilities.events,
+                queue_policies: capabilities
+                    .queue_policies
+                    .into_iter()
+                    .map(|policy| (policy.queue.clone(), policy))
+                    .collect(),
+                workflow_schedules: capabilities.workflow_schedules,
+                workflow_retentions: capabilities
+                    .workflow_retentions
+                    .into_iter()
+                    .map(|retention| (retention.workflow.clone(), retention))
+                    .collect(),
             });
 
         Ok(Self {
@@ -240,22 +264,66 @@ impl PreparedRevision {
         None
     }
 
-    /// The cron events this revision's contract declares; the worker arms
-    /// a `__cron` object for each at first touch.
+    /// The cron events this revision's contract declares, listeners and
+    /// workflow schedules alike; the worker arms a `__cron` object for
+    /// each at first touch, and an expression both use shares one.
     pub fn cron_events(&self) -> Vec<String> {
+        let Some(contract) = self.contract.as_ref() else {
+            return Vec::new();
+        };
+        let mut events: Vec<String> = contract
+            .events
+            .iter()
+            .filter(|event| event.starts_with("cron:"))
+            .cloned()
+            .collect();
+        for schedule in &contract.workflow_schedules {
+            let event = format!("cron:{}", schedule.schedule);
+            if !events.contains(&event) {
+                events.push(event);
+            }
+        }
+        events
+    }
+
+    /// The workflow schedules that fire on cron event `event`.
+    pub fn workflow_schedules(
+        &self,
+        event: &str,
+    ) -> Vec<&crate::proto::script_service::WorkflowSchedule> {
         self.contract
             .as_ref()
             .map(|contract| {
                 contract
-                    .events
+                    .workflow_schedules
                     .iter()
-                    .filter(|event| event.starts_with("cron:"))
-                    .cloned()
+                    .filter(|schedule| {
+                        event.strip_prefix("cron:") == Some(schedule.schedule.as_str())
+                    })
                     .collect()
             })
             .unwrap_or_default()
     }
 
+    /// The delivery policy this revision declares for `queue`, if any;
+    /// live sessions and contract-less revisions declare none.
+    pub fn queue_policy(&self, queue: &str) -> Option<&crate::proto::script_service::QueuePolicy> {
+        self.contract
+            .as_ref()
+            .and_then(|contract| contract.queue_policies.get(queue))
+    }
+
+    /// How long this revision keeps settled runs of `workflow`, if it
+    /// declares a retention at all.
+    pub fn workflow_retention(
+        &self,
+        workflow: &str,
+    ) -> Option<&crate::proto::script_service::WorkflowRetention> {
+        self.contract
+            .as_ref()
+            .and_then(|contract| contract.workflow_retentions.get(workflow))
+    }
+
     /// Migration files for one database, in application order: every
     /// `migrations/<database>/*.sql` in the bundle, sorted by path, which
     /// is why the scaffold numbers them.
@@ -369,6 +437,57 @@ impl ActiasRuntime {
         self.lua.named_registry_value(&Self::listener_key(event))
     }
 
+    /// Registry key holding the batching a queue listener declared.
+    fn batching_key(event: &str) -> String {
+        format!("batching_{event}")
+    }
+
+    /// The batching the listener for `event` declared, or `None` when it
+    /// takes its messages one at a time.
+    pub fn batching(&self, event: &str) -> Option<crate::platform::queue::Batching> {
+        let stored: Table = self
+            .lua
+            .named_registry_value(&Self::batching_key(event))
+            .ok()?;
+        Some(crate::platform::queue::Batching {
+            size: stored.get("size").ok()?,
+            max_wait_ms: stored.get("max_wait_ms").ok()?,
+        })
+    }
+
+    /// Registry key holding the zone and catch-up a cron listener declared.
+    fn cron_policy_key(event: &str) -> String {
+        format!("cron_policy_{event}")
+    }
+
+    /// The policy the listener for the cron `event` declared; UTC and
+    /// `latest` when it declared none, or when only workflows schedule on
+    /// the expression.
+    pub fn cron_policy(&self, event: &str) -> crate::platform::cron::CronPolicy {
+        let stored: Option<Table> = self
+            .lua
+            .named_registry_value(&Self::cron_policy_key(event))
+            .ok();
+        let mut policy = crate::platform::cron::CronPolicy::default();
+        if let Some(stored) = stored {
+            if let Some(tz) = stored
+                .get::<String>("tz")
+                .ok()
+                .and_then(|zone| zone.parse().ok())
+            {
+                policy.tz = tz;
+            }
+            if let Some(catch_up) = stored
+                .get::<String>("catch_up")
+                .ok()
+                .and_then(|text| crate::platform::cron::CatchUp::parse(&text))
+            {
+                policy.catch_up = catch_up;
+            }
+        }
+        policy
+    }
+
     /// Errors unless the vm is evaluating the entry point's top level.
     ///
     /// Every declaration form calls this first, so `kv "x"` inside a handler
@@ -404,6 +523,7 @@ impl ActiasRuntime {
             ContractKind::Object => (&contract.objects, "Object class"),
             ContractKind::Database => (&contract.databases, "Database"),
             ContractKind::Queue => (&contract.queues, "Queue"),
+            ContractKind::Topic => (&contract.topics, "Topic"),
         };
 
         if allowed.contains(name) {
@@ -437,6 +557,13 @@ impl ActiasRuntime {
         }
     }
 
+    /// Notes a `topic "name"` declaration for [`Self::declarations`].
+    pub fn record_topic_declaration(lua: &Lua, name: &str) {
+        if let Some(mut declarations) = lua.app_data_mut::<Declarations>() {
+            declarations.topics.push(name.to_owned());
+        }
+    }
+
     /// Notes a `queue "name"` declaration for [`Self::declarations`].
     pub fn record_queue_declaration(lua: &Lua, name: &str) {
         if let Some(mut declarations) = lua.app_data_mut::<Declarations>() {
@@ -487,6 +614,23 @@ impl ActiasRuntime {
                             "A queue event names its queue: on \"queue:<name>\".".to_owned(),
                         ));
                     }
+                } else if let Some(name) = event.strip_prefix("topic:") {
+                    // The name ends up in `<topic>/<subscriber>`.
+                    if name.trim().is_empty() || name.contains('/') {
+                        return Err(mlua::Error::RuntimeError(
+                            "A topic event names its topic without '/': on \"topic:<name>\"."
+                                .to_owned(),
+                        ));
+                    }
+                } else if let Some(name) = event.strip_prefix("database:") {
+                    // The name ends up in `database/<name>/<subscriber>`.
+                    if name.trim().is_empty() || name.contains('/') {
+                        return Err(mlua::Error::RuntimeError(
+                            "A database event names its database without '/': \
+                             on \"database:<name>\"."
+                                .to_owned(),
+                        ));
+                    }
                 } else if !Self::EVENTS.contains(&event.as_str()) {
                     return Err(mlua::Error::RuntimeError(format!(
                         "Invalid event '{event}', expected one of: {}.",
@@ -500,13 +644,66 @@ impl ActiasRuntime {
 
                 // `on "fetch" (fn)` is `on("fetch")(fn)`, so the declaration
                 // returns the registrar that takes the handler.
-                lua.create_function(move |lua, callback: mlua::Function| {
-                    lua.set_named_registry_value(&Self::listener_key(&event), callback)
-                })
+                Self::listener_registrar(lua, event)
             })?,
         )
     }
 
+    /// The function `on "<event>"` returns. It takes the handler, or, for
+    /// a queue, topic or database, an options table first (`on "queue:jobs"
+    /// { batch = 50 } (fn)`), in which case it stores the batching and
+    /// hands back a registrar for the handler. A cron event's table is
+    /// its zone and catch-up policy instead.
+    fn listener_registrar(lua: &Lua, event: String) -> mlua::Result<mlua::Function> {
+        lua.create_function(move |lua, argument: mlua::Value| match argument {
+            mlua::Value::Function(callback) => {
+                lua.set_named_registry_value(&Self::listener_key(&event), callback)?;
+                Ok(mlua::Value::Nil)
+            }
+            mlua::Value::Table(options) if event.starts_with("cron:") => {
+                let policy = crate::platform::cron::CronPolicy::from_table(&event, &options)
+                    .map_err(mlua::Error::RuntimeError)?;
+
+                let stored = lua.create_table()?;
+                stored.set("tz", policy.tz.name())?;
+                stored.set("catch_up", policy.catch_up.as_str())?;
+                lua.set_named_registry_value(&Self::cron_policy_key(&event), stored)?;
+
+                Ok(mlua::Value::Function(Self::listener_registrar(
+                    lua,
+                    event.clone(),
+                )?))
+            }
+            mlua::Value::Table(options) => {
+                let Some(queue) = event
+                    .strip_prefix("queue:")
+                    .or_else(|| event.strip_prefix("topic:"))
+                    .or_else(|| event.strip_prefix("database:"))
+                else {
+                    return Err(mlua::Error::RuntimeError(format!(
+                        "on \"{event}\" takes no options; only queue, topic, database and cron \
+                         events do."
+                    )));
+                };
+                let batching = crate::platform::queue::Batching::from_table(queue, &options)
+                    .map_err(mlua::Error::RuntimeError)?;
+
+                let stored = lua.create_table()?;
+                stored.set("size", batching.size)?;
+                stored.set("max_wait_ms", batching.max_wait_ms)?;
+                lua.set_named_registry_value(&Self::batching_key(&event), stored)?;
+
+                Ok(mlua::Value::Function(Self::listener_registrar(
+                    lua,
+                    event.clone(),
+                )?))
+            }
+            _ => Err(mlua::Error::RuntimeError(format!(
+                "on \"{event}\" takes a handler function."
+            ))),
+        })
+    }
+
     /// The listener key prefix workflow definitions register under; the
     /// platform class fires `workflow:<definition>` when a run executes.
     pub const WORKFLOW_EVENT_PREFIX: &'static str = "workflow:";
@@ -530,19 +727,38 @@ impl ActiasRuntime {
                     declarations.workflows.push(name.clone());
                 }
 
-                lua.create_function(move |lua, callback: mlua::Function| {
-                    lua.set_named_registry_value(
-                        &Self::listener_key(&format!("{}{name}", Self::WORKFLOW_EVENT_PREFIX)),
-                        callback,
-                    )?;
-                    // The declaration hands back the same handle
-                    // `workflows "name"` mints for cross-script callers.
-                    crate::extensions::objects::workflow_definition_handle(lua, name.clone())
-                })
+                Self::workflow_registrar(lua, name)
             })?,
         )
     }
 
+    /// The function `workflow "name"` returns. It takes the body, or
+    /// first an options table (`workflow "name" { schedule = "0 3 * * *"
+    /// } (fn)`): the schedule is the contract's business, extracted at
+    /// publish, so here the table only hands back the body's registrar.
+    fn workflow_registrar(lua: &Lua, name: String) -> mlua::Result<mlua::Function> {
+        lua.create_function(move |lua, argument: mlua::Value| match argument {
+            mlua::Value::Function(callback) => {
+                lua.set_named_registry_value(
+                    &Self::listener_key(&format!("{}{name}", Self::WORKFLOW_EVENT_PREFIX)),
+                    callback,
+                )?;
+                // The declaration hands back the same handle
+                // `workflows "name"` mints for cross-script callers.
+                Ok(mlua::Value::Table(
+                    crate::extensions::objects::workflow_definition_handle(lua, name.clone())?,
+                ))
+            }
+            mlua::Value::Table(_) => Ok(mlua::Value::Function(Self::workflow_registrar(
+                lua,
+                name.clone(),
+            )?)),
+            _ => Err(mlua::Error::RuntimeError(format!(
+                "workflow \"{name}\" takes a body function."
+            ))),
+        })
+    }
+
     /// Table of modules loaded so far, keyed by [`module_key`].
     ///
     /// # Errors
@@ -1135,19 +1351,21 @@ mod tests {
     }
 
     impl crate::extensions::determinism::Determinism for Scripted {
-        fn time(&self) -> i64 {
-            self.times
+        fn time(&self) -> Result<i64, String> {
+            Ok(self
+                .times
                 .lock()
                 .expect("no poison")
                 .pop_front()
-                .unwrap_or(0)
+                .unwrap_or(0))
         }
-        fn uuid(&self) -> String {
-            self.uuids
+        fn uuid(&self) -> Result<String, String> {
+            Ok(self
+                .uuids
                 .lock()
                 .expect("no poison")
                 .pop_front()
-                .unwrap_or_default()
+                .unwrap_or_default())
         }
         fn random(&self) -> f64 {
             // xorshift64*, stepped per draw: engine-independent and
@@ -1438,6 +1656,10 @@ mod tests {
                     queues: vec![],
                     workflows: vec![],
                     workflow_steps: vec![],
+                    queue_policies: vec![],
+                    topics: vec![],
+                    workflow_schedules: vec![],
+                    workflow_retentions: vec![],
                 }),
                 ..Default::default()
             }),
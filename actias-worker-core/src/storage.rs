@@ -13,6 +13,9 @@ use std::path::Path;
 /// flush step. Checkpoint trims the WAL so files stay small.
 pub struct SqliteStorage {
     connection: rusqlite::Connection,
+    /// Whether [`Self::watch_changes`] is running, which changes what
+    /// the script guard answers for deletes.
+    watching: bool,
 }
 
 impl SqliteStorage {
@@ -30,7 +33,10 @@ impl SqliteStorage {
             .pragma_update(None, "synchronous", "FULL")
             .map_err(|e| e.to_string())?;
 
-        Ok(Self { connection })
+        Ok(Self {
+            connection,
+            watching: false,
+        })
     }
 
     /// Opens the file read-only, for reads that bypass the owner's
@@ -44,7 +50,10 @@ impl SqliteStorage {
             rusqlite::Connection::open_with_flags(path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)
                 .map_err(|e| e.to_string())?;
 
-        Ok(Self { connection })
+        Ok(Self {
+            connection,
+            watching: false,
+        })
     }
 
     /// An in-memory database, for tests and local fakes.
@@ -54,6 +63,7 @@ impl SqliteStorage {
     pub fn in_memory() -> Result<Self, String> {
         Ok(Self {
             connection: rusqlite::Connection::open_in_memory().map_err(|e| e.to_string())?,
+            watching: false,
         })
     }
 }
@@ -119,40 +129,280 @@ impl SqliteStorage {
         result
     }
 
-    /// Migration names already applied to this database, sorted.
+    /// Runs a restore's dump script under the dump guard: platform tables
+    /// and the version marker are fair game, because a dump carries them,
+    /// but nothing may reach past this file.
+    ///
+    /// # Errors
+    /// Returns SQLite's message.
+    pub fn exec_dump(&mut self, sql: &str) -> Result<(), String> {
+        self.connection.authorizer(Some(dump_authorizer));
+        let result = self
+            .connection
+            .execute_batch(sql)
+            .map_err(|e| e.to_string());
+        self.connection.authorizer(
+            None::<fn(rusqlite::hooks::AuthContext<'_>) -> rusqlite::hooks::Authorization>,
+        );
+        result
+    }
+
+    /// Runs `run` with SQLite's update hook watching, returning its result
+    /// and every row it inserted, updated or deleted in a user table, in
+    /// the order SQLite touched them. The hook fires mid-statement, when
+    /// the connection may not be used, so it records only where each row
+    /// lives; reading the row back is the caller's job, after `run`. A
+    /// deleted row is gone by then, so for tables keyed by something other
+    /// than the rowid a temp trigger keeps its key on the way out (see
+    /// [`Self::arm_key_triggers`]). Reserved tables are skipped, WITHOUT
+    /// ROWID tables never fire the hook at all, and a row an `INSERT OR
+    /// REPLACE` displaces is not reported, only its replacement.
+    pub fn watch_changes<T>(
+        &mut self,
+        run: impl FnOnce(&mut Self) -> Result<T, String>,
+    ) -> (Result<T, String>, Vec<RowChange>) {
+        use rusqlite::hooks::Action;
+
+        let armed = match self.arm_key_triggers() {
+            Ok(armed) => armed,
+            Err(error) => return (Err(error), Vec::new()),
+        };
+
+        let seen = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        self.connection.update_hook(Some(
+            move |action: Action, database: &str, table: &str, rowid: i64| {
+                let op = match action {
+                    Action::SQLITE_INSERT => ChangeOp::Insert,
+                    Action::SQLITE_UPDATE => ChangeOp::Update,
+                    Action::SQLITE_DELETE => ChangeOp::Delete,
+                    _ => return,
+                };
+                if database != "main" || table.starts_with("__actias_") {
+                    return;
+                }
+                if let Ok(mut seen) = sink.lock() {
+                    seen.push(RowChange {
+                        op,
+                        table: table.to_owned(),
+                        rowid,
+                        key: None,
+                    });
+                }
+            },
+        ));
+        self.watching = true;
+        let result = run(self);
+        self.watching = false;
+        self.connection
+            .update_hook(None::<fn(Action, &str, &str, i64)>);
+
+        let mut changes = seen
+            .lock()
+            .map(|mut seen| std::mem::take(&mut *seen))
+            .unwrap_or_default();
+        match self
+            .disarm_key_triggers(armed)
+            .and_then(|()| self.take_deleted_keys())
+        {
+            Ok(mut keys) => {
+                for change in &mut changes {
+                    if change.op == ChangeOp::Delete {
+                        change.key = keys.remove(&(change.table.clone(), change.rowid));
+                    }
+                }
+            }
+            Err(error) => return (Err(error), changes),
+        }
+        (result, changes)
+    }
+
+    /// Puts a temp `AFTER DELETE` trigger on every table keyed by
+    /// something other than its rowid, copying the leaving row's key into
+    /// [`DELETED_KEYS`], and answers their names. They live for one
+    /// watched statement: armed fresh, they always match the table as it
+    /// is, and deletes nobody watches never pay for them. Temp objects are
+    /// this connection's alone and never reach the file.
+    fn arm_key_triggers(&mut self) -> Result<Vec<String>, String> {
+        let connection = &self.connection;
+        connection
+            .execute(
+                "CREATE TEMP TABLE IF NOT EXISTS __actias_deleted_keys \
+                 (tbl TEXT NOT NULL, row INTEGER NOT NULL, key TEXT NOT NULL)",
+                [],
+            )
+            .map_err(|e| e.to_string())?;
+
+        let columns: Vec<(String, String, String)> = {
+            let mut statement = connection
+                .prepare(
+                    "SELECT l.name, p.name, p.type \
+                     FROM pragma_table_list AS l JOIN pragma_table_info(l.name) AS p \
+                     WHERE l.schema = 'main' AND l.type = 'table' AND l.wr = 0 AND p.pk > 0 \
+                     AND l.name NOT LIKE 'sqlite\\_%' ESCAPE '\\' \
+                     AND l.name NOT LIKE '\\_\\_actias\\_%' ESCAPE '\\' \
+                     ORDER BY l.name, p.pk",
+                )
+                .map_err(|e| e.to_string())?;
+            statement
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+                .map_err(|e| e.to_string())?
+                .collect::<Result<_, _>>()
+                .map_err(|e| e.to_string())?
+        };
+        let mut keyed: Vec<(String, Vec<(String, String)>)> = Vec::new();
+        for (table, column, kind) in columns {
+            match keyed.last_mut() {
+                Some((last, key)) if *last == table => key.push((column, kind)),
+                _ => keyed.push((table, vec![(column, kind)])),
+            }
+        }
+
+        // Identifier interpolation is safe here: the names came from
+        // SQLite's own table list, quoted against exotic ones.
+        let quote = |name: &str| format!("\"{}\"", name.replace('"', "\"\""));
+        let mut armed = Vec::new();
+        for (table, key) in keyed {
+            // A lone INTEGER PRIMARY KEY is the rowid; the hook has it.
+            if key.len() == 1 && key[0].1.eq_ignore_ascii_case("INTEGER") {
+                continue;
+            }
+            let name = format!("{KEY_TRIGGER_PREFIX}{}", armed.len());
+            let key = key
+                .iter()
+                .map(|(column, _)| format!("OLD.{}", quote(column)))
+                .collect::<Vec<_>>()
+                .join(", ");
+            connection
+                .execute(&format!("DROP TRIGGER IF EXISTS temp.{name}"), [])
+                .map_err(|e| e.to_string())?;
+            connection
+                .execute(
+                    &format!(
+                        "CREATE TEMP TRIGGER {name} AFTER DELETE ON main.{} BEGIN \
+                         INSERT INTO __actias_deleted_keys VALUES ('{}', OLD.rowid, json_array({key})); \
+                         END",
+                        quote(&table),
+                        table.replace('\'', "''"),
+                    ),
+                    [],
+                )
+                .map_err(|e| e.to_string())?;
+            armed.push(name);
+        }
+        Ok(armed)
+    }
+
+    /// Drops what [`Self::arm_key_triggers`] armed; one whose table the
+    /// statement dropped is gone already.
+    fn disarm_key_triggers(&mut self, armed: Vec<String>) -> Result<(), String> {
+        for name in armed {
+            self.connection
+                .execute(&format!("DROP TRIGGER IF EXISTS temp.{name}"), [])
+                .map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+
+    /// Empties [`DELETED_KEYS`], answering each key by its table and rowid.
+    fn take_deleted_keys(
+        &mut self,
+    ) -> Result<std::collections::HashMap<(String, i64), Vec<serde_json::Value>>, String> {
+        let rows: Vec<(String, i64, String)> = {
+            let mut statement = self
+                .connection
+                .prepare("SELECT tbl, row, key FROM __actias_deleted_keys")
+                .map_err(|e| e.to_string())?;
+            statement
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+                .map_err(|e| e.to_string())?
+                .collect::<Result<_, _>>()
+                .map_err(|e| e.to_string())?
+        };
+        self.connection
+            .execute("DELETE FROM __actias_deleted_keys", [])
+            .map_err(|e| e.to_string())?;
+        rows.into_iter()
+            .map(|(table, rowid, key)| {
+                let key = serde_json::from_str(&key).map_err(|e| e.to_string())?;
+                Ok(((table, rowid), key))
+            })
+            .collect()
+    }
+
+    /// Migrations already applied to this database, sorted by name, each
+    /// with the blake3 of the content it ran with; rows recorded before
+    /// checksums were kept have none until [`Self::adopt_checksum`].
     ///
     /// # Errors
     /// Returns SQLite's message.
-    pub fn applied_migrations(&mut self) -> Result<Vec<String>, String> {
+    pub fn applied_migrations(&mut self) -> Result<Vec<(String, Option<String>)>, String> {
         self.connection
             .execute(
-                "CREATE TABLE IF NOT EXISTS __actias_migrations (name TEXT PRIMARY KEY)",
+                "CREATE TABLE IF NOT EXISTS __actias_migrations \
+                 (name TEXT PRIMARY KEY, checksum TEXT)",
                 [],
             )
             .map_err(|e| e.to_string())?;
+        // A table from before checksums gains the column in place; the
+        // database class stamps no schema version, so the shape decides.
+        let checksummed: bool = self
+            .connection
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM pragma_table_info('__actias_migrations') \
+                 WHERE name = 'checksum')",
+                [],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+        if !checksummed {
+            self.connection
+                .execute(
+                    "ALTER TABLE __actias_migrations ADD COLUMN checksum TEXT",
+                    [],
+                )
+                .map_err(|e| e.to_string())?;
+        }
 
         let mut statement = self
             .connection
-            .prepare("SELECT name FROM __actias_migrations ORDER BY name")
+            .prepare("SELECT name, checksum FROM __actias_migrations ORDER BY name")
             .map_err(|e| e.to_string())?;
-        let names = statement
-            .query_map([], |row| row.get::<_, String>(0))
+        let applied = statement
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
             .map_err(|e| e.to_string())?
             .collect::<Result<Vec<_>, _>>()
             .map_err(|e| e.to_string())?;
-        Ok(names)
+        Ok(applied)
+    }
+
+    /// Records one migration as applied with its content's checksum;
+    /// rides the call's transaction, so a failed migration records
+    /// nothing.
+    ///
+    /// # Errors
+    /// Returns SQLite's message.
+    pub fn record_migration(&mut self, name: &str, checksum: &str) -> Result<(), String> {
+        self.connection
+            .execute(
+                "INSERT INTO __actias_migrations (name, checksum) VALUES (?, ?)",
+                rusqlite::params![name, checksum],
+            )
+            .map_err(|e| e.to_string())?;
+        Ok(())
     }
 
-    /// Records one migration as applied; rides the call's transaction, so
-    /// a failed migration records nothing.
+    /// Stamps a checksum on a migration applied before checksums were
+    /// kept: the content deployed when it is first checked is taken as
+    /// the content it ran with.
     ///
     /// # Errors
     /// Returns SQLite's message.
-    pub fn record_migration(&mut self, name: &str) -> Result<(), String> {
+    pub fn adopt_checksum(&mut self, name: &str, checksum: &str) -> Result<(), String> {
         self.connection
             .execute(
-                "INSERT INTO __actias_migrations (name) VALUES (?)",
-                rusqlite::params![name],
+                "UPDATE __actias_migrations SET checksum = ? WHERE name = ? AND checksum IS NULL",
+                rusqlite::params![checksum, name],
             )
             .map_err(|e| e.to_string())?;
         Ok(())
@@ -315,6 +565,17 @@ impl SqliteStorage {
         Ok(())
     }
 
+    /// The guard script SQL runs under right now.
+    fn script_guard(
+        &self,
+    ) -> fn(rusqlite::hooks::AuthContext<'_>) -> rusqlite::hooks::Authorization {
+        if self.watching {
+            watched_authorizer
+        } else {
+            script_authorizer
+        }
+    }
+
     /// The bare connection, for platform-owned statements. The script
     /// guard exists only around script-issued SQL; platform modules drive
     /// the connection directly, the way the alarm and migration helpers
@@ -335,10 +596,48 @@ impl SqliteStorage {
     }
 }
 
+/// What a watched statement did to a row.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChangeOp {
+    Insert,
+    Update,
+    Delete,
+}
+
+impl ChangeOp {
+    /// The name listeners see.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Insert => "insert",
+            Self::Update => "update",
+            Self::Delete => "delete",
+        }
+    }
+}
+
+/// One row a watched statement touched, as the update hook reports it:
+/// where the row lives, not what it holds.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RowChange {
+    pub op: ChangeOp,
+    pub table: String,
+    pub rowid: i64,
+    /// A deleted row's primary key values in key order, kept on its way
+    /// out; only for tables keyed by something other than the rowid.
+    pub key: Option<Vec<serde_json::Value>>,
+}
+
 /// The alarm table's name, for existence probes; must match the DDL in
 /// [`SqliteStorage::ensure_meta`].
 const ALARM_TABLE: &str = "__actias_alarm";
 
+/// The temp table deleted rows leave their keys in while watched.
+const DELETED_KEYS: &str = "__actias_deleted_keys";
+
+/// What [`SqliteStorage::arm_key_triggers`] names its triggers with; a
+/// script may neither make nor drop one.
+const KEY_TRIGGER_PREFIX: &str = "__actias_key_";
+
 /// What script-issued SQL may do. Platform paths (the dispatch
 /// transaction, meta tables, pragmas at open) run without this guard;
 /// everything a script writes runs under it.
@@ -351,8 +650,38 @@ fn script_authorizer(context: rusqlite::hooks::AuthContext<'_>) -> rusqlite::hoo
         // The platform owns transaction boundaries; a script BEGIN would
         // corrupt the all-or-nothing guarantee of its own call.
         AuthAction::Transaction { .. } => return Authorization::Deny,
-        // Pragmas can flip durability off or reset the init marker.
-        AuthAction::Pragma { .. } => return Authorization::Deny,
+        // Pragmas can flip durability off or reset the init marker; FTS5
+        // reads `data_version` on its own, which changes nothing.
+        AuthAction::Pragma {
+            pragma_name,
+            pragma_value,
+        } => {
+            return if is_data_version_read(pragma_name, *pragma_value) {
+                Authorization::Allow
+            } else {
+                Authorization::Deny
+            };
+        }
+
+        // A key trigger's own write; nothing a script writes runs as one.
+        AuthAction::Insert { table_name }
+            if *table_name == DELETED_KEYS
+                && context.database_name == Some("temp")
+                && context
+                    .accessor
+                    .is_some_and(|trigger| trigger.starts_with(KEY_TRIGGER_PREFIX)) =>
+        {
+            return Authorization::Allow;
+        }
+        // Reserved names are the platform's, triggers included.
+        AuthAction::CreateTrigger { trigger_name, .. }
+        | AuthAction::CreateTempTrigger { trigger_name, .. }
+        | AuthAction::DropTrigger { trigger_name, .. }
+        | AuthAction::DropTempTrigger { trigger_name, .. }
+            if trigger_name.starts_with("__actias_") =>
+        {
+            return Authorization::Deny;
+        }
 
         AuthAction::Read { table_name, .. } => table_name,
         AuthAction::Insert { table_name } => table_name,
@@ -361,9 +690,15 @@ fn script_authorizer(context: rusqlite::hooks::AuthContext<'_>) -> rusqlite::hoo
         AuthAction::CreateTable { table_name } => table_name,
         AuthAction::CreateIndex { table_name, .. } => table_name,
         AuthAction::CreateTrigger { table_name, .. } => table_name,
+        AuthAction::CreateTempTable { table_name } => table_name,
+        AuthAction::CreateTempIndex { table_name, .. } => table_name,
+        AuthAction::CreateTempTrigger { table_name, .. } => table_name,
         AuthAction::DropTable { table_name } => table_name,
         AuthAction::DropIndex { table_name, .. } => table_name,
         AuthAction::DropTrigger { table_name, .. } => table_name,
+        AuthAction::DropTempTable { table_name } => table_name,
+        AuthAction::DropTempIndex { table_name, .. } => table_name,
+        AuthAction::DropTempTrigger { table_name, .. } => table_name,
         AuthAction::AlterTable { table_name, .. } => table_name,
 
         _ => return Authorization::Allow,
@@ -377,6 +712,56 @@ fn script_authorizer(context: rusqlite::hooks::AuthContext<'_>) -> rusqlite::hoo
     Authorization::Allow
 }
 
+/// [`script_authorizer`] while changes are watched. A DELETE without a
+/// WHERE would take SQLite's truncate shortcut, which empties the table
+/// without firing the update hook; answering IGNORE for the delete is
+/// SQLite's documented switch that turns the shortcut off, and the rows
+/// are still deleted, one by one.
+fn watched_authorizer(context: rusqlite::hooks::AuthContext<'_>) -> rusqlite::hooks::Authorization {
+    use rusqlite::hooks::{AuthAction, Authorization};
+
+    match script_authorizer(context) {
+        Authorization::Allow if matches!(context.action, AuthAction::Delete { .. }) => {
+            Authorization::Ignore
+        }
+        verdict => verdict,
+    }
+}
+
+/// What a restored dump may do: rebuild any table, reserved ones
+/// included, and set `user_version`; never attach another file, open its
+/// own transaction (the restore owns it), flip another pragma or load an
+/// extension. The dump is operator-supplied, but the file it lands in is
+/// on a shared node.
+fn dump_authorizer(context: rusqlite::hooks::AuthContext<'_>) -> rusqlite::hooks::Authorization {
+    use rusqlite::hooks::{AuthAction, Authorization};
+
+    match &context.action {
+        AuthAction::Attach { .. } | AuthAction::Detach { .. } => Authorization::Deny,
+        AuthAction::Transaction { .. } => Authorization::Deny,
+        AuthAction::Pragma {
+            pragma_name,
+            pragma_value,
+        } if !pragma_name.eq_ignore_ascii_case("user_version")
+            && !is_data_version_read(pragma_name, *pragma_value) =>
+        {
+            Authorization::Deny
+        }
+        AuthAction::Function { function_name }
+            if function_name.eq_ignore_ascii_case("load_extension") =>
+        {
+            Authorization::Deny
+        }
+        _ => Authorization::Allow,
+    }
+}
+
+/// A bare `PRAGMA data_version`: read-only, and what FTS5 tables issue
+/// on every access, so the authorizers let it through.
+fn is_data_version_read(name: &str, value: Option<&str>) -> bool {
+    name.eq_ignore_ascii_case("data_version") && value.is_none()
+}
+
 /// One json parameter as something SQLite can bind.
 fn bind(value: &serde_json::Value) -> Result<rusqlite::types::Value, String> {
     use rusqlite::types::Value;
@@ -427,7 +812,7 @@ impl SqliteStorage {
         let bound: Vec<rusqlite::types::Value> =
             params.iter().map(bind).collect::<Result<_, _>>()?;
 
-        self.connection.authorizer(Some(script_authorizer));
+        self.connection.authorizer(Some(self.script_guard()));
         let result = self
             .connection
             .execute(sql, rusqlite::params_from_iter(bound))
@@ -452,7 +837,7 @@ impl SqliteStorage {
         let bound: Vec<rusqlite::types::Value> =
             params.iter().map(bind).collect::<Result<_, _>>()?;
 
-        self.connection.authorizer(Some(script_authorizer));
+        self.connection.authorizer(Some(self.script_guard()));
         let prepared = self.connection.prepare(sql);
         self.connection.authorizer(
             None::<fn(rusqlite::hooks::AuthContext<'_>) -> rusqlite::hooks::Authorization>,
@@ -645,6 +1030,82 @@ mod tests {
         owner.rollback().expect("rolls back");
     }
 
+    #[test]
+    fn a_watched_statement_reports_the_rows_it_touched() {
+        let mut storage = SqliteStorage::in_memory().expect("opens");
+        storage
+            .exec("CREATE TABLE t (id INTEGER PRIMARY KEY, s TEXT)", &[])
+            .expect("creates");
+        storage.save_alarm(1, "c", "n", "k").expect("platform row");
+
+        let (result, changes) = storage.watch_changes(|storage| {
+            storage.exec("INSERT INTO t (id, s) VALUES (1, 'a'), (2, 'b')", &[])?;
+            storage.exec("UPDATE t SET s = 'c' WHERE id = 2", &[])?;
+            storage.exec("DELETE FROM t WHERE id = 1", &[])?;
+            storage.clear_alarm()?;
+            // No WHERE: the truncate shortcut would skip the hook.
+            storage.exec("DELETE FROM t", &[])
+        });
+        result.expect("statements run");
+
+        let seen: Vec<(ChangeOp, &str, i64)> = changes
+            .iter()
+            .map(|change| (change.op, change.table.as_str(), change.rowid))
+            .collect();
+        assert_eq!(
+            seen,
+            vec![
+                (ChangeOp::Insert, "t", 1),
+                (ChangeOp::Insert, "t", 2),
+                (ChangeOp::Update, "t", 2),
+                (ChangeOp::Delete, "t", 1),
+                (ChangeOp::Delete, "t", 2),
+            ],
+            "reserved tables stay out of it"
+        );
+    }
+
+    #[test]
+    fn a_watched_delete_keeps_a_text_key_on_its_way_out() {
+        let mut storage = SqliteStorage::in_memory().expect("opens");
+        storage
+            .exec(
+                "CREATE TABLE t (a TEXT, b INTEGER, PRIMARY KEY (a, b))",
+                &[],
+            )
+            .expect("creates");
+        storage
+            .exec("INSERT INTO t VALUES ('x', 1), ('y', 2)", &[])
+            .expect("inserts");
+
+        let (result, changes) =
+            storage.watch_changes(|storage| storage.exec("DELETE FROM t WHERE a = 'x'", &[]));
+        result.expect("deletes");
+        assert_eq!(
+            changes[0].key,
+            Some(vec![serde_json::json!("x"), serde_json::json!(1)])
+        );
+
+        // Armed fresh per statement, so a renamed column still reports.
+        storage
+            .exec("ALTER TABLE t RENAME COLUMN a TO c", &[])
+            .expect("renames");
+        let (result, changes) = storage.watch_changes(|storage| storage.exec("DELETE FROM t", &[]));
+        result.expect("deletes");
+        assert_eq!(changes.len(), 1, "{changes:?}");
+        assert_eq!(
+            changes[0].key,
+            Some(vec![serde_json::json!("y"), serde_json::json!(2)])
+        );
+
+        // Nor may a script make or drop a key trigger of its own.
+        let refused = storage.exec(
+            "CREATE TEMP TRIGGER __actias_key_x AFTER INSERT ON t BEGIN SELECT 1; END",
+            &[],
+        );
+        assert!(refused.is_err(), "{refused:?}");
+    }
+
     #[test]
     fn an_unbindable_parameter_is_refused() {
         let mut storage = SqliteStorage::in_memory().expect("opens");
@@ -44,8 +44,13 @@ pub struct Config {
     pub queue_max_attempts: i64,
     /// First queue retry delay in milliseconds; doubles per attempt.
     pub queue_backoff_base_ms: i64,
+    /// Ceiling the queue retry delay doubles up to, milliseconds.
+    pub queue_max_backoff_ms: i64,
     /// Seconds between cold-alarm sweeps of the object data dir.
     pub object_sweep_secs: u64,
+    /// Seconds a shutdown may spend handing resident objects off before
+    /// deregistering anyway; keep it inside the orchestrator's stop grace.
+    pub drain_timeout_secs: u64,
     /// Shared secret authenticating node-to-node object forwards.
     pub internal_token: String,
     /// Seconds a snapshot replica serves reads before refreshing.
@@ -98,7 +103,9 @@ impl Config {
             object_idle_secs: get_env_or("OBJECT_IDLE_SECS", 300),
             queue_max_attempts: get_env_or("QUEUE_MAX_ATTEMPTS", 5),
             queue_backoff_base_ms: get_env_or("QUEUE_BACKOFF_BASE_MS", 2000),
+            queue_max_backoff_ms: get_env_or("QUEUE_MAX_BACKOFF_MS", 3_600_000),
             object_sweep_secs: get_env_or("OBJECT_SWEEP_SECS", 30),
+            drain_timeout_secs: get_env_or("DRAIN_TIMEOUT_SECS", 8),
             // Development default; a deployment must set its own.
             internal_token: get_env_or("INTERNAL_TOKEN", "dev-internal-token".to_owned()),
             replica_ttl_secs: get_env_or("OBJECT_REPLICA_TTL_SECS", 30),
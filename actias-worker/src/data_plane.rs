@@ -12,12 +12,15 @@ use tonic::{Request, Response, Status};
 use actias_worker_core::extensions::objects::{CallerIdentity, ObjectTarget};
 use actias_worker_core::identity::ObjectKey;
 use actias_worker_core::platform::PlatformRead;
+use actias_worker_core::platform::database::Dump;
 use actias_worker_core::proto::node_registry::{GetLeaseRequest, GetNodeRequest};
 use actias_worker_core::proto::worker_data::worker_data_client::WorkerDataClient;
 use actias_worker_core::proto::worker_data::worker_data_server::WorkerData;
-use actias_worker_core::proto::worker_data::{CallResult, ObjectCall, ReadRequest, ReadValue};
+use actias_worker_core::proto::worker_data::{
+    CallResult, ObjectCall, ReadRequest, ReadValue, RestoreRequest, RestoreResult, restore_request,
+};
 
-use crate::routing::{ObjectRouting, fresh_replica_file, owner_prepared};
+use crate::routing::{ObjectRouting, ResolveError, fresh_replica_file, owner_prepared};
 use crate::server::AppState;
 
 /// The metadata key carrying the cluster-internal secret.
@@ -55,14 +58,31 @@ pub(crate) fn authed<T>(token: &str, message: T) -> Request<T> {
     request
 }
 
+/// The largest message the data plane sends or takes, either way. A dump
+/// or a restore carries a whole object file, and its SQL text spells
+/// blobs out at twice their size, so this leaves room for four files'
+/// worth plus the envelope rather than tonic's 4 MiB default.
+pub(crate) fn message_limit(max_object_bytes: u64) -> usize {
+    let limit = max_object_bytes
+        .saturating_mul(4)
+        .saturating_add(1024 * 1024);
+    usize::try_from(limit).unwrap_or(usize::MAX)
+}
+
 /// A client for one peer's data plane, over a cached lazy channel:
 /// a dead peer costs its caller the failure, never a held-up cache.
 pub(crate) async fn peer_client(
     state: &AppState,
     address: &str,
 ) -> Result<WorkerDataClient<Channel>, String> {
+    let limit = message_limit(state.object_db_max_bytes);
+    let client = |channel: Channel| {
+        WorkerDataClient::new(channel)
+            .max_decoding_message_size(limit)
+            .max_encoding_message_size(limit)
+    };
     if let Some(channel) = state.peers.get(address).await {
-        return Ok(WorkerDataClient::new(channel));
+        return Ok(client(channel));
     }
     let endpoint = Channel::from_shared(format!("http://{address}"))
         .map_err(|_| "The peer's address is not routable.".to_owned())?;
@@ -71,17 +91,18 @@ pub(crate) async fn peer_client(
         .peers
         .insert(address.to_owned(), channel.clone())
         .await;
-    Ok(WorkerDataClient::new(channel))
+    Ok(client(channel))
 }
 
-/// The read a request asks for; `sql` outranks `messages` outranks the
-/// class's default overview.
+/// The read a request asks for; `sql` outranks `dump` outranks
+/// `messages` outranks the class's default overview.
 // A Status-sized Err is the rpc surface's contract, not a choice.
 #[allow(clippy::result_large_err)]
 fn stats_read(request: &ReadRequest) -> Result<PlatformRead, Status> {
     match request.sql.clone() {
         Some(sql) => Ok(PlatformRead::Query { sql }),
-        None if request.messages => Ok(PlatformRead::QueueMessages),
+        None if request.dump => Ok(PlatformRead::Dump { raw: request.raw }),
+        None if request.messages => Ok(PlatformRead::messages_for_class(&request.class)),
         None => PlatformRead::stats_for_class(&request.class)
             .ok_or_else(|| Status::invalid_argument("No stats for that class.")),
     }
@@ -217,6 +238,7 @@ impl WorkerData for WorkerDataService {
     /// first hop). Method failures ride the envelope; they are the
     /// object's own user-safe errors.
     async fn dispatch(&self, request: Request<ObjectCall>) -> Result<Response<CallResult>, Status> {
+        let received = std::time::Instant::now();
         let call = request.into_inner();
 
         let answer = async {
@@ -249,6 +271,12 @@ impl WorkerData for WorkerDataService {
                             script: caller.script,
                             revision: caller.revision,
                         }),
+                        // Replica reads never forward; what arrives here
+                        // is always for the holder.
+                        consistency: None,
+                        // The budget is rebuilt against this node's clock.
+                        deadline: (call.budget_ms > 0)
+                            .then(|| received + std::time::Duration::from_millis(call.budget_ms)),
                     },
                     call.first_hop,
                 )
@@ -295,6 +323,61 @@ impl WorkerData for WorkerDataService {
         };
         self.read_routed(request, read).await
     }
+
+    /// One restore as a fenced takeover here, or on a first hop, once on
+    /// the live holder's node; a dump that does not load or a lost fence
+    /// is the caller's refusal, not a transport failure.
+    async fn restore(
+        &self,
+        request: Request<RestoreRequest>,
+    ) -> Result<Response<RestoreResult>, Status> {
+        let request = request.into_inner();
+        let dump = match request.dump.clone() {
+            Some(restore_request::Dump::Sql(sql)) => Dump::Sql(sql),
+            Some(restore_request::Dump::File(file)) => Dump::File(file),
+            None => return Err(Status::invalid_argument("The restore carries no dump.")),
+        };
+
+        let key = ObjectKey::received(&request.scope_id, &request.class, &request.name);
+        match crate::restore::restore(&self.state, &key, dump).await {
+            Ok(restored) => {
+                actias_common::tracing::info!(object = %key, epoch = restored.epoch, "object restored from a dump");
+                Ok(Response::new(RestoreResult {
+                    size_bytes: restored.size_bytes,
+                    epoch: restored.epoch,
+                }))
+            }
+            Err(ResolveError::Elsewhere(holder)) if request.first_hop => {
+                let node = self
+                    .state
+                    .registry
+                    .clone()
+                    .get_node(GetNodeRequest { node_id: holder })
+                    .await
+                    .map_err(|e| {
+                        Status::unavailable(format!(
+                            "The object's home could not be resolved: {}",
+                            e.message()
+                        ))
+                    })?
+                    .into_inner();
+                let mut client = peer_client(&self.state, &node.address)
+                    .await
+                    .map_err(Status::unavailable)?;
+                let forwarded = RestoreRequest {
+                    first_hop: false,
+                    ..request
+                };
+                client
+                    .restore(authed(&self.state.internal_token, forwarded))
+                    .await
+            }
+            Err(ResolveError::Elsewhere(_)) => Err(Status::failed_precondition(
+                "The object moved mid-restore; retry.",
+            )),
+            Err(ResolveError::Other(error)) => Err(Status::invalid_argument(error)),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -321,7 +404,7 @@ mod tests {
     }
 
     #[test]
-    fn the_read_selector_ranks_sql_over_messages_over_class() {
+    fn the_read_selector_ranks_sql_over_dump_over_messages_over_class() {
         let request = |sql: Option<&str>, messages: bool, class: &str| ReadRequest {
             scope_id: "p".into(),
             class: class.into(),
@@ -330,16 +413,30 @@ mod tests {
             messages,
             since: 0,
             first_hop: false,
+            dump: false,
+            raw: false,
         };
 
         assert!(matches!(
             stats_read(&request(Some("SELECT 1"), true, "__queue")),
             Ok(PlatformRead::Query { .. })
         ));
+        assert!(matches!(
+            stats_read(&ReadRequest {
+                dump: true,
+                raw: true,
+                ..request(None, true, "__database")
+            }),
+            Ok(PlatformRead::Dump { raw: true })
+        ));
         assert!(matches!(
             stats_read(&request(None, true, "__queue")),
             Ok(PlatformRead::QueueMessages)
         ));
+        assert!(matches!(
+            stats_read(&request(None, true, "__topic")),
+            Ok(PlatformRead::FanOutDead)
+        ));
         assert!(matches!(
             stats_read(&request(None, false, "__queue")),
             Ok(PlatformRead::QueueStats)
@@ -348,9 +445,13 @@ mod tests {
             stats_read(&request(None, false, "Warehouse")),
             Ok(PlatformRead::DatabaseOverview)
         ));
+        assert!(matches!(
+            stats_read(&request(None, false, "__cron")),
+            Ok(PlatformRead::CronState)
+        ));
         // Platform classes without an overview refuse instead of guessing.
         assert!(
-            stats_read(&request(None, false, "__cron"))
+            stats_read(&request(None, false, "__topic"))
                 .is_err_and(|status| status.code() == tonic::Code::InvalidArgument)
         );
     }
@@ -391,6 +492,7 @@ mod tests {
             chain: vec![],
             caller: None,
             first_hop: true,
+            budget_ms: 0,
         };
 
         let refused = client.dispatch(authed("wrong-token", call())).await;
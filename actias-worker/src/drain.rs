@@ -0,0 +1,99 @@
+//! The shutdown drain: the node leaves the cluster the way a rebalance
+//! moves one object, for every object at once. Without it a stopping
+//! node's leases sit held until heartbeat age-out, and every call to
+//! anything it hosted fails or waits in the meantime; a rolling deploy
+//! would pay that once per node.
+//!
+//! In order: the host closes, so nothing new is built here; every
+//! resident object drains its mailbox, ships, and releases its lease,
+//! each on its own, so one slow object never holds the rest; every local
+//! alarm is mirrored once more, save those of objects confirmed handed
+//! off, so a survivor's sweep fires the timers of failed and unfinished
+//! handoffs and of hibernated objects alike; and the node deregisters,
+//! freeing whatever the drain did not get to. A handed-off object is
+//! never mirrored: its new holder may already have armed its own alarm.
+//! Queue deliveries are object calls like any other, so draining the
+//! mailboxes covers them.
+//!
+//! Everything is bounded by the drain budget and best effort: what a
+//! timeout or a failed ship leaves behind is exactly what a crash would
+//! have, and the epoch fence and boot scan already cover that.
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use actias_common::tracing::{info, warn};
+use actias_worker_core::proto::node_registry::DeregisterRequest;
+
+use crate::server::AppState;
+
+/// The deregistration rpc's own bound, inside the drain budget.
+const GOODBYE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Drains this node out of the cluster; returns once it has, or once
+/// `budget` is spent on the objects.
+pub async fn run(state: AppState, budget: Duration) {
+    state.objects.close();
+
+    let resident = state.objects.resident_ids().await;
+    let total = resident.len();
+    let handoffs = resident.into_iter().map(|own_key| {
+        let state = state.clone();
+        tokio::spawn(async move {
+            let handed = crate::rebalance::hand_off(&state, &own_key).await;
+            (own_key, handed)
+        })
+    });
+    let handoffs: Vec<_> = handoffs.collect();
+
+    // Only a confirmed handoff passes the alarm on; anything else (a
+    // failure, one still in flight when the budget runs out, an object
+    // never resident) keeps its timer through the mirror.
+    let mut handed_off = HashSet::new();
+    let drained = tokio::time::timeout(budget, async {
+        for handoff in handoffs {
+            match handoff.await {
+                Ok((own_key, Ok(true))) => {
+                    handed_off.insert(own_key);
+                }
+                Ok((_, Ok(false))) | Err(_) => {}
+                Ok((own_key, Err(error))) => {
+                    warn!(%error, own_key, "drain handoff failed; deregistration frees it");
+                }
+            }
+        }
+    })
+    .await;
+    let handed = handed_off.len();
+    match drained {
+        Ok(()) => info!(handed, total, "resident objects drained"),
+        Err(_) => warn!(
+            handed,
+            total, "drain budget spent; the rest ride on deregistration"
+        ),
+    }
+
+    crate::sweeper::mirror_local_alarms(&state, |own_key| !handed_off.contains(own_key)).await;
+    goodbye(&state).await;
+}
+
+/// Deregistering frees every lease this node still holds at once, so
+/// the replacement claims them immediately instead of serving a ttl's
+/// worth of dead forwards. A crash still ages out.
+async fn goodbye(state: &AppState) {
+    let node_id = state
+        .node_identity
+        .read()
+        .expect("no poisoned lock")
+        .clone();
+    let Some(node_id) = node_id else { return };
+
+    let goodbye = state.registry.clone().deregister(DeregisterRequest {
+        node_id: node_id.clone(),
+    });
+    match tokio::time::timeout(GOODBYE_TIMEOUT, goodbye).await {
+        Ok(Ok(_)) => info!(node_id, "deregistered from the placement store"),
+        Ok(Err(error)) => warn!(%error, "deregistration failed; age-out covers it"),
+        Err(_) => warn!("deregistration timed out; age-out covers it"),
+    }
+}
@@ -3,14 +3,22 @@
 //! request gauge as load. A NOT_FOUND heartbeat means this node aged out
 //! (a long stall, a registry wipe); the loop registers again rather than
 //! dying, so membership self-heals.
+//!
+//! Each beat also reports the busiest resident objects, and the reply may
+//! ask for some of them to be handed off; those asks go to
+//! [`crate::rebalance`], off the beat, so a slow drain never delays
+//! liveness.
 
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU32, Ordering};
 use std::time::Duration;
 
 use actias_common::tracing::{info, warn};
+use actias_worker_core::identity::ObjectKey;
+use actias_worker_core::objects::ObjectHost;
 use actias_worker_core::proto::node_registry::node_registry_service_client::NodeRegistryServiceClient;
 use actias_worker_core::proto::node_registry::{HeartbeatRequest, RegisterNodeRequest};
+use tokio::sync::mpsc;
 use tonic::transport::Channel;
 
 /// Runs forever; spawn it and forget it.
@@ -19,6 +27,8 @@ pub async fn register_and_heartbeat(
     address: String,
     in_flight: Arc<AtomicU32>,
     identity: Arc<std::sync::RwLock<Option<String>>>,
+    objects: Arc<ObjectHost>,
+    handoffs: mpsc::Sender<String>,
 ) {
     loop {
         // Registration retries until it lands; the worker serves requests
@@ -48,15 +58,36 @@ pub async fn register_and_heartbeat(
         loop {
             tokio::time::sleep(interval).await;
 
+            // The registry speaks object ids; the host speaks keys. The
+            // map translates an ask back to what the host can drain.
+            let hot: Vec<(String, String)> = objects
+                .take_hottest(crate::rebalance::HOT_OBJECTS_REPORTED)
+                .await
+                .into_iter()
+                .filter_map(|own_key| {
+                    ObjectKey::parse(&own_key).map(|key| (key.object_id(), own_key))
+                })
+                .collect();
+
             let beat = client
                 .heartbeat(HeartbeatRequest {
                     node_id: node_id.clone(),
                     load: in_flight.load(Ordering::Relaxed),
+                    hot_objects: hot.iter().map(|(object_id, _)| object_id.clone()).collect(),
                 })
                 .await;
 
             match beat {
-                Ok(_) => {}
+                Ok(response) => {
+                    for object_id in response.into_inner().handoffs {
+                        let Some((_, own_key)) = hot.iter().find(|(id, _)| *id == object_id) else {
+                            continue;
+                        };
+                        // A full queue means handoffs are already behind;
+                        // dropping the ask is fine, the registry repeats.
+                        let _ = handoffs.try_send(own_key.clone());
+                    }
+                }
                 Err(status) if status.code() == tonic::Code::NotFound => {
                     warn!(node_id, "node aged out of the registry, re-registering");
                     break;
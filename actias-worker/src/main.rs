@@ -1,9 +1,12 @@
 mod blob_cache;
 mod config;
 mod data_plane;
+mod drain;
 mod heartbeat;
 mod metrics;
 mod object_store;
+mod rebalance;
+mod restore;
 mod routing;
 mod server;
 mod sweeper;
@@ -77,11 +80,15 @@ pub async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         .await?;
     let in_flight = std::sync::Arc::new(std::sync::atomic::AtomicU32::new(0));
     let node_identity = std::sync::Arc::new(std::sync::RwLock::new(None));
+    let objects = std::sync::Arc::new(actias_worker_core::objects::ObjectHost::default());
+    let (handoff_asks, handoff_queue) = tokio::sync::mpsc::channel(64);
     tokio::spawn(heartbeat::register_and_heartbeat(
         registry_client.clone(),
         config.node_address.clone(),
         in_flight.clone(),
         node_identity.clone(),
+        objects.clone(),
+        handoff_asks,
     ));
 
     let redis = redis::aio::ConnectionManager::new(
@@ -133,7 +140,7 @@ pub async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         secret_client,
         request_timeout: std::time::Duration::from_secs(config.request_timeout_secs),
         in_flight,
-        objects: std::sync::Arc::new(actias_worker_core::objects::ObjectHost::default()),
+        objects,
         metrics: std::sync::Arc::default(),
         armed_crons: std::sync::Arc::default(),
         object_data_dir: std::path::PathBuf::from(config.object_data_dir),
@@ -142,6 +149,7 @@ pub async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         queue_policy: actias_worker_core::platform::queue::QueuePolicy {
             max_attempts: config.queue_max_attempts,
             backoff_base_ms: config.queue_backoff_base_ms,
+            max_backoff_ms: config.queue_max_backoff_ms,
         },
         node_identity,
         registry: registry_client,
@@ -155,16 +163,25 @@ pub async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         std::time::Duration::from_secs(config.object_sweep_secs),
     ));
 
+    // Handoffs the registry asks for in heartbeat replies, one at a time.
+    tokio::spawn(rebalance::run(state.clone(), handoff_queue));
+
     // The data plane: object dispatch and typed reads, cluster-internal.
     // The registry address other nodes and the api dial is THIS listener.
+    // Dumps and restores carry whole files, past tonic's default limits.
     let grpc_addr = SocketAddr::from(([0, 0, 0, 0], config.grpc_port));
-    let data_plane = tonic::transport::Server::builder()
-        .add_service(
-            actias_worker_core::proto::worker_data::worker_data_server::WorkerDataServer::with_interceptor(
-                data_plane::WorkerDataService::new(state.clone()),
-                data_plane::require_internal_token(state.internal_token.clone()),
-            ),
+    let message_limit = data_plane::message_limit(state.object_db_max_bytes);
+    let data_service =
+        actias_worker_core::proto::worker_data::worker_data_server::WorkerDataServer::new(
+            data_plane::WorkerDataService::new(state.clone()),
         )
+        .max_decoding_message_size(message_limit)
+        .max_encoding_message_size(message_limit);
+    let data_plane = tonic::transport::Server::builder()
+        .add_service(tonic::service::interceptor::InterceptedService::new(
+            data_service,
+            data_plane::require_internal_token(state.internal_token.clone()),
+        ))
         .serve_with_shutdown(grpc_addr, shutdown_signal());
 
     let app = server::router(state.clone(), config.max_body_bytes);
@@ -174,36 +191,18 @@ pub async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let listener = tokio::net::TcpListener::bind(addr).await?;
     let http = axum::serve(listener, app).with_graceful_shutdown(shutdown_signal());
 
-    // The goodbye fires AT the shutdown signal, not after the drain:
-    // graceful drain waits on peers' persistent h2 channels and can
-    // outlive the sigkill window, and the node stops being routable the
-    // moment it stops accepting anyway. Deregistering frees this node's
-    // leases at once, so a deploy's replacement claims them immediately
-    // instead of serving a ttl's worth of dead forwards. Best effort; a
-    // crash still ages out, and the epoch fence covers the drain window.
-    let registry_for_goodbye = state.registry.clone();
-    let identity_for_goodbye = state.node_identity.clone();
-    let goodbye_task = tokio::spawn(async move {
-        shutdown_signal().await;
-        let node_id = identity_for_goodbye
-            .read()
-            .expect("no poisoned lock")
-            .clone();
-        let Some(node_id) = node_id else { return };
-        let mut registry = registry_for_goodbye;
-        let goodbye = registry.deregister(
-            actias_worker_core::proto::node_registry::DeregisterRequest {
-                node_id: node_id.clone(),
-            },
-        );
-        match tokio::time::timeout(std::time::Duration::from_secs(5), goodbye).await {
-            Ok(Ok(_)) => info!(node_id, "deregistered from the placement store"),
-            Ok(Err(error)) => {
-                actias_common::tracing::warn!(%error, "deregistration failed; age-out covers it")
-            }
-            Err(_) => {
-                actias_common::tracing::warn!("deregistration timed out; age-out covers it")
-            }
+    // The drain starts AT the shutdown signal, not after the listeners
+    // close: graceful shutdown waits on peers' persistent h2 channels and
+    // can outlive the sigkill window, and objects handed off while the
+    // last requests finish are exactly what keeps a rolling deploy from
+    // stalling callers. Late calls to a drained object find its lease
+    // free and claim it wherever they are.
+    let drain_budget = std::time::Duration::from_secs(config.drain_timeout_secs);
+    let drain_task = tokio::spawn({
+        let state = state.clone();
+        async move {
+            shutdown_signal().await;
+            drain::run(state, drain_budget).await;
         }
     });
 
@@ -213,11 +212,13 @@ pub async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         data_plane.await.map_err(anyhow::Error::from)
     },)?;
 
-    // A fast drain must not outrun the goodbye: when nothing holds the
-    // listeners open, main gets here in milliseconds and exiting now
-    // would kill the spawned deregistration mid-flight, silently. Its
-    // own 5s rpc timeout bounds this wait; the extra second is slack.
-    let _ = tokio::time::timeout(std::time::Duration::from_secs(6), goodbye_task).await;
+    // Fast listeners must not outrun the drain: when nothing holds them
+    // open, main gets here in milliseconds and exiting now would kill the
+    // handoffs and the deregistration mid-flight, silently. The drain
+    // budget plus the goodbye's own 5s rpc timeout bound this wait; the
+    // extra second is slack.
+    let _ =
+        tokio::time::timeout(drain_budget + std::time::Duration::from_secs(6), drain_task).await;
 
     Ok(())
 }
@@ -108,6 +108,41 @@ impl ObjectStore {
         Ok(true)
     }
 
+    /// Deletes the shipped snapshot and its manifest; the object will
+    /// never be restored again. Missing keys are not an error, so a purge
+    /// retried after a partial failure converges.
+    pub async fn forget(&self, object_id: &str) -> Result<(), String> {
+        // The manifest first: without it a restore finds nothing, even
+        // if the snapshot delete then fails.
+        for key in [Self::manifest_key(object_id), Self::snapshot_key(object_id)] {
+            self.client
+                .delete_object()
+                .bucket(&self.bucket)
+                .key(key)
+                .send()
+                .await
+                .map_err(|e| e.into_service_error().to_string())?;
+        }
+        Ok(())
+    }
+
+    /// Keeps a purged object's parting JSON under `archives/<path>.json`,
+    /// outside every object's own prefix, so forgetting the object never
+    /// touches it.
+    pub async fn archive(&self, path: &str, value: &serde_json::Value) -> Result<(), String> {
+        let bytes = serde_json::to_vec(value).map_err(|e| e.to_string())?;
+        self.client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(format!("archives/{path}.json"))
+            .content_type("application/json")
+            .body(bytes.into())
+            .send()
+            .await
+            .map_err(|e| e.into_service_error().to_string())?;
+        Ok(())
+    }
+
     async fn manifest(&self, object_id: &str) -> Result<Option<Manifest>, String> {
         let result = self
             .client
@@ -0,0 +1,91 @@
+//! The worker's half of rebalancing. The registry picks; this node
+//! obeys. Each heartbeat reports the busiest resident objects, and a
+//! reply naming some of them is an ask to hand them off: drain the
+//! mailbox (every queued call answers here, none is dropped), ship the
+//! file, release the lease. The next caller anywhere claims the object
+//! and restores the snapshot, which is the same path failover takes.
+//!
+//! Asks are advisory. An object that hibernated since the report, or
+//! whose ship fails, simply stays; the registry asks again after its
+//! cooldown if the node is still hot.
+
+use actias_common::tracing::{debug, info, warn};
+use actias_worker_core::identity::ObjectKey;
+use actias_worker_core::proto::node_registry::{GetLeaseRequest, ReleaseLeaseRequest};
+use tokio::sync::mpsc;
+
+use crate::server::AppState;
+
+/// Hot objects one heartbeat reports; the registry never asks for more
+/// than it was told about.
+pub const HOT_OBJECTS_REPORTED: usize = 8;
+
+/// Runs forever, one handoff at a time; spawn it and forget it.
+pub async fn run(state: AppState, mut asks: mpsc::Receiver<String>) {
+    while let Some(own_key) = asks.recv().await {
+        match hand_off(&state, &own_key).await {
+            Ok(true) => info!(own_key, "object handed off"),
+            Ok(false) => debug!(own_key, "handoff skipped; nothing to release"),
+            Err(error) => warn!(%error, own_key, "handoff abandoned; the object stays"),
+        }
+    }
+}
+
+/// One handoff. False when there was nothing to hand off: the object is
+/// not resident here, or the lease is no longer this node's.
+pub(crate) async fn hand_off(state: &AppState, own_key: &str) -> Result<bool, String> {
+    let key =
+        ObjectKey::parse(own_key).ok_or_else(|| format!("'{own_key}' is not an object key"))?;
+    let object_id = key.object_id();
+    let node_id = state
+        .node_identity
+        .read()
+        .expect("no poisoned lock")
+        .clone()
+        .ok_or_else(|| "This node has not finished registering.".to_owned())?;
+
+    // Held until the lease is gone: local callers wait rather than build
+    // a second vm over a file that is about to change hands.
+    let Some(_drained) = state.objects.begin_handoff(own_key).await else {
+        return Ok(false);
+    };
+
+    let lease = state
+        .registry
+        .clone()
+        .get_lease(GetLeaseRequest {
+            object_id: object_id.clone(),
+        })
+        .await
+        .map_err(|e| e.to_string())?
+        .into_inner();
+    if lease.node_id != node_id {
+        return Ok(false);
+    }
+
+    // Every write already shipped through the output gate; this ship is
+    // the insurance against one that failed and was only logged. The
+    // lease stays put unless it lands, because the next holder restores
+    // whatever the store has. Released as a handoff, so the registry
+    // places this node's own next claim on a peer instead of here.
+    let file = state.object_data_dir.join(key.db_file_name());
+    if file.exists() {
+        state
+            .object_store
+            .ship(&object_id, lease.epoch, &file)
+            .await?;
+    }
+
+    state
+        .registry
+        .clone()
+        .release_lease(ReleaseLeaseRequest {
+            object_id,
+            node_id,
+            handoff: true,
+        })
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(true)
+}
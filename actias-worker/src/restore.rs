@@ -0,0 +1,125 @@
+//! Restoring an object's storage from a dump, as a fenced takeover: this
+//! node claims the lease, takes the object out of service, ships the new
+//! file at its own epoch and only then swaps it in. The ship's fence is
+//! what keeps a zombie ex-holder from overwriting the restore; holding
+//! the object is what keeps a live vm from writing over it. The next call
+//! builds a fresh vm over the restored file, exactly like a rehoming.
+
+use std::path::{Path, PathBuf};
+
+use actias_worker_core::identity::ObjectKey;
+use actias_worker_core::platform::database::{Dump, load_dump};
+
+use crate::routing::{ObjectRouting, ResolveError, owner_prepared};
+use crate::server::AppState;
+
+/// What a landed restore reports.
+pub struct Restored {
+    pub size_bytes: u64,
+    /// The epoch the restored snapshot shipped at.
+    pub epoch: u64,
+}
+
+/// One restore. [`ResolveError::Elsewhere`] names a live holder the
+/// caller may forward to; nothing was touched in that case.
+pub(crate) async fn restore(
+    state: &AppState,
+    key: &ObjectKey,
+    dump: Dump,
+) -> Result<Restored, ResolveError> {
+    let file = state.object_data_dir.join(key.db_file_name());
+    let candidate = file.with_extension("restore");
+
+    // The candidate is built and checked before anything is claimed, so a
+    // dump that does not load costs the object nothing.
+    let max_bytes = state.object_db_max_bytes;
+    let built = tokio::task::spawn_blocking({
+        let candidate = candidate.clone();
+        move || {
+            discard(&candidate);
+            load_dump(&candidate, &dump, max_bytes)
+        }
+    })
+    .await
+    .map_err(|error| ResolveError::Other(format!("The restore task died: {error}")))?;
+
+    let result = match built {
+        Ok(()) => take_over(state, key, &file, &candidate).await,
+        Err(error) => Err(ResolveError::Other(error)),
+    };
+    if result.is_err() {
+        discard(&candidate);
+    }
+    result
+}
+
+/// The takeover itself, candidate already built.
+async fn take_over(
+    state: &AppState,
+    key: &ObjectKey,
+    file: &Path,
+    candidate: &Path,
+) -> Result<Restored, ResolveError> {
+    let owner = owner_prepared(state, key)
+        .await
+        .map_err(ResolveError::Other)?;
+
+    // An object mid-handoff is about to lose its lease; waiting it out
+    // means the claim below sees where it went.
+    state.objects.settled(&key.to_string()).await;
+    let lease = ObjectRouting::new(state, owner.clone())
+        .claim_lease(key, &owner.script.id)
+        .await
+        .map_err(ResolveError::Other)?;
+    if !lease.acquired {
+        return Err(ResolveError::Elsewhere(lease.node_id));
+    }
+
+    // Held until the new file is in place: the resident vm answers what
+    // it has queued and ends, and callers here wait instead of building
+    // a vm over the old file.
+    let Some(_held) = state.objects.begin_replace(&key.to_string()).await else {
+        return Err(ResolveError::Other(
+            "The object is moving between nodes; retry the restore.".to_owned(),
+        ));
+    };
+
+    // Shipped before the swap: a fenced ship leaves the live file alone,
+    // and a swapped file is always the one a rehoming would restore.
+    state
+        .object_store
+        .ship(&key.object_id(), lease.epoch, candidate)
+        .await
+        .map_err(ResolveError::Other)?;
+
+    let size_bytes = tokio::fs::metadata(candidate)
+        .await
+        .map(|metadata| metadata.len())
+        .unwrap_or_default();
+    // The old file's sidecars would replay its log over the new one.
+    for suffix in ["-wal", "-shm"] {
+        let _ = tokio::fs::remove_file(sidecar(file, suffix)).await;
+    }
+    tokio::fs::rename(candidate, file).await.map_err(|error| {
+        ResolveError::Other(format!("The restored file was not swapped in: {error}"))
+    })?;
+
+    Ok(Restored {
+        size_bytes,
+        epoch: lease.epoch,
+    })
+}
+
+/// A SQLite file's sidecar path: the file name plus `-wal` or `-shm`.
+fn sidecar(file: &Path, suffix: &str) -> PathBuf {
+    let mut path = file.to_path_buf().into_os_string();
+    path.push(suffix);
+    path.into()
+}
+
+/// Removes a candidate and its sidecars, whatever is there.
+fn discard(candidate: &Path) {
+    for suffix in ["", "-wal", "-shm"] {
+        let _ = std::fs::remove_file(sidecar(candidate, suffix));
+    }
+}
@@ -8,11 +8,15 @@ use std::sync::Arc;
 
 use actias_common::logging::script_log_channel;
 use actias_worker_core::extensions::log::LogPublisher;
-use actias_worker_core::extensions::objects::{ObjectRouter, ObjectTarget};
+use actias_worker_core::extensions::objects::{CALL_TIMED_OUT, ObjectRouter, ObjectTarget};
 use actias_worker_core::identity::ObjectKey;
+use actias_worker_core::objects::{NODE_DRAINING, ObjectError};
+use actias_worker_core::platform::database::ListenerCheck;
+use actias_worker_core::platform::topic::{SubscriberFuture, SubscriberLookup};
 use actias_worker_core::proto::node_registry::AcquireLeaseRequest;
 use actias_worker_core::proto::script_service::FindScriptRequest;
 use actias_worker_core::proto::script_service::GetRevisionRequest;
+use actias_worker_core::proto::script_service::ListSubscribersRequest;
 use actias_worker_core::proto::script_service::ResolveClassOwnerRequest;
 use actias_worker_core::proto::script_service::Script;
 use actias_worker_core::proto::script_service::find_script_request::Query;
@@ -128,6 +132,112 @@ pub async fn owner_prepared(
         .map_err(|e: Arc<String>| e.as_ref().clone())
 }
 
+/// A workflow vm over `prepared`. The enforced-determinism profile: the
+/// shared cell is both the replay cursor and the shim source. The
+/// instance file opens BEFORE the vm builds, because `secret`
+/// declarations run during construction and must see the run's pins;
+/// the task reopens the same file afterwards.
+async fn workflow_runtime(
+    state: &AppState,
+    prepared: Arc<PreparedRevision>,
+    file: &std::path::Path,
+    logs: Option<LogPublisher>,
+) -> mlua::Result<ActiasRuntime> {
+    let pins = actias_worker_core::platform::workflow::SecretPins::load(file)
+        .map_err(mlua::Error::RuntimeError)?;
+    let shared = Arc::new(actias_worker_core::platform::workflow::WfShared::default());
+    let runtime = ActiasRuntime::with_profile(
+        prepared,
+        state.clients.kv.clone(),
+        state.egress.clone(),
+        logs,
+        state.secret_client.clone(),
+        None,
+        actias_worker_core::runtime::VmProfile::Workflow {
+            source: shared.clone(),
+            secret_pins: Some(Arc::new(pins)),
+        },
+    )
+    .await?;
+    runtime.set_app_data(shared);
+    Ok(runtime)
+}
+
+/// What a run's migration builds its candidate from: a workflow vm on
+/// another revision of the owner script, loaded through the revision
+/// cache like any pinned one. No target means the owner's current code.
+fn revision_vm(
+    state: &AppState,
+    owner: Arc<PreparedRevision>,
+    file: std::path::PathBuf,
+) -> actias_worker_core::platform::workflow::RevisionVm {
+    let state = state.clone();
+    Arc::new(move |target: Option<String>| {
+        let state = state.clone();
+        let owner = owner.clone();
+        let file = file.clone();
+        Box::pin(async move {
+            let prepared = match target {
+                Some(revision_id) if revision_id != owner.revision_id => {
+                    cached_revision(&state, owner.script.clone(), revision_id)
+                        .await
+                        .map_err(|error| format!("The revision could not load: {error:#}"))?
+                }
+                _ => owner,
+            };
+            let revision_id = prepared.revision_id.clone();
+            let runtime = workflow_runtime(&state, prepared, &file, None)
+                .await
+                .map_err(|error| error.to_string())?;
+            Ok((runtime, revision_id))
+        })
+    })
+}
+
+/// Who a topic fans out to, asked of the script-service on every fan-out
+/// rather than cached: a subscriber that just published must not miss
+/// the next entry for a pointer ttl.
+fn subscriber_lookup(state: &AppState, project_id: &str) -> SubscriberLookup {
+    let client = state.clients.script.clone();
+    let project_id = project_id.to_owned();
+    Arc::new(move |event: String| {
+        let mut client = client.clone();
+        let project_id = project_id.clone();
+        Box::pin(async move {
+            Ok(client
+                .list_subscribers(ListSubscribersRequest {
+                    project_id,
+                    topic: String::new(),
+                    event,
+                })
+                .await
+                .map_err(|e| e.message().to_owned())?
+                .into_inner()
+                .script_ids)
+        })
+    })
+}
+
+/// [`subscriber_lookup`] through the listeners cache, for the check a
+/// database makes on every call; only whether anyone listens matters
+/// there, and that may lag a publish by the pointer ttl.
+fn listener_check(state: &AppState, project_id: &str) -> ListenerCheck {
+    let lookup = subscriber_lookup(state, project_id);
+    let listeners = state.caches.listeners.clone();
+    let project_id = project_id.to_owned();
+    ListenerCheck(Arc::new(move |event: String| -> SubscriberFuture {
+        let listeners = listeners.clone();
+        let key = format!("{project_id}/{event}");
+        let load = lookup(event);
+        Box::pin(async move {
+            listeners
+                .try_get_with(key, load)
+                .await
+                .map_err(|e: Arc<String>| e.as_ref().clone())
+        })
+    }))
+}
+
 /// Everything routing an object method call needs; one per node, shared
 /// by request vms and pinned vms alike, so objects call objects through
 /// exactly the machinery requests use. The prepared revision is the
@@ -143,6 +253,15 @@ pub struct ObjectRouting {
 /// Per-call budget for one object method, mirroring the request deadline.
 const OBJECT_CALL_BUDGET_SECS: u64 = 10;
 
+/// How long a call bounced by a draining holder waits before claiming
+/// the object itself; one handoff's worth, roughly.
+const DRAIN_RETRY_PAUSE: std::time::Duration = std::time::Duration::from_millis(250);
+
+/// Bounds on how long a purge that could not finish waits before the
+/// object's alarm tries it again.
+const PURGE_RETRY_MIN_MS: i64 = 60_000;
+const PURGE_RETRY_MAX_MS: i64 = 6 * 60 * 60 * 1000;
+
 /// Why an object could not be made resident here.
 pub enum ResolveError {
     /// A live incumbent holds the lease; forward the call to it.
@@ -171,7 +290,7 @@ impl ObjectRouting {
     /// The lease claim for one identity, spoken as this node. The claim
     /// carries the key's preimage plus the owner script as directory
     /// metadata.
-    async fn claim_lease(
+    pub(crate) async fn claim_lease(
         &self,
         key: &ObjectKey,
         owner_script_id: &str,
@@ -224,6 +343,17 @@ impl ObjectRouting {
             .await
             .map_err(ResolveError::Other)?;
 
+        // An object mid-handoff is about to lose its lease; waiting it out
+        // means the claim below sees where it went.
+        self.state.objects.settled(&key.to_string()).await;
+
+        // A draining node claims nothing: the caller goes wherever the
+        // object lives now, or hears to retry once this node has let go.
+        if self.state.objects.is_closed() && !self.state.objects.is_resident(&key.to_string()).await
+        {
+            return Err(self.while_draining(key).await);
+        }
+
         // A non-resident object needs the lease before anything spawns;
         // a resident one already holds it (leases live as long as we do).
         if !self.state.objects.is_resident(&key.to_string()).await {
@@ -241,6 +371,36 @@ impl ObjectRouting {
             .map_err(ResolveError::Other)
     }
 
+    /// Where a call to a non-resident object goes while this node drains:
+    /// to a live holder if there is one, otherwise back to the caller as
+    /// [`NODE_DRAINING`], which a forwarding peer answers by claiming the
+    /// object itself.
+    async fn while_draining(&self, key: &ObjectKey) -> ResolveError {
+        let own = self
+            .state
+            .node_identity
+            .read()
+            .expect("no poisoned lock")
+            .clone();
+        let holder = self
+            .state
+            .registry
+            .clone()
+            .get_lease(actias_worker_core::proto::node_registry::GetLeaseRequest {
+                object_id: key.object_id(),
+            })
+            .await
+            .ok()
+            .map(|lease| lease.into_inner().node_id);
+
+        match holder {
+            Some(holder) if own.as_deref() != Some(holder.as_str()) => {
+                ResolveError::Elsewhere(holder)
+            }
+            _ => ResolveError::Other(format!("{NODE_DRAINING}; retry the call.")),
+        }
+    }
+
     /// The spawn itself, lease already settled (or re-settled by the
     /// factory for the resident-revision-bump edge, where it is our own).
     async fn resolve_local(
@@ -315,30 +475,9 @@ impl ObjectRouting {
                 });
 
                 let runtime = if workflow {
-                    // The enforced-determinism profile: the shared cell is
-                    // both the replay cursor and the shim source. The
-                    // instance file opens BEFORE the vm builds, because
-                    // `secret` declarations run during construction and
-                    // must see the run's pins; the task reopens the same
-                    // file afterwards.
-                    let pins = actias_worker_core::platform::workflow::SecretPins::load(&file)
-                        .map_err(mlua::Error::RuntimeError)?;
-                    let shared =
-                        Arc::new(actias_worker_core::platform::workflow::WfShared::default());
-                    let runtime = ActiasRuntime::with_profile(
-                        prepared.clone(),
-                        routing.state.clients.kv.clone(),
-                        routing.state.egress.clone(),
-                        logs,
-                        routing.state.secret_client.clone(),
-                        None,
-                        actias_worker_core::runtime::VmProfile::Workflow {
-                            source: shared.clone(),
-                            secret_pins: Some(Arc::new(pins)),
-                        },
-                    )
-                    .await?;
-                    runtime.set_app_data(shared);
+                    let runtime =
+                        workflow_runtime(&routing.state, prepared.clone(), &file, logs).await?;
+                    runtime.set_app_data(revision_vm(&routing.state, owner.clone(), file.clone()));
                     runtime
                 } else {
                     ActiasRuntime::new(
@@ -356,6 +495,24 @@ impl ObjectRouting {
                 // routing context matches the code it runs.
                 let vm_routing = ObjectRouting::new(&routing.state, prepared);
                 runtime.set_app_data::<ObjectRouter>(vm_routing.as_router());
+                // Topics fan out their log; databases their changes.
+                if [
+                    actias_common::classes::TOPIC_CLASS,
+                    actias_common::classes::DATABASE_CLASS,
+                ]
+                .contains(&identity.class())
+                {
+                    runtime.set_app_data::<SubscriberLookup>(subscriber_lookup(
+                        &routing.state,
+                        identity.scope(),
+                    ));
+                }
+                if identity.class() == actias_common::classes::DATABASE_CLASS {
+                    runtime.set_app_data::<ListenerCheck>(listener_check(
+                        &routing.state,
+                        identity.scope(),
+                    ));
+                }
 
                 let mut storage = actias_worker_core::storage::SqliteStorage::open(&file)
                     .map_err(mlua::Error::RuntimeError)?;
@@ -387,6 +544,7 @@ impl ObjectRouting {
                 });
 
                 let alarm_sync = alarm_mirror(&routing.state, &object_id, &identity.to_string());
+                let on_purge = purge_hook(&routing.state, &identity, &file);
 
                 Ok((
                     runtime,
@@ -397,6 +555,7 @@ impl ObjectRouting {
                         after_write: Some(after_write),
                         alarm_sync: Some(alarm_sync),
                         queue: routing.state.queue_policy.clone(),
+                        on_purge: Some(on_purge),
                     },
                 ))
             })
@@ -422,6 +581,130 @@ impl ObjectRouting {
         fresh_replica_file(&self.state, object_id).await
     }
 
+    /// A user-class read served from a snapshot replica on this node, with
+    /// the staleness it was served at. [`None`] sends the call to the
+    /// holder instead: the method is not marked `readonly`, nothing was
+    /// ever shipped, or the object is resident here, where its own
+    /// mailbox is both fresher and cheaper than a second vm.
+    ///
+    /// The replica runs the owner's current revision on a throwaway vm
+    /// over a read-only connection; no lease is involved, so any node
+    /// answers.
+    async fn replica_call(
+        self: &Arc<Self>,
+        key: &ObjectKey,
+        target: &ObjectTarget,
+        consistency: &actias_worker_core::extensions::objects::ReadConsistency,
+    ) -> Result<Option<(serde_json::Value, std::time::Duration)>, String> {
+        // Platform classes have their own read paths (the database
+        // bypass, the stats reads); only user classes mark methods.
+        if target.class.starts_with("__") || self.state.objects.is_resident(&key.to_string()).await
+        {
+            return Ok(None);
+        }
+
+        let owner = owner_prepared(&self.state, key).await?;
+        let max_age = consistency
+            .max_staleness_ms
+            .map(|ms| std::time::Duration::from_millis(ms as u64))
+            .unwrap_or(self.state.replica_ttl);
+        let key_string = key.to_string();
+
+        // The object's cached vm answers whether the method is readonly
+        // before anything is fetched; one busy with another read leaves
+        // this call a throwaway vm rather than a wait.
+        let vm_key = format!("{key_string}@{}", owner.revision_id);
+        let cached = match self.state.caches.replica_vms.get(&vm_key).await {
+            Some(cached) => cached,
+            None => {
+                let built = Arc::new(tokio::sync::Mutex::new(self.replica_vm(&owner).await?));
+                self.state
+                    .caches
+                    .replica_vms
+                    .insert(vm_key.clone(), built.clone())
+                    .await;
+                built
+            }
+        };
+        let guard = cached.try_lock().ok();
+        let throwaway;
+        let runtime: &ActiasRuntime = match &guard {
+            Some(guard) => guard,
+            None => {
+                throwaway = self.replica_vm(&owner).await?;
+                &throwaway
+            }
+        };
+        if !actias_worker_core::extensions::objects::is_read_only_method(
+            runtime,
+            &target.class,
+            &target.method,
+        ) {
+            return Ok(None);
+        }
+
+        let Some((replica, staleness)) = until_deadline(
+            target.deadline,
+            &key_string,
+            replica_within(&self.state, &key.object_id(), max_age),
+        )
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        // The replica's outbound calls route like the holder's would.
+        let vm_routing = ObjectRouting::new(&self.state, owner);
+        runtime.set_app_data::<ObjectRouter>(vm_routing.as_router());
+
+        let chain = actias_worker_core::objects::extend_call_chain(&target.chain, &key_string)?;
+        let storage = actias_worker_core::storage::SqliteStorage::open_read_only(&replica)?;
+        let dispatched = until_deadline(target.deadline, &key_string, async {
+            actias_worker_core::objects::dispatch_read_only(
+                runtime,
+                storage,
+                serde_json::json!({
+                    "class": target.class,
+                    "name": target.name,
+                    "method": target.method,
+                    "args": target.arguments,
+                    "chain": chain,
+                }),
+                Some(OBJECT_CALL_BUDGET_SECS),
+            )
+            .await
+            .map_err(|e| e.to_string())
+        })
+        .await;
+        if guard.is_some() && matches!(&dispatched, Err(error) if error.starts_with(CALL_TIMED_OUT))
+        {
+            // A call cut off mid-run may leave the vm anywhere; the next
+            // read builds a fresh one.
+            self.state.caches.replica_vms.invalidate(&vm_key).await;
+        }
+        let value = dispatched?;
+
+        self.state
+            .metrics
+            .replica_reads
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        Ok(Some((value, staleness)))
+    }
+
+    /// A vm running the owner's code, for replica reads.
+    async fn replica_vm(&self, owner: &Arc<PreparedRevision>) -> Result<ActiasRuntime, String> {
+        ActiasRuntime::new(
+            owner.clone(),
+            self.state.clients.kv.clone(),
+            self.state.egress.clone(),
+            None,
+            self.state.secret_client.clone(),
+            None,
+        )
+        .await
+        .map_err(|e| e.to_string())
+    }
+
     /// One hop to the lease holder's data plane; its answer is the answer.
     async fn forward(
         &self,
@@ -441,6 +724,19 @@ impl ObjectRouting {
             .map_err(|e| format!("The object's home could not be resolved: {e}"))?
             .into_inner();
 
+        // What is left of the deadline travels as a budget; one already
+        // spent is not worth the hop.
+        let budget = match target.deadline {
+            Some(deadline) => {
+                let left = deadline.saturating_duration_since(std::time::Instant::now());
+                if left.is_zero() {
+                    return Err(timed_out(&key.to_string()));
+                }
+                Some(left)
+            }
+            None => None,
+        };
+
         let mut client = crate::data_plane::peer_client(&self.state, &node.address).await?;
         let call = actias_worker_core::proto::worker_data::ObjectCall {
             scope_id: key.scope().to_owned(),
@@ -461,12 +757,24 @@ impl ObjectRouting {
             }),
             // This hop is the one a first hop is allowed; spent now.
             first_hop: false,
+            budget_ms: budget.map_or(0, |left| left.as_millis().max(1) as u64),
         };
 
+        let mut request = crate::data_plane::authed(&self.state.internal_token, call);
+        if let Some(left) = budget {
+            // The transport gives up at the deadline too, so a holder that
+            // never answers cannot outlast the caller's budget.
+            request.set_timeout(left);
+        }
         let result = client
-            .dispatch(crate::data_plane::authed(&self.state.internal_token, call))
+            .dispatch(request)
             .await
-            .map_err(|e| format!("The object's home did not answer: {}", e.message()))?
+            .map_err(|e| match e.code() {
+                tonic::Code::DeadlineExceeded | tonic::Code::Cancelled if budget.is_some() => {
+                    timed_out(&key.to_string())
+                }
+                _ => format!("The object's home did not answer: {}", e.message()),
+            })?
             .into_inner();
 
         if !result.error.is_empty() {
@@ -520,6 +828,26 @@ impl ObjectRouting {
             }
         }
 
+        // A caller that accepts bounded staleness hears how much it got;
+        // zero whenever the answer came from the holder after all.
+        if let Some(consistency) = target.consistency.clone() {
+            let (value, staleness) = match self.replica_call(&key, &target, &consistency).await? {
+                Some(served) => served,
+                None => {
+                    let strong = ObjectTarget {
+                        consistency: None,
+                        ..target
+                    };
+                    let value = Box::pin(self.route_inner(strong, allow_forward)).await?;
+                    (value, std::time::Duration::ZERO)
+                }
+            };
+            return Ok(serde_json::json!({
+                "value": value,
+                "staleness_ms": staleness.as_millis() as i64,
+            }));
+        }
+
         // A forwarded call arrives with its chain already extended through
         // this target; extending again would refuse it as its own cycle.
         let chain = if target.chain.last().map(String::as_str) == Some(key_string.as_str()) {
@@ -531,7 +859,15 @@ impl ObjectRouting {
             Ok(handle) => handle,
             // The incumbent lives: the call belongs on its node, one hop.
             Err(ResolveError::Elsewhere(holder)) if allow_forward => {
-                return self.forward(&holder, &key, &target, chain).await;
+                return match self.forward(&holder, &key, &target, chain).await {
+                    // The holder is draining and has let go (or is about
+                    // to): the call is ours to claim, after a breath.
+                    Err(error) if error.contains(NODE_DRAINING) => {
+                        tokio::time::sleep(DRAIN_RETRY_PAUSE).await;
+                        Box::pin(self.route_inner(target, false)).await
+                    }
+                    answer => answer,
+                };
             }
             Err(ResolveError::Elsewhere(holder)) => {
                 return Err(format!(
@@ -542,7 +878,7 @@ impl ObjectRouting {
         };
 
         handle
-            .call(
+            .call_until(
                 "__dispatch",
                 serde_json::json!({
                     "class": target.class,
@@ -555,10 +891,150 @@ impl ObjectRouting {
                         "revision": caller.revision,
                     })),
                 }),
+                target.deadline,
             )
             .await
-            .map_err(|e| e.to_string())
+            .map_err(|error| match error {
+                ObjectError::TimedOut => timed_out(&key_string),
+                error => error.to_string(),
+            })
+    }
+}
+
+/// The one timeout text callers see, wherever along the chain it lapsed.
+fn timed_out(key: &str) -> String {
+    format!("{CALL_TIMED_OUT}: '{key}' did not answer in time.")
+}
+
+/// Runs `work` until the caller's deadline, when it set one; a lapse
+/// fails the way any object call past its budget does.
+async fn until_deadline<T>(
+    deadline: Option<std::time::Instant>,
+    key: &str,
+    work: impl Future<Output = Result<T, String>>,
+) -> Result<T, String> {
+    match deadline {
+        Some(deadline) => tokio::time::timeout_at(tokio::time::Instant::from_std(deadline), work)
+            .await
+            .map_err(|_| timed_out(key))?,
+        None => work.await,
+    }
+}
+
+/// Deletes one purged object everywhere it lives, archive first, then
+/// the placement store's directory row, alarm and lease, and only then
+/// the shipped snapshot and the local file. While the directory still
+/// names the object its file and snapshot stay, so a purge that fails
+/// before the directory forgets it keeps the object whole and re-arms
+/// its alarm to try again. A failure after that is logged and leaves
+/// remains that nothing names any more.
+fn purge_hook(
+    state: &AppState,
+    key: &ObjectKey,
+    file: &std::path::Path,
+) -> actias_worker_core::objects::OnPurge {
+    let store = state.object_store.clone();
+    let registry = state.registry.clone();
+    let node_identity = state.node_identity.clone();
+    let mirror = alarm_mirror(state, &key.object_id(), &key.to_string());
+    let key = key.clone();
+    let file = file.to_path_buf();
+
+    Arc::new(move |request| {
+        let store = store.clone();
+        let mut registry = registry.clone();
+        let node_id = node_identity
+            .read()
+            .expect("no poisoned lock")
+            .clone()
+            .unwrap_or_default();
+        let mirror = mirror.clone();
+        let key = key.clone();
+        let file = file.clone();
+
+        Box::pin(async move {
+            let object_id = key.object_id();
+            if let Some(archive) = &request.archive {
+                let path = format!("{}/{}/{}", key.scope(), key.class(), key.name());
+                if let Err(error) = store.archive(&path, archive).await {
+                    actias_common::tracing::warn!(
+                        %error, object = %key, "archive failed; the object is kept"
+                    );
+                    rearm_purge(&file, &key, &mirror, request.due_ms).await;
+                    return;
+                }
+            }
+
+            let forgotten = registry
+                .forget_instance(
+                    actias_worker_core::proto::node_registry::ForgetInstanceRequest {
+                        object_id: object_id.clone(),
+                        node_id,
+                        scope_id: key.scope().to_owned(),
+                        class: key.class().to_owned(),
+                        name: key.name().to_owned(),
+                    },
+                )
+                .await;
+            if let Err(status) = forgotten {
+                actias_common::tracing::warn!(
+                    error = %status, object = %key, "directory row was not forgotten; the object is kept"
+                );
+                rearm_purge(&file, &key, &mirror, request.due_ms).await;
+                return;
+            }
+
+            if let Err(error) = store.forget(&object_id).await {
+                actias_common::tracing::warn!(%error, object = %key, "snapshot was not forgotten");
+            }
+            // SQLite's sidecars go with the file.
+            for suffix in ["", "-wal", "-shm"] {
+                let mut path = file.clone().into_os_string();
+                path.push(suffix);
+                match tokio::fs::remove_file(&path).await {
+                    Ok(()) => {}
+                    Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+                    Err(error) => actias_common::tracing::warn!(
+                        %error, object = %key, "object file was not removed"
+                    ),
+                }
+            }
+            actias_common::tracing::info!(object = %key, "object purged");
+        })
+    })
+}
+
+/// Puts a purge that could not finish back on the object's alarm, in its
+/// file and through the registry mirror alike, so the object wakes, finds
+/// its retention lapsed and purges again. The wait is how long the purge
+/// is overdue, within bounds, so it roughly doubles with each failure.
+async fn rearm_purge(
+    file: &std::path::Path,
+    key: &ObjectKey,
+    mirror: &actias_worker_core::objects::AlarmSync,
+    due_ms: i64,
+) {
+    let now = actias_worker_core::extensions::objects::unix_now_ms();
+    let retry_at = now + (now - due_ms).clamp(PURGE_RETRY_MIN_MS, PURGE_RETRY_MAX_MS);
+
+    let file = file.to_path_buf();
+    let (class, name, own_key) = (
+        key.class().to_owned(),
+        key.name().to_owned(),
+        key.to_string(),
+    );
+    let saved = tokio::task::spawn_blocking(move || {
+        actias_worker_core::storage::SqliteStorage::open(&file)?
+            .save_alarm(retry_at, &class, &name, &own_key)
+    })
+    .await
+    .map_err(|e| e.to_string())
+    .and_then(|saved| saved);
+    if let Err(error) = saved {
+        actias_common::tracing::warn!(%error, object = %key, "purge retry was not armed");
+        return;
     }
+    mirror(Some(retry_at));
 }
 
 /// The registry mirror for one object's alarm: `Some(due_ms)` upserts the
@@ -629,25 +1105,48 @@ pub(crate) async fn fresh_replica_file(
     state: &AppState,
     object_id: &str,
 ) -> Result<Option<std::path::PathBuf>, String> {
+    Ok(replica_within(state, object_id, state.replica_ttl)
+        .await?
+        .map(|(replica, _)| replica))
+}
+
+/// The replica file for one object no older than `max_age`, with its age.
+///
+/// The age is the staleness a read from it is served at: a call that
+/// wrote ships its snapshot before its caller hears the result, so at
+/// restore time the replica holds every acknowledged write, and it can
+/// only have missed what was acknowledged since. The restore lands beside
+/// the file and renames over it, so a read already holding the old copy
+/// finishes on it undisturbed.
+pub(crate) async fn replica_within(
+    state: &AppState,
+    object_id: &str,
+    max_age: std::time::Duration,
+) -> Result<Option<(std::path::PathBuf, std::time::Duration)>, String> {
     let dir = state.object_data_dir.join("replicas");
     let replica = dir.join(format!("{object_id}.db"));
 
-    let fresh = std::fs::metadata(&replica)
+    let age = std::fs::metadata(&replica)
         .and_then(|meta| meta.modified())
         .ok()
         .and_then(|modified| modified.elapsed().ok())
-        .is_some_and(|age| age < state.replica_ttl);
+        .filter(|age| *age < max_age);
+    if let Some(age) = age {
+        return Ok(Some((replica, age)));
+    }
 
-    if !fresh {
-        tokio::fs::create_dir_all(&dir)
-            .await
-            .map_err(|e| e.to_string())?;
-        if !state.object_store.restore(object_id, &replica).await? {
-            return Ok(None);
-        }
+    tokio::fs::create_dir_all(&dir)
+        .await
+        .map_err(|e| e.to_string())?;
+    let incoming = dir.join(format!("{object_id}.db.incoming"));
+    if !state.object_store.restore(object_id, &incoming).await? {
+        return Ok(None);
     }
+    tokio::fs::rename(&incoming, &replica)
+        .await
+        .map_err(|e| e.to_string())?;
 
-    Ok(Some(replica))
+    Ok(Some((replica, std::time::Duration::ZERO)))
 }
 
 /// One bypassed read: a fresh read-only connection, the query, done.
@@ -62,6 +62,17 @@ pub struct WorkerCaches {
     /// revision. Mutable twice over (the owner can change on publish, the
     /// owner republishes), so it expires on the pointer ttl.
     pub(crate) owners: moka::future::Cache<String, Arc<PreparedRevision>>,
+    /// `<project id>/<event>` to the scripts listening for it, for the
+    /// per-call check whether a database's writes are watched; a publish
+    /// adds or drops listeners, so it expires on the pointer ttl.
+    pub(crate) listeners: moka::future::Cache<String, Vec<String>>,
+    /// Replica vms by `<object key>@<revision id>`: one per object, since
+    /// a vm's globals are that object's, and one call at a time behind
+    /// the lock. Idle ones expire on the pointer ttl.
+    pub(crate) replica_vms: moka::future::Cache<
+        String,
+        Arc<tokio::sync::Mutex<actias_worker_core::runtime::ActiasRuntime>>,
+    >,
 }
 
 impl WorkerCaches {
@@ -79,6 +90,14 @@ impl WorkerCaches {
                 .max_capacity(10_000)
                 .time_to_live(pointer_ttl)
                 .build(),
+            listeners: moka::future::Cache::builder()
+                .max_capacity(10_000)
+                .time_to_live(pointer_ttl)
+                .build(),
+            replica_vms: moka::future::Cache::builder()
+                .max_capacity(1_000)
+                .time_to_idle(pointer_ttl)
+                .build(),
             revisions: moka::future::Cache::builder()
                 .max_capacity(revision_cache_bytes)
                 .weigher(|_, prepared: &Arc<PreparedRevision>| {
@@ -537,6 +556,8 @@ async fn handle(State(state): State<AppState>, request: axum::extract::Request)
 
 /// Resolves the script, runs it, and shapes its response.
 async fn run_script(state: AppState, request: axum::extract::Request) -> anyhow::Result<Response> {
+    let deadline = std::time::Instant::now() + state.request_timeout;
+
     // DefaultBodyLimit only takes effect through extractors, so the body is
     // wrapped explicitly; without this the cap silently would not apply.
     use axum::RequestExt;
@@ -840,6 +861,9 @@ async fn run_script(state: AppState, request: axum::extract::Request) -> anyhow:
     )
     .await?;
     lua.set_app_data::<ObjectRouter>(router);
+    // Object calls made by the handler share the request's deadline, so a
+    // stuck object fails its caller's call rather than the whole request.
+    lua.set_app_data(extensions::objects::CallDeadline(Some(deadline)));
 
     let listener = lua.listener(ActiasRuntime::FETCH_EVENT)?;
 
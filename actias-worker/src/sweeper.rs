@@ -57,16 +57,20 @@ pub fn scan_alarms(data_dir: &Path) -> Vec<(String, i64)> {
     found
 }
 
-/// Re-mirrors every locally persisted alarm into the registry; best
-/// effort, once. An alarm this misses still fires through its own file
-/// the next time the object is resident here.
-async fn mirror_local_alarms(state: &AppState) {
+/// Re-mirrors the locally persisted alarms `keep` admits into the
+/// registry; best effort, once at boot and once more during a shutdown
+/// drain. An alarm this misses still fires through its own file the next
+/// time the object is resident here.
+pub(crate) async fn mirror_local_alarms(state: &AppState, keep: impl Fn(&str) -> bool) {
     let data_dir = state.object_data_dir.clone();
     let Ok(found) = tokio::task::spawn_blocking(move || scan_alarms(&data_dir)).await else {
         return;
     };
 
     for (own_key, due_ms) in found {
+        if !keep(&own_key) {
+            continue;
+        }
         let Some(key) = ObjectKey::parse(&own_key) else {
             continue;
         };
@@ -80,14 +84,14 @@ async fn mirror_local_alarms(state: &AppState) {
             })
             .await
         {
-            warn!(error = %error, own_key, "boot alarm mirror failed");
+            warn!(error = %error, own_key, "local alarm mirror failed");
         }
     }
 }
 
 /// Runs forever; spawn it and forget it.
 pub async fn run(state: AppState, every: Duration) {
-    mirror_local_alarms(&state).await;
+    mirror_local_alarms(&state, |_| true).await;
 
     loop {
         tokio::time::sleep(every).await;